@@ -1,6 +1,13 @@
-use crate::db::connection::get_connection;
-use crate::models::{Account, CreateAccountInput, UpdateAccountInput};
-use crate::utils::error::Result;
+use crate::commands::duplicates::refresh_fingerprint;
+use crate::db::connection::{get_connection, with_transaction};
+use crate::db::currency::resolve_currency;
+use crate::db::ledger::{check_balances, find_orphaned_references, set_balance};
+use crate::models::{
+    Account, AccountBalanceCheck, CreateAccountInput, LedgerReport, UpdateAccountInput,
+};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use rusqlite::Connection;
 
 fn row_to_account(row: &rusqlite::Row) -> rusqlite::Result<Account> {
     Ok(Account {
@@ -32,6 +39,7 @@ pub fn create_account(input: CreateAccountInput) -> Result<Account> {
     log::info!("create_account called: name={}, type={}, bank_id={:?}, credit_limit={:?}", 
         input.name, input.account_type, input.bank_id, input.credit_limit);
     let conn = get_connection()?;
+    let currency = resolve_currency(&conn, input.currency, None)?;
 
     conn.execute(
         "INSERT INTO accounts (name, account_type, bank_id, owner_user_id, account_number,
@@ -43,7 +51,7 @@ pub fn create_account(input: CreateAccountInput) -> Result<Account> {
             input.bank_id,
             input.owner_user_id,
             input.account_number,
-            input.currency,
+            currency,
             input.initial_balance,
             input.initial_balance, // current_balance = initial
             input.color,
@@ -102,38 +110,42 @@ pub fn get_accounts(active_only: Option<bool>) -> Result<Vec<Account>> {
 #[tauri::command]
 pub fn update_account(id: i64, input: UpdateAccountInput) -> Result<Account> {
     log::info!("update_account called: id={}, balance={:?}, credit_limit={:?}", id, input.current_balance, input.credit_limit);
-    let conn = get_connection()?;
 
-    conn.execute(
-        "UPDATE accounts SET name = ?1, account_type = ?2, bank_id = ?3,
-         owner_user_id = ?4, account_number = ?5, currency = ?6,
-         color = ?7, icon = ?8, active = ?9, current_balance = ?10, initial_balance = ?10, credit_limit = ?11
-         WHERE id = ?12",
-        rusqlite::params![
-            input.name,
-            input.account_type,
-            input.bank_id,
-            input.owner_user_id,
-            input.account_number,
-            input.currency,
-            input.color,
-            input.icon,
-            input.active as i32,
-            input.current_balance.unwrap_or(0.0),
-            input.credit_limit,
-            id
-        ],
-    )?;
+    with_transaction(|conn| {
+        conn.execute(
+            "UPDATE accounts SET name = ?1, account_type = ?2, bank_id = ?3,
+             owner_user_id = ?4, account_number = ?5, currency = ?6,
+             color = ?7, icon = ?8, active = ?9, credit_limit = ?10
+             WHERE id = ?11",
+            rusqlite::params![
+                input.name,
+                input.account_type,
+                input.bank_id,
+                input.owner_user_id,
+                input.account_number,
+                input.currency,
+                input.color,
+                input.icon,
+                input.active as i32,
+                input.credit_limit,
+                id
+            ],
+        )?;
 
-    // Return inline to avoid deadlock
-    let account = conn.query_row(
-        &format!("SELECT {} FROM accounts WHERE id = ?1", ACCOUNT_COLUMNS),
-        [id],
-        row_to_account,
-    )?;
-    
-    log::info!("update_account done: balance={}", account.current_balance);
-    Ok(account)
+        if let Some(balance) = input.current_balance {
+            set_balance(conn, id, balance)?;
+        }
+
+        // Return inline to avoid deadlock
+        let account = conn.query_row(
+            &format!("SELECT {} FROM accounts WHERE id = ?1", ACCOUNT_COLUMNS),
+            [id],
+            row_to_account,
+        )?;
+
+        log::info!("update_account done: balance={}", account.current_balance);
+        Ok(account)
+    })
 }
 
 #[tauri::command]
@@ -147,10 +159,10 @@ pub fn delete_account(id: i64) -> Result<()> {
 }
 
 #[tauri::command]
-pub fn get_account_balance(id: i64) -> Result<f64> {
+pub fn get_account_balance(id: i64) -> Result<Money> {
     let conn = get_connection()?;
 
-    let balance: f64 = conn.query_row(
+    let balance: Money = conn.query_row(
         "SELECT current_balance FROM accounts WHERE id = ?1",
         [id],
         |row| row.get(0),
@@ -159,35 +171,217 @@ pub fn get_account_balance(id: i64) -> Result<f64> {
     Ok(balance)
 }
 
-pub fn update_account_balance(account_id: i64, amount_change: f64) -> Result<()> {
-    let conn = get_connection()?;
+#[tauri::command]
+pub fn set_account_balance(id: i64, balance: Money) -> Result<Account> {
+    log::info!("set_account_balance called: id={}, balance={}", id, balance);
 
-    conn.execute(
-        "UPDATE accounts SET current_balance = current_balance + ?1 WHERE id = ?2",
-        rusqlite::params![amount_change, account_id],
-    )?;
+    with_transaction(|conn| {
+        // Počáteční zůstatek se dopočítá, aby transakce dál odpovídaly zůstatku
+        set_balance(conn, id, balance)?;
 
-    Ok(())
+        // Return inline to avoid deadlock
+        let account = conn.query_row(
+            &format!("SELECT {} FROM accounts WHERE id = ?1", ACCOUNT_COLUMNS),
+            [id],
+            row_to_account,
+        )?;
+
+        log::info!("set_account_balance: done");
+        Ok(account)
+    })
 }
 
+// ============================================
+// LEDGER INTEGRITY
+// ============================================
+
+/// Zkontroluje, že zůstatky účtů odpovídají transakcím a že žádný záznam
+/// neodkazuje na neexistující řádek.
 #[tauri::command]
-pub fn set_account_balance(id: i64, balance: f64) -> Result<Account> {
-    log::info!("set_account_balance called: id={}, balance={}", id, balance);
+pub fn verify_ledger() -> Result<LedgerReport> {
     let conn = get_connection()?;
+    ledger_report(&conn)
+}
 
-    conn.execute(
-        "UPDATE accounts SET current_balance = ?1, initial_balance = ?1 WHERE id = ?2",
-        rusqlite::params![balance, id],
-    )?;
+fn ledger_report(conn: &Connection) -> Result<LedgerReport> {
+    let checks = check_balances(conn)?;
+    let checked_accounts = checks.len();
+    let discrepancies: Vec<AccountBalanceCheck> = checks
+        .into_iter()
+        .filter(|c| !c.difference.is_zero())
+        .collect();
+    let orphaned_references = find_orphaned_references(conn)?;
 
-    // Return inline to avoid deadlock
-    let account = conn.query_row(
-        &format!("SELECT {} FROM accounts WHERE id = ?1", ACCOUNT_COLUMNS),
-        [id],
-        row_to_account,
-    )?;
+    log::info!(
+        "verify_ledger: {} discrepancies, {} orphaned references",
+        discrepancies.len(),
+        orphaned_references.len()
+    );
 
-    log::info!("set_account_balance: done");
-    Ok(account)
+    Ok(LedgerReport {
+        is_consistent: discrepancies.is_empty() && orphaned_references.is_empty(),
+        checked_accounts,
+        discrepancies,
+        orphaned_references,
+    })
+}
+
+/// Opraví nesouhlasící zůstatky účtů.
+///
+/// - `recompute`: uložený zůstatek se přepíše zůstatkem spočteným z transakcí
+/// - `adjustment`: uložený zůstatek zůstane a rozdíl se zaúčtuje jako transakce
+///   typu `adjustment` ("Vyrovnání zůstatku")
+#[tauri::command]
+pub fn rebuild_balances(mode: String, account_ids: Option<Vec<i64>>) -> Result<Vec<AccountBalanceCheck>> {
+    log::info!("rebuild_balances called: mode={}, accounts={:?}", mode, account_ids);
+
+    if mode != "recompute" && mode != "adjustment" {
+        return Err(AppError::InvalidInput(format!(
+            "Neznámý způsob opravy zůstatků: {}",
+            mode
+        )));
+    }
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    with_transaction(|conn| fix_balances(conn, &mode, account_ids.as_deref(), &today))
+}
+
+fn fix_balances(
+    conn: &Connection,
+    mode: &str,
+    account_ids: Option<&[i64]>,
+    today: &str,
+) -> Result<Vec<AccountBalanceCheck>> {
+    let fixed: Vec<AccountBalanceCheck> = check_balances(conn)?
+        .into_iter()
+        .filter(|c| !c.difference.is_zero())
+        .filter(|c| match account_ids {
+            Some(ids) => ids.contains(&c.account_id),
+            None => true,
+        })
+        .collect();
+
+    for check in &fixed {
+        if mode == "recompute" {
+            conn.execute(
+                "UPDATE accounts SET current_balance = ?1 WHERE id = ?2",
+                rusqlite::params![check.computed_balance, check.account_id],
+            )?;
+        } else {
+            // Zůstatek už rozdíl obsahuje, transakce jej jen doloží
+            conn.execute(
+                "INSERT INTO transactions (date, amount, currency, transaction_type,
+                 to_account_id, description, status)
+                 VALUES (?1, ?2, ?3, 'adjustment', ?4, 'Vyrovnání zůstatku', 'completed')",
+                rusqlite::params![today, check.difference, check.currency, check.account_id],
+            )?;
+            refresh_fingerprint(conn, conn.last_insert_rowid())?;
+        }
+    }
+
+    log::info!("rebuild_balances: fixed {} accounts", fixed.len());
+    Ok(fixed)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn czk(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    /// Účet 1 má uložený zůstatek o 20 Kč vyšší, účet 2 o 5 Kč nižší, účet 3 sedí.
+    fn ledger_with_discrepancies() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO accounts (id, name, account_type, initial_balance, current_balance)
+             VALUES (1, 'A', 'checking', 100000, 97000), (2, 'B', 'checking', 50000, 54500),
+                    (3, 'C', 'savings', 10000, 10000);
+             INSERT INTO transactions (date, amount, transaction_type, status, from_account_id)
+             VALUES ('2024-03-01', 5000, 'expense', 'completed', 1);
+             INSERT INTO transactions (date, amount, transaction_type, status, to_account_id)
+             VALUES ('2024-03-02', 5000, 'income', 'completed', 2),
+                    ('2024-03-03', 9900, 'income', 'planned', 3);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn report_lists_only_discrepancies() {
+        let conn = ledger_with_discrepancies();
+        let report = ledger_report(&conn).unwrap();
+
+        assert!(!report.is_consistent);
+        assert_eq!(report.checked_accounts, 3);
+        assert!(report.orphaned_references.is_empty());
+        let differences: Vec<(i64, Money)> = report
+            .discrepancies
+            .iter()
+            .map(|c| (c.account_id, c.difference))
+            .collect();
+        assert_eq!(differences, vec![(1, czk("20")), (2, czk("-5"))]);
+    }
+
+    #[test]
+    fn recompute_overwrites_stored_balances() {
+        let conn = ledger_with_discrepancies();
+        let fixed = fix_balances(&conn, "recompute", Some(&[2, 3]), "2024-04-01").unwrap();
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed[0].account_id, 2);
+
+        // Účet 1 nebyl vybrán
+        let report = ledger_report(&conn).unwrap();
+        assert_eq!(report.discrepancies.len(), 1);
+        assert_eq!(report.discrepancies[0].account_id, 1);
+
+        fix_balances(&conn, "recompute", None, "2024-04-01").unwrap();
+        assert!(ledger_report(&conn).unwrap().is_consistent);
+        let balance: Money = conn
+            .query_row(
+                "SELECT current_balance FROM accounts WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(balance, czk("950"));
+    }
+
+    #[test]
+    fn adjustment_books_the_difference() {
+        let conn = ledger_with_discrepancies();
+        fix_balances(&conn, "adjustment", None, "2024-04-01").unwrap();
+
+        assert!(ledger_report(&conn).unwrap().is_consistent);
+        let balances: Vec<Money> = check_balances(&conn)
+            .unwrap()
+            .iter()
+            .map(|c| c.stored_balance)
+            .collect();
+        assert_eq!(balances, vec![czk("970"), czk("545"), czk("100")]);
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT to_account_id, amount, date, fingerprint FROM transactions
+                 WHERE transaction_type = 'adjustment' ORDER BY to_account_id",
+            )
+            .unwrap();
+        let adjustments: Vec<(i64, Money, String, Option<String>)> = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(adjustments.len(), 2);
+        assert_eq!((adjustments[0].0, adjustments[0].1), (1, czk("20")));
+        assert_eq!((adjustments[1].0, adjustments[1].1), (2, czk("-5")));
+        for (_, _, date, fingerprint) in &adjustments {
+            assert_eq!(date, "2024-04-01");
+            assert!(fingerprint.is_some());
+        }
+    }
+}
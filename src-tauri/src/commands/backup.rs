@@ -1,7 +1,12 @@
-use crate::db::connection::{get_connection, get_db_path};
-use crate::models::{TransactionFilters, FullBackup, BackupData, HouseholdMemberWithIncomes, BankWithAccounts, AccountExtended};
+use crate::commands::transactions::filter_conditions;
+use crate::db::connection::{get_connection, get_db_path, with_transaction};
+use crate::db::migrations::{current_version, ensure_supported, latest_version, SchemaVersion};
+use crate::models::{TransactionFilters, FullBackup, BackupData, HouseholdMemberWithIncomes, BankWithAccounts, AccountExtended, BACKUP_VERSION};
 use crate::models::household::*;
 use crate::utils::error::Result;
+use crate::utils::money::Money;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::fs;
 
 #[tauri::command]
@@ -14,6 +19,14 @@ pub fn export_database(path: String) -> Result<()> {
 #[tauri::command]
 pub fn import_database(path: String) -> Result<()> {
     let db_path = get_db_path()?;
+
+    // Nepřebírat databázi zapsanou novější verzí aplikace
+    let source = rusqlite::Connection::open_with_flags(
+        &path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?;
+    ensure_supported(current_version(&source)?)?;
+    drop(source);
     
     // Backup stávající databáze
     let backup_path = db_path.with_extension("db.backup");
@@ -25,14 +38,24 @@ pub fn import_database(path: String) -> Result<()> {
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_schema_version() -> Result<SchemaVersion> {
+    let conn = get_connection()?;
+
+    Ok(SchemaVersion {
+        current_version: current_version(&conn)?,
+        latest_version: latest_version(),
+    })
+}
+
 /// Export all data as JSON
 #[tauri::command]
 pub fn export_full_backup() -> Result<FullBackup> {
     let conn = get_connection()?;
     
-    // Get household members with incomes
+    // Get household members (persons) with incomes
     let mut members_stmt = conn.prepare(
-        "SELECT id, name, color, avatar, created_at, updated_at FROM household_members"
+        "SELECT id, name, color, avatar, created_at, updated_at FROM users WHERE is_shared_user = 0 AND active = 1"
     )?;
     let members: Vec<HouseholdMember> = members_stmt.query_map([], |row| {
         Ok(HouseholdMember {
@@ -49,8 +72,8 @@ pub fn export_full_backup() -> Result<FullBackup> {
     for member in members {
         let member_id = member.id.unwrap();
         let mut income_stmt = conn.prepare(
-            "SELECT id, member_id, name, amount, frequency, day_of_month, account_id, is_active, created_at, updated_at 
-             FROM member_incomes WHERE member_id = ?1"
+            "SELECT id, user_id, name, amount, frequency, day_of_month, account_id, is_active, created_at, updated_at, schedule
+             FROM member_incomes WHERE user_id = ?1"
         )?;
         let incomes: Vec<MemberIncome> = income_stmt.query_map([member_id], |row| {
             Ok(MemberIncome {
@@ -64,6 +87,7 @@ pub fn export_full_backup() -> Result<FullBackup> {
                 is_active: row.get::<_, i32>(7)? == 1,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                schedule: row.get(10)?,
             })
         })?.collect::<std::result::Result<Vec<_>, _>>()?;
         
@@ -122,7 +146,8 @@ pub fn export_full_backup() -> Result<FullBackup> {
     
     // Get scheduled transfers
     let mut transfers_stmt = conn.prepare(
-        "SELECT id, name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active, created_at, updated_at 
+        "SELECT id, name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active, created_at, updated_at,
+         variable_symbol, constant_symbol, specific_symbol, counterparty_account, schedule
          FROM scheduled_transfers"
     )?;
     let transfers: Vec<ScheduledTransfer> = transfers_stmt.query_map([], |row| {
@@ -139,12 +164,17 @@ pub fn export_full_backup() -> Result<FullBackup> {
             is_active: row.get::<_, i32>(9)? == 1,
             created_at: row.get(10)?,
             updated_at: row.get(11)?,
+            variable_symbol: row.get(12)?,
+            constant_symbol: row.get(13)?,
+            specific_symbol: row.get(14)?,
+            counterparty_account: row.get(15)?,
+            schedule: row.get(16)?,
         })
     })?.collect::<std::result::Result<Vec<_>, _>>()?;
     
     // Get fixed expenses
     let mut expenses_stmt = conn.prepare(
-        "SELECT id, name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes, created_at, updated_at 
+        "SELECT id, name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes, created_at, updated_at, schedule
          FROM fixed_expenses"
     )?;
     let expenses: Vec<FixedExpense> = expenses_stmt.query_map([], |row| {
@@ -161,6 +191,7 @@ pub fn export_full_backup() -> Result<FullBackup> {
             notes: row.get(9)?,
             created_at: row.get(10)?,
             updated_at: row.get(11)?,
+            schedule: row.get(12)?,
         })
     })?.collect::<std::result::Result<Vec<_>, _>>()?;
     
@@ -184,7 +215,7 @@ pub fn export_full_backup() -> Result<FullBackup> {
     })?.collect::<std::result::Result<Vec<_>, _>>()?;
     
     Ok(FullBackup {
-        version: "1.0.0".to_string(),
+        version: BACKUP_VERSION.to_string(),
         created_at: chrono::Utc::now().to_rfc3339(),
         data: BackupData {
             household_members: members_with_incomes,
@@ -205,114 +236,159 @@ pub fn save_backup_to_file(path: String) -> Result<()> {
     Ok(())
 }
 
+/// Osobu ze zálohy spáruje s existujícím uživatelem podle jména, jinak ji založí.
+fn upsert_person(conn: &Connection, member: &HouseholdMember) -> Result<i64> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM users
+             WHERE is_shared_user = 0 AND lower(trim(name)) = lower(trim(?1))
+             ORDER BY active DESC, id LIMIT 1",
+            [&member.name],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match existing {
+        Some(id) => {
+            conn.execute(
+                "UPDATE users SET color = ?1, avatar = ?2, active = 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+                (&member.color, &member.avatar, id),
+            )?;
+            Ok(id)
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO users (name, color, avatar, role, is_shared_user, active) VALUES (?1, ?2, ?3, 'member', 0, 1)",
+                (&member.name, &member.color, &member.avatar),
+            )?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+fn existing_user(conn: &Connection, id: i64) -> Result<Option<i64>> {
+    let user = conn
+        .query_row("SELECT id FROM users WHERE id = ?1", [id], |row| row.get(0))
+        .optional()?;
+    Ok(user)
+}
+
 /// Load and import backup from JSON file
 #[tauri::command]
 pub fn import_from_backup_file(path: String) -> Result<()> {
     let json = fs::read_to_string(path)?;
     let backup: FullBackup = serde_json::from_str(&json)?;
     
-    let conn = get_connection()?;
+    // Zálohy 1.0.0 mají u členů vlastní ID, ne ID uživatelů - vlastníky účtů nelze přemapovat
+    let remap_owners = backup.version != "1.0.0";
     
-    // Clear existing data
-    conn.execute("DELETE FROM member_incomes", [])?;
-    conn.execute("DELETE FROM household_members", [])?;
-    conn.execute("DELETE FROM scheduled_transfers", [])?;
-    conn.execute("DELETE FROM accounts WHERE bank_id IS NOT NULL", [])?;
-    conn.execute("DELETE FROM banks", [])?;
-    conn.execute("DELETE FROM fixed_expenses", [])?;
-    conn.execute("DELETE FROM budget_categories", [])?;
+    // Celý import běží v jedné SQL transakci - při chybě zůstanou původní data
+    with_transaction(|conn| {
+        // Clear existing data (osoby zůstávají, odkazují na ně transakce)
+        conn.execute("DELETE FROM member_incomes", [])?;
+        conn.execute("DELETE FROM scheduled_transfers", [])?;
+        conn.execute("DELETE FROM accounts WHERE bank_id IS NOT NULL", [])?;
+        conn.execute("DELETE FROM banks", [])?;
+        conn.execute("DELETE FROM fixed_expenses", [])?;
+        conn.execute("DELETE FROM budget_categories", [])?;
     
-    // Import banks and accounts
-    for bank_data in backup.data.banks {
-        conn.execute(
-            "INSERT INTO banks (name, short_name, logo, color, notes, active) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            (&bank_data.bank.name, &None::<String>, &bank_data.bank.logo, &bank_data.bank.color, &bank_data.bank.notes, if bank_data.bank.active { 1 } else { 0 }),
-        )?;
-        let bank_id = conn.last_insert_rowid();
+        // Import household members (persons) with incomes
+        let mut person_ids: HashMap<i64, i64> = HashMap::new();
+        for member_data in backup.data.household_members {
+            let user_id = upsert_person(conn, &member_data.member)?;
+            if let Some(backup_id) = member_data.member.id {
+                person_ids.insert(backup_id, user_id);
+            }
         
-        for acc in bank_data.accounts {
+            for income in member_data.incomes {
+                conn.execute(
+                    "INSERT INTO member_incomes (user_id, name, amount, frequency, day_of_month, account_id, is_active, schedule) 
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    (user_id, &income.name, income.amount, &income.frequency, income.day_of_month, income.account_id, if income.is_active { 1 } else { 0 }, &income.schedule),
+                )?;
+            }
+        }
+    
+        // Import banks and accounts
+        for bank_data in backup.data.banks {
             conn.execute(
-                "INSERT INTO accounts (name, account_type, bank_id, owner_user_id, account_number, currency, initial_balance, current_balance, color, icon, is_premium, premium_min_flow, active) 
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
-                (
-                    &acc.name, &acc.account_type, bank_id, acc.owner_user_id, &acc.account_number,
-                    &acc.currency, acc.initial_balance, acc.current_balance, &acc.color, &acc.icon,
-                    if acc.is_premium { 1 } else { 0 }, acc.premium_min_flow, if acc.active { 1 } else { 0 }
-                ),
+                "INSERT INTO banks (name, short_name, logo, color, notes, active) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                (&bank_data.bank.name, &None::<String>, &bank_data.bank.logo, &bank_data.bank.color, &bank_data.bank.notes, if bank_data.bank.active { 1 } else { 0 }),
             )?;
+            let bank_id = conn.last_insert_rowid();
+        
+            for acc in bank_data.accounts {
+                let owner_user_id = match acc.owner_user_id {
+                    Some(id) => match person_ids.get(&id).filter(|_| remap_owners) {
+                        Some(user_id) => Some(*user_id),
+                        None => existing_user(conn, id)?,
+                    },
+                    None => None,
+                };
+                conn.execute(
+                    "INSERT INTO accounts (name, account_type, bank_id, owner_user_id, account_number, currency, initial_balance, current_balance, color, icon, is_premium, premium_min_flow, active) 
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                    (
+                        &acc.name, &acc.account_type, bank_id, owner_user_id, &acc.account_number,
+                        &acc.currency, acc.initial_balance, acc.current_balance, &acc.color, &acc.icon,
+                        if acc.is_premium { 1 } else { 0 }, acc.premium_min_flow, if acc.active { 1 } else { 0 }
+                    ),
+                )?;
+            }
         }
-    }
     
-    // Import household members with incomes
-    for member_data in backup.data.household_members {
-        conn.execute(
-            "INSERT INTO household_members (name, color, avatar) VALUES (?1, ?2, ?3)",
-            (&member_data.member.name, &member_data.member.color, &member_data.member.avatar),
-        )?;
-        let member_id = conn.last_insert_rowid();
-        
-        for income in member_data.incomes {
+        // Import scheduled transfers
+        for transfer in backup.data.scheduled_transfers {
             conn.execute(
-                "INSERT INTO member_incomes (member_id, name, amount, frequency, day_of_month, account_id, is_active) 
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                (member_id, &income.name, income.amount, &income.frequency, income.day_of_month, income.account_id, if income.is_active { 1 } else { 0 }),
+                "INSERT INTO scheduled_transfers (name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active,
+                 variable_symbol, constant_symbol, specific_symbol, counterparty_account, schedule) 
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                rusqlite::params![&transfer.name, transfer.from_account_id, transfer.to_account_id, transfer.amount, transfer.day_of_month, &transfer.description, &transfer.category, transfer.display_order, if transfer.is_active { 1 } else { 0 },
+                    &transfer.variable_symbol, &transfer.constant_symbol, &transfer.specific_symbol, &transfer.counterparty_account, &transfer.schedule],
             )?;
         }
-    }
     
-    // Import scheduled transfers
-    for transfer in backup.data.scheduled_transfers {
-        conn.execute(
-            "INSERT INTO scheduled_transfers (name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            (&transfer.name, transfer.from_account_id, transfer.to_account_id, transfer.amount, transfer.day_of_month, &transfer.description, &transfer.category, transfer.display_order, if transfer.is_active { 1 } else { 0 }),
-        )?;
-    }
-    
-    // Import fixed expenses
-    for expense in backup.data.fixed_expenses {
-        conn.execute(
-            "INSERT INTO fixed_expenses (name, amount, category, frequency, day_of_month, assigned_to, is_active, notes) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            (&expense.name, expense.amount, &expense.category, &expense.frequency, expense.day_of_month, &expense.assigned_to, if expense.is_active { 1 } else { 0 }, &expense.notes),
-        )?;
-    }
-    
-    // Import budget categories
-    for budget in backup.data.budget_categories {
-        conn.execute(
-            "INSERT INTO budget_categories (name, budget_type, monthly_limit, color, icon, assigned_to) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            (&budget.name, &budget.budget_type, budget.monthly_limit, &budget.color, &budget.icon, &budget.assigned_to),
-        )?;
-    }
+        // Import fixed expenses
+        for expense in backup.data.fixed_expenses {
+            conn.execute(
+                "INSERT INTO fixed_expenses (name, amount, category, frequency, day_of_month, assigned_to, is_active, notes, schedule) 
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                (&expense.name, expense.amount, &expense.category, &expense.frequency, expense.day_of_month, &expense.assigned_to, if expense.is_active { 1 } else { 0 }, &expense.notes, &expense.schedule),
+            )?;
+        }
     
-    Ok(())
+        // Import budget categories
+        for budget in backup.data.budget_categories {
+            conn.execute(
+                "INSERT INTO budget_categories (name, budget_type, monthly_limit, color, icon, assigned_to) 
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                (&budget.name, &budget.budget_type, budget.monthly_limit, &budget.color, &budget.icon, &budget.assigned_to),
+            )?;
+        }
+
+        Ok(())
+    })
 }
 
 #[tauri::command]
 pub fn export_transactions_csv(path: String, filters: Option<TransactionFilters>) -> Result<()> {
     let conn = get_connection()?;
     
-    let query = if filters.is_some() {
-        "SELECT t.id, t.date, t.amount, t.currency, t.transaction_type, t.description, 
-         t.status, c.name as category, a1.name as from_account, a2.name as to_account
-         FROM transactions t
-         LEFT JOIN categories c ON t.category_id = c.id
-         LEFT JOIN accounts a1 ON t.from_account_id = a1.id
-         LEFT JOIN accounts a2 ON t.to_account_id = a2.id
-         ORDER BY t.date DESC"
-    } else {
+    let (conditions, params) = filter_conditions(&filters.unwrap_or_default());
+    let query = format!(
         "SELECT t.id, t.date, t.amount, t.currency, t.transaction_type, t.description, 
          t.status, c.name as category, a1.name as from_account, a2.name as to_account
          FROM transactions t
          LEFT JOIN categories c ON t.category_id = c.id
          LEFT JOIN accounts a1 ON t.from_account_id = a1.id
          LEFT JOIN accounts a2 ON t.to_account_id = a2.id
-         ORDER BY t.date DESC"
-    };
+         WHERE 1=1{}
+         ORDER BY t.date DESC",
+        conditions
+    );
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
     
-    let mut stmt = conn.prepare(query)?;
+    let mut stmt = conn.prepare(&query)?;
     let mut wtr = csv::Writer::from_path(path)?;
     
     wtr.write_record([
@@ -320,11 +396,11 @@ pub fn export_transactions_csv(path: String, filters: Option<TransactionFilters>
         "Z účtu", "Na účet"
     ])?;
     
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map(param_refs.as_slice(), |row| {
         Ok((
             row.get::<_, i64>(0)?,
             row.get::<_, String>(1)?,
-            row.get::<_, f64>(2)?,
+            row.get::<_, Money>(2)?,
             row.get::<_, String>(3)?,
             row.get::<_, String>(4)?,
             row.get::<_, Option<String>>(5)?,
@@ -1,4 +1,4 @@
-use crate::db::connection::get_connection;
+use crate::db::connection::{get_connection, with_transaction};
 use crate::models::{Bank, CreateBankInput, UpdateBankInput};
 use crate::utils::error::Result;
 
@@ -125,13 +125,13 @@ pub fn update_bank(id: i64, input: UpdateBankInput) -> Result<Bank> {
 
 #[tauri::command]
 pub fn delete_bank(id: i64) -> Result<()> {
-    let conn = get_connection()?;
-
-    // Hard delete (kvůli UNIQUE constraint na name)
-    // Nejprve smazat účty
-    conn.execute("DELETE FROM accounts WHERE bank_id = ?1", [id])?;
-    conn.execute("DELETE FROM banks WHERE id = ?1", [id])?;
+    with_transaction(|conn| {
+        // Hard delete (kvůli UNIQUE constraint na name)
+        // Nejprve smazat účty
+        conn.execute("DELETE FROM accounts WHERE bank_id = ?1", [id])?;
+        conn.execute("DELETE FROM banks WHERE id = ?1", [id])?;
 
-    Ok(())
+        Ok(())
+    })
 }
 
@@ -0,0 +1,377 @@
+use crate::commands::shared_expenses::reallocate_shares;
+use crate::commands::splits::{ensure_splits_match, splits_total};
+use crate::commands::transactions::{ensure_unlocked, query_transaction};
+use crate::db::connection::{get_connection, with_transaction};
+use crate::db::ledger::{reverse_entry, LedgerEntry};
+use crate::models::{DuplicateCandidate, Transaction};
+use crate::utils::error::{AppError, Result};
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Otisk zdroje: banka účtu | den | typ a částka | protistrana | reference.
+const FINGERPRINT_SQL: &str = "LOWER(
+    COALESCE((SELECT CAST(bank_id AS TEXT) FROM accounts
+              WHERE accounts.id = COALESCE(transactions.from_account_id, transactions.to_account_id)), '')
+    || '|' || SUBSTR(transactions.date, 1, 10)
+    || '|' || transactions.transaction_type || ':' || transactions.amount
+    || '|' || TRIM(COALESCE(transactions.counterparty_account, transactions.description, ''))
+    || '|' || COALESCE(transactions.external_id, transactions.variable_symbol, ''))";
+
+pub(crate) fn refresh_fingerprint(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute(
+        &format!("UPDATE transactions SET fingerprint = {} WHERE id = ?1", FINGERPRINT_SQL),
+        [id],
+    )?;
+    Ok(())
+}
+
+fn normalized(value: Option<&str>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty())
+}
+
+/// Ohodnotí dvojici se stejným účtem, typem a částkou.
+fn score_pair(a: &Transaction, b: &Transaction, days_apart: i64) -> (u32, Vec<String>) {
+    if a.fingerprint.is_some() && a.fingerprint == b.fingerprint {
+        return (100, vec!["Shodný otisk zdroje".to_string()]);
+    }
+
+    let mut score = 50;
+    let mut reasons = vec!["Stejný účet a částka".to_string()];
+
+    score += match days_apart {
+        0 => 25,
+        1 => 15,
+        2 => 10,
+        _ => 5,
+    };
+    reasons.push(if days_apart == 0 {
+        "Stejné datum".to_string()
+    } else {
+        format!("Datum se liší o {} d.", days_apart)
+    });
+
+    let party = |t: &Transaction| {
+        normalized(t.counterparty_account.as_deref()).or_else(|| normalized(t.description.as_deref()))
+    };
+    if party(a).is_some() && party(a) == party(b) {
+        score += 15;
+        reasons.push("Stejná protistrana".to_string());
+    }
+
+    let reference = |t: &Transaction| {
+        normalized(t.external_id.as_deref()).or_else(|| normalized(t.variable_symbol.as_deref()))
+    };
+    if reference(a).is_some() && reference(a) == reference(b) {
+        score += 10;
+        reasons.push("Stejná reference".to_string());
+    }
+
+    (score.min(99), reasons)
+}
+
+/// Najde pravděpodobné duplicity: stejný účet, typ, měna a částka, datum nejvýše
+/// `max_days_apart` dní od sebe (výchozí 3). Pohyby s rozdílným externím ID jsou
+/// dva různé bankovní pohyby, a proto se nepárují.
+#[tauri::command]
+pub fn find_duplicate_transactions(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    max_days_apart: Option<i64>,
+    min_score: Option<u32>,
+) -> Result<Vec<DuplicateCandidate>> {
+    let conn = get_connection()?;
+    let max_days_apart = max_days_apart.unwrap_or(3).max(0);
+    let min_score = min_score.unwrap_or(60);
+
+    let mut stmt = conn.prepare(
+        "SELECT a.id, b.id, CAST(ABS(julianday(SUBSTR(a.date, 1, 10)) - julianday(SUBSTR(b.date, 1, 10))) AS INTEGER)
+         FROM transactions a
+         JOIN transactions b ON b.id > a.id
+             AND b.transaction_type = a.transaction_type
+             AND b.amount = a.amount
+             AND b.currency = a.currency
+             AND b.from_account_id IS a.from_account_id
+             AND b.to_account_id IS a.to_account_id
+         WHERE ABS(julianday(SUBSTR(a.date, 1, 10)) - julianday(SUBSTR(b.date, 1, 10))) <= ?1
+           AND NOT (a.external_id IS NOT NULL AND b.external_id IS NOT NULL AND a.external_id != b.external_id)
+           AND (?2 IS NULL OR a.date >= ?2)
+           AND (?3 IS NULL OR a.date <= ?3)
+         ORDER BY a.date DESC, a.id DESC",
+    )?;
+    let pairs = stmt
+        .query_map(
+            rusqlite::params![max_days_apart, start_date, end_date],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut cache: HashMap<i64, Transaction> = HashMap::new();
+    let mut load = |id: i64| -> Result<Transaction> {
+        if let Some(transaction) = cache.get(&id) {
+            return Ok(transaction.clone());
+        }
+        let transaction = query_transaction(&conn, id)?;
+        cache.insert(id, transaction.clone());
+        Ok(transaction)
+    };
+
+    let mut candidates = Vec::new();
+    for (a, b, days_apart) in pairs {
+        let transaction = load(a)?;
+        let duplicate = load(b)?;
+        let (score, reasons) = score_pair(&transaction, &duplicate, days_apart);
+        if score >= min_score {
+            candidates.push(DuplicateCandidate {
+                transaction,
+                duplicate,
+                score,
+                reasons,
+            });
+        }
+    }
+
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.score));
+    log::info!("find_duplicate_transactions: {} candidates", candidates.len());
+    Ok(candidates)
+}
+
+/// Sloučit lze jen pohyby, které by `find_duplicate_transactions` spároval:
+/// stejný typ, měna, částka a účty, bez rozdílných externích ID.
+fn ensure_mergeable(keep: &Transaction, duplicate: &Transaction) -> Result<()> {
+    let same = keep.transaction_type == duplicate.transaction_type
+        && keep.currency == duplicate.currency
+        && keep.amount == duplicate.amount
+        && keep.from_account_id == duplicate.from_account_id
+        && keep.to_account_id == duplicate.to_account_id;
+    let distinct_ids = matches!(
+        (&keep.external_id, &duplicate.external_id),
+        (Some(a), Some(b)) if a != b
+    );
+    if !same || distinct_ids {
+        return Err(AppError::InvalidInput(format!(
+            "Transakce {} není duplicitou transakce {} (liší se typ, měna, částka, účet nebo externí ID)",
+            duplicate.id, keep.id
+        )));
+    }
+    Ok(())
+}
+
+fn count_rows(conn: &Connection, table: &str, transaction_id: i64) -> Result<i64> {
+    let count = conn.query_row(
+        &format!("SELECT COUNT(*) FROM {} WHERE transaction_id = ?1", table),
+        [transaction_id],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Převezme do `keep` štítky, rozpad, rozdělení sdílených výdajů, vyrovnání a chybějící
+/// údaje transakce `duplicate`, kterou pak smaže i s jejím dopadem na zůstatky.
+pub(crate) fn absorb_duplicate(
+    conn: &Connection,
+    keep: &Transaction,
+    duplicate: &Transaction,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO transaction_tags (transaction_id, tag_id)
+         SELECT ?1, tag_id FROM transaction_tags WHERE transaction_id = ?2",
+        [keep.id, duplicate.id],
+    )?;
+
+    // Rozpad se převezme, jen pokud ho ponechaná transakce nemá
+    if count_rows(conn, "transaction_splits", keep.id)? == 0
+        && count_rows(conn, "transaction_splits", duplicate.id)? > 0
+    {
+        ensure_splits_match(splits_total(conn, duplicate.id, None)?, keep.amount)?;
+        conn.execute(
+            "UPDATE transaction_splits SET transaction_id = ?1 WHERE transaction_id = ?2",
+            [keep.id, duplicate.id],
+        )?;
+    }
+    if count_rows(conn, "shared_expense_splits", keep.id)? == 0 {
+        conn.execute(
+            "UPDATE shared_expense_splits SET transaction_id = ?1 WHERE transaction_id = ?2",
+            [keep.id, duplicate.id],
+        )?;
+        reallocate_shares(conn, keep.id, &keep.transaction_type, keep.amount)?;
+    }
+    conn.execute(
+        "UPDATE settlements SET transaction_id = ?1 WHERE transaction_id = ?2",
+        [keep.id, duplicate.id],
+    )?;
+
+    // Smazat před úpravou, termín pravidelné platby smí mít jen jedna transakce
+    reverse_entry(conn, &LedgerEntry::from(duplicate))?;
+    conn.execute("DELETE FROM transactions WHERE id = ?1", [duplicate.id])?;
+
+    conn.execute(
+        "UPDATE transactions SET
+            flow_group_id = COALESCE(flow_group_id, ?2),
+            category_id = COALESCE(category_id, ?3),
+            description = COALESCE(description, ?4),
+            notes = COALESCE(notes, ?5),
+            external_id = COALESCE(external_id, ?6),
+            value_date = COALESCE(value_date, ?7),
+            variable_symbol = COALESCE(variable_symbol, ?8),
+            constant_symbol = COALESCE(constant_symbol, ?9),
+            specific_symbol = COALESCE(specific_symbol, ?10),
+            counterparty_account = COALESCE(counterparty_account, ?11),
+            occurrence_date = CASE WHEN recurring_payment_id IS NULL
+                THEN ?12 ELSE occurrence_date END,
+            recurring_payment_id = COALESCE(recurring_payment_id, ?13),
+            payee_id = COALESCE(payee_id, ?14),
+            is_shared = CASE WHEN EXISTS (
+                SELECT 1 FROM shared_expense_splits WHERE transaction_id = ?1
+            ) THEN 1 ELSE is_shared END,
+            updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1",
+        rusqlite::params![
+            keep.id,
+            duplicate.flow_group_id,
+            duplicate.category_id,
+            duplicate.description,
+            duplicate.notes,
+            duplicate.external_id,
+            duplicate.value_date,
+            duplicate.variable_symbol,
+            duplicate.constant_symbol,
+            duplicate.specific_symbol,
+            duplicate.counterparty_account,
+            duplicate.occurrence_date,
+            duplicate.recurring_payment_id,
+            duplicate.payee_id,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Sloučí duplicity do transakce `keep_id`: převezme štítky, tok, rozpad, rozdělení
+/// sdílených výdajů a chybějící údaje, duplicity smaže a vrátí jejich dopad na zůstatky.
+#[tauri::command]
+pub fn merge_transactions(keep_id: i64, duplicate_ids: Vec<i64>) -> Result<Transaction> {
+    if duplicate_ids.is_empty() {
+        return Err(AppError::InvalidInput("Není co sloučit".to_string()));
+    }
+    if duplicate_ids.contains(&keep_id) {
+        return Err(AppError::InvalidInput(
+            "Transakci nelze sloučit samu se sebou".to_string(),
+        ));
+    }
+
+    with_transaction(|conn| merge_into(conn, keep_id, &duplicate_ids))
+}
+
+fn merge_into(conn: &Connection, keep_id: i64, duplicate_ids: &[i64]) -> Result<Transaction> {
+    for &duplicate_id in duplicate_ids {
+        // Načíst znovu: předchozí duplicita mohla doplnit externí ID, rozpad apod.
+        let keep = query_transaction(conn, keep_id)?;
+        ensure_unlocked(&keep)?;
+        let duplicate = query_transaction(conn, duplicate_id)?;
+        ensure_unlocked(&duplicate)?;
+        ensure_mergeable(&keep, &duplicate)?;
+
+        absorb_duplicate(conn, &keep, &duplicate)?;
+    }
+
+    refresh_fingerprint(conn, keep_id)?;
+    log::info!(
+        "merge_transactions: {} duplicates merged into {}",
+        duplicate_ids.len(),
+        keep_id
+    );
+    query_transaction(conn, keep_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    /// Tři stejné výdaje z účtu 1 (zůstatek už je promítnutý) a štítek 1 u transakce 2.
+    fn setup() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO accounts (id, name, account_type, initial_balance, current_balance)
+             VALUES (1, 'A', 'checking', 100000, 85000);
+             INSERT INTO tags (id, name) VALUES (1, 'dovolená');
+             INSERT INTO recurring_payments (id, name, amount, frequency, account_id)
+             VALUES (1, 'Nájem', 5000, 'monthly', 1);
+             INSERT INTO transactions (id, date, amount, transaction_type, status, from_account_id)
+             VALUES (1, '2024-03-01', 5000, 'expense', 'completed', 1),
+                    (2, '2024-03-01', 5000, 'expense', 'completed', 1),
+                    (3, '2024-03-02', 5000, 'expense', 'completed', 1);
+             INSERT INTO transaction_tags (transaction_id, tag_id) VALUES (2, 1);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn balance(conn: &Connection) -> i64 {
+        conn.query_row(
+            "SELECT current_balance FROM accounts WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn merges_several_duplicates() {
+        let conn = setup();
+        conn.execute_batch(
+            "UPDATE transactions SET external_id = 'A', notes = 'z výpisu' WHERE id = 2;
+             UPDATE transactions SET category_id = (SELECT MIN(id) FROM categories),
+                    recurring_payment_id = 1,
+                    occurrence_date = '2024-03-02', recurring_generated = 1 WHERE id = 3;",
+        )
+        .unwrap();
+
+        let merged = merge_into(&conn, 1, &[2, 3]).unwrap();
+        assert_eq!(merged.external_id.as_deref(), Some("A"));
+        assert_eq!(merged.notes.as_deref(), Some("z výpisu"));
+        assert!(merged.category_id.is_some());
+        assert_eq!(merged.recurring_payment_id, Some(1));
+        assert_eq!(merged.occurrence_date.as_deref(), Some("2024-03-02"));
+        assert_eq!(count_rows(&conn, "transaction_tags", 1).unwrap(), 1);
+        assert_eq!(balance(&conn), 95000);
+
+        let left: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(left, 1);
+    }
+
+    #[test]
+    fn duplicates_are_checked_against_each_other() {
+        // Druhá duplicita se porovná s transakcí doplněnou o externí ID z první
+        let conn = setup();
+        conn.execute_batch(
+            "UPDATE transactions SET external_id = 'A' WHERE id = 2;
+             UPDATE transactions SET external_id = 'B' WHERE id = 3;",
+        )
+        .unwrap();
+        assert!(matches!(
+            merge_into(&conn, 1, &[2, 3]),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn reconciled_transaction_is_not_merged_into() {
+        let conn = setup();
+        conn.execute(
+            "UPDATE transactions SET reconciliation_status = 'reconciled' WHERE id = 1",
+            [],
+        )
+        .unwrap();
+        assert!(matches!(
+            merge_into(&conn, 1, &[2]),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert_eq!(balance(&conn), 85000);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::db::connection::{get_connection, with_transaction};
+use crate::db::currency::find_rate;
+use crate::models::{ExchangeRate, RateImportResult, SetExchangeRateInput};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::fs;
+
+const RATE_COLUMNS: &str = "id, date, from_currency, to_currency, rate, source, created_at";
+
+fn row_to_rate(row: &rusqlite::Row) -> rusqlite::Result<ExchangeRate> {
+    Ok(ExchangeRate {
+        id: row.get(0)?,
+        date: row.get(1)?,
+        from_currency: row.get(2)?,
+        to_currency: row.get(3)?,
+        rate: row.get(4)?,
+        source: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn normalize_currency(code: &str) -> Result<String> {
+    let code = code.trim().to_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::InvalidInput(format!("Neplatný kód měny: {}", code)));
+    }
+    Ok(code)
+}
+
+fn validate_date(date: &str) -> Result<()> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| AppError::InvalidInput(format!("Neplatné datum: {}", date)))
+}
+
+/// Uloží kurz; existující kurz stejného páru a dne se přepíše.
+fn upsert_rate(
+    conn: &Connection,
+    date: &str,
+    from: &str,
+    to: &str,
+    rate: f64,
+    source: &str,
+) -> Result<ExchangeRate> {
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(AppError::InvalidInput(format!("Neplatný kurz: {}", rate)));
+    }
+    if from == to {
+        return Err(AppError::InvalidInput(
+            "Kurz musí být mezi dvěma různými měnami".to_string(),
+        ));
+    }
+
+    conn.execute(
+        "INSERT INTO exchange_rates (date, from_currency, to_currency, rate, source)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(date, from_currency, to_currency) DO UPDATE SET
+         rate = excluded.rate,
+         source = excluded.source",
+        rusqlite::params![date, from, to, rate, source],
+    )?;
+
+    let saved = conn.query_row(
+        &format!(
+            "SELECT {} FROM exchange_rates WHERE date = ?1 AND from_currency = ?2 AND to_currency = ?3",
+            RATE_COLUMNS
+        ),
+        [date, from, to],
+        row_to_rate,
+    )?;
+
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn set_exchange_rate(input: SetExchangeRateInput) -> Result<ExchangeRate> {
+    validate_date(&input.date)?;
+    let from = normalize_currency(&input.from_currency)?;
+    let to = normalize_currency(&input.to_currency)?;
+    let source = input.source.unwrap_or_else(|| "manual".to_string());
+
+    let conn = get_connection()?;
+    upsert_rate(&conn, &input.date, &from, &to, input.rate, &source)
+}
+
+#[tauri::command]
+pub fn get_exchange_rates(
+    from_currency: Option<String>,
+    to_currency: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<ExchangeRate>> {
+    let conn = get_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM exchange_rates
+         WHERE (?1 IS NULL OR from_currency = ?1)
+         AND (?2 IS NULL OR to_currency = ?2)
+         AND (?3 IS NULL OR date >= ?3)
+         AND (?4 IS NULL OR date <= ?4)
+         ORDER BY date DESC, from_currency, to_currency",
+        RATE_COLUMNS
+    ))?;
+
+    let rates = stmt
+        .query_map(
+            rusqlite::params![
+                from_currency.map(|c| c.trim().to_uppercase()),
+                to_currency.map(|c| c.trim().to_uppercase()),
+                start_date,
+                end_date
+            ],
+            row_to_rate,
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(rates)
+}
+
+#[tauri::command]
+pub fn delete_exchange_rate(id: i64) -> Result<()> {
+    let conn = get_connection()?;
+    conn.execute("DELETE FROM exchange_rates WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Přepočte částku kurzem platným k datu (poslední známý kurz ke dni včetně).
+#[tauri::command]
+pub fn convert_amount(
+    amount: Money,
+    from_currency: String,
+    to_currency: String,
+    date: String,
+) -> Result<Money> {
+    validate_date(&date)?;
+    let from = normalize_currency(&from_currency)?;
+    let to = normalize_currency(&to_currency)?;
+
+    let conn = get_connection()?;
+    let rate = find_rate(&conn, &from, &to, &date)?;
+    Ok(amount.mul_f64(rate))
+}
+
+/// Řádek denního kurzovního lístku ČNB: kód měny a kurz za 1 jednotku v CZK.
+#[derive(Debug, PartialEq)]
+pub(crate) struct CnbRate {
+    pub code: String,
+    pub rate: f64,
+}
+
+/// Rozparsuje denní kurzovní lístek ČNB (`denni_kurz.txt`):
+///
+/// ```text
+/// 17.10.2025 #201
+/// země|měna|množství|kód|kurz
+/// EMU|euro|1|EUR|24,330
+/// Japonsko|jen|100|JPY|15,512
+/// ```
+pub(crate) fn parse_cnb_daily(text: &str) -> Result<(String, Vec<CnbRate>)> {
+    let mut lines = text
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}').trim())
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty());
+
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| AppError::InvalidInput("Kurzovní lístek je prázdný".to_string()))?;
+    let date_text = header.split_whitespace().next().unwrap_or_default();
+    let date = NaiveDate::parse_from_str(date_text, "%d.%m.%Y")
+        .map_err(|_| {
+            AppError::InvalidInput(format!("Neplatné datum kurzovního lístku: {}", date_text))
+        })?
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut rates = Vec::new();
+    for (index, line) in lines {
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+        if fields.len() != 5 {
+            return Err(AppError::InvalidInput(format!(
+                "Řádek {}: očekáváno 5 sloupců oddělených '|'",
+                index + 1
+            )));
+        }
+
+        // Záhlaví sloupců
+        if fields[2].parse::<f64>().is_err() && rates.is_empty() {
+            continue;
+        }
+
+        let quantity: f64 = fields[2].replace(' ', "").parse().map_err(|_| {
+            AppError::InvalidInput(format!("Řádek {}: neplatné množství '{}'", index + 1, fields[2]))
+        })?;
+        let rate: f64 = fields[4].replace(' ', "").replace(',', ".").parse().map_err(|_| {
+            AppError::InvalidInput(format!("Řádek {}: neplatný kurz '{}'", index + 1, fields[4]))
+        })?;
+
+        if quantity <= 0.0 || rate <= 0.0 {
+            return Err(AppError::InvalidInput(format!(
+                "Řádek {}: množství i kurz musí být kladné",
+                index + 1
+            )));
+        }
+
+        rates.push(CnbRate {
+            code: normalize_currency(fields[3])?,
+            rate: rate / quantity,
+        });
+    }
+
+    if rates.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Kurzovní lístek neobsahuje žádné kurzy".to_string(),
+        ));
+    }
+
+    Ok((date, rates))
+}
+
+/// Importuje denní kurzovní lístek ČNB ze souboru. Kurzy se ukládají jako `XXX → CZK`.
+#[tauri::command]
+pub fn import_cnb_rates(path: String) -> Result<RateImportResult> {
+    log::info!("import_cnb_rates called: {}", path);
+
+    let bytes = fs::read(&path)?;
+    let text = String::from_utf8_lossy(&bytes);
+    let (date, rates) = parse_cnb_daily(&text)?;
+
+    with_transaction(|conn| {
+        for rate in &rates {
+            upsert_rate(conn, &date, &rate.code, "CZK", rate.rate, "cnb")?;
+        }
+
+        log::info!("import_cnb_rates: {} rates for {}", rates.len(), date);
+        Ok(RateImportResult {
+            date: date.clone(),
+            imported: rates.len(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_of(rates: &[CnbRate], code: &str) -> f64 {
+        rates.iter().find(|r| r.code == code).unwrap().rate
+    }
+
+    #[test]
+    fn parses_cnb_daily_fixing() {
+        let (date, rates) = parse_cnb_daily(include_str!("fixtures/cnb_denni_kurz.txt")).unwrap();
+
+        assert_eq!(date, "2024-01-02");
+        assert_eq!(rates.len(), 31);
+        assert_eq!(rate_of(&rates, "EUR"), 24.725);
+        assert_eq!(rate_of(&rates, "TRY"), 0.752);
+        // Kurz je za uvedené množství jednotek
+        assert!((rate_of(&rates, "JPY") - 0.15759).abs() < 1e-12);
+        assert!((rate_of(&rates, "IDR") - 0.001445).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cnb_fixing_tolerates_bom_and_crlf() {
+        let text =
+            "\u{feff}02.01.2024 #1\r\nzemě|měna|množství|kód|kurz\r\nEMU|euro|1|EUR|24,725\r\n\r\n";
+        let (date, rates) = parse_cnb_daily(text).unwrap();
+        assert_eq!(date, "2024-01-02");
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rate_of(&rates, "EUR"), 24.725);
+    }
+
+    #[test]
+    fn malformed_cnb_fixing_is_rejected() {
+        assert!(parse_cnb_daily("").is_err());
+        assert!(parse_cnb_daily("2024-01-02 #1\nEMU|euro|1|EUR|24,725").is_err());
+        assert!(parse_cnb_daily("02.01.2024 #1\nzemě|měna|množství|kód|kurz").is_err());
+        assert!(parse_cnb_daily("02.01.2024 #1\nEMU|euro|1|EUR").is_err());
+        assert!(parse_cnb_daily("02.01.2024 #1\nEMU|euro|0|EUR|24,725").is_err());
+    }
+}
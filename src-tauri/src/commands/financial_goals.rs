@@ -1,10 +1,12 @@
-use crate::db::connection::get_connection;
+use crate::db::connection::{get_connection, with_transaction};
 use crate::models::{FinancialGoal, CreateGoalInput, UpdateGoalInput, FundWithdrawal, CreateWithdrawalInput, MonthlyPlan, CreateMonthlyPlanInput, UpdateMonthlyPlanInput};
 use crate::utils::error::Result;
+use crate::utils::money::Money;
+use crate::utils::schedule::Schedule;
 
 const GOAL_COLUMNS: &str = "id, name, goal_type, icon, color, weekly_amount, day_of_week, 
     monthly_contribution, current_balance, yearly_amount, target_month, current_saved,
-    account_id, notes, is_active, created_at, updated_at";
+    account_id, notes, is_active, created_at, updated_at, schedule";
 
 fn row_to_goal(row: &rusqlite::Row) -> rusqlite::Result<FinancialGoal> {
     Ok(FinancialGoal {
@@ -25,9 +27,20 @@ fn row_to_goal(row: &rusqlite::Row) -> rusqlite::Result<FinancialGoal> {
         is_active: row.get::<_, i32>(14)? == 1,
         created_at: row.get(15)?,
         updated_at: row.get(16)?,
+        schedule: row.get(17)?,
     })
 }
 
+/// Opakování cíle: zadané pravidlo, jinak týdně v `day_of_week` (jen týdenní cíle).
+fn goal_schedule(schedule: Option<&str>, goal_type: &str, day_of_week: Option<i32>) -> Result<Option<String>> {
+    let today = chrono::Local::now().date_naive();
+    match (schedule.filter(|s| !s.trim().is_empty()), day_of_week) {
+        (Some(rule), _) => Ok(Some(Schedule::parse(rule, today)?.to_string())),
+        (None, Some(day)) if goal_type == "weekly_variable" => Ok(Some(Schedule::weekly_on(day, today).to_string())),
+        (None, _) => Ok(None),
+    }
+}
+
 // ============================================
 // FINANCIAL GOALS CRUD
 // ============================================
@@ -35,12 +48,13 @@ fn row_to_goal(row: &rusqlite::Row) -> rusqlite::Result<FinancialGoal> {
 #[tauri::command]
 pub fn create_financial_goal(input: CreateGoalInput) -> Result<FinancialGoal> {
     log::info!("create_financial_goal: name={}, type={}", input.name, input.goal_type);
+    let schedule = goal_schedule(input.schedule.as_deref(), &input.goal_type, input.day_of_week)?;
     let conn = get_connection()?;
 
     conn.execute(
         "INSERT INTO financial_goals (name, goal_type, icon, color, weekly_amount, day_of_week,
-         monthly_contribution, current_balance, yearly_amount, target_month, current_saved, account_id, notes, is_active)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, 1)",
+         monthly_contribution, current_balance, yearly_amount, target_month, current_saved, account_id, notes, is_active, schedule)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, 1, ?14)",
         rusqlite::params![
             input.name,
             input.goal_type,
@@ -49,12 +63,13 @@ pub fn create_financial_goal(input: CreateGoalInput) -> Result<FinancialGoal> {
             input.weekly_amount,
             input.day_of_week,
             input.monthly_contribution,
-            input.current_balance.unwrap_or(0.0),
+            input.current_balance.unwrap_or_default(),
             input.yearly_amount,
             input.target_month,
-            input.current_saved.unwrap_or(0.0),
+            input.current_saved.unwrap_or_default(),
             input.account_id,
             input.notes,
+            schedule,
         ],
     )?;
 
@@ -86,14 +101,15 @@ pub fn get_financial_goals() -> Result<Vec<FinancialGoal>> {
 #[tauri::command]
 pub fn update_financial_goal(id: i64, input: UpdateGoalInput) -> Result<FinancialGoal> {
     log::info!("update_financial_goal: id={}", id);
+    let schedule = goal_schedule(input.schedule.as_deref(), &input.goal_type, input.day_of_week)?;
     let conn = get_connection()?;
 
     conn.execute(
         "UPDATE financial_goals SET name = ?1, goal_type = ?2, icon = ?3, color = ?4,
          weekly_amount = ?5, day_of_week = ?6, monthly_contribution = ?7, current_balance = ?8,
          yearly_amount = ?9, target_month = ?10, current_saved = ?11, account_id = ?12, notes = ?13, is_active = ?14,
-         updated_at = CURRENT_TIMESTAMP
-         WHERE id = ?15",
+         schedule = ?15, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?16",
         rusqlite::params![
             input.name,
             input.goal_type,
@@ -109,6 +125,7 @@ pub fn update_financial_goal(id: i64, input: UpdateGoalInput) -> Result<Financia
             input.account_id,
             input.notes,
             input.is_active as i32,
+            schedule,
             id
         ],
     )?;
@@ -135,39 +152,39 @@ pub fn delete_financial_goal(id: i64) -> Result<()> {
 
 #[tauri::command]
 pub fn create_fund_withdrawal(input: CreateWithdrawalInput) -> Result<FundWithdrawal> {
-    let conn = get_connection()?;
-    
     let date = input.date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
 
-    conn.execute(
-        "INSERT INTO fund_withdrawals (goal_id, amount, description, date) VALUES (?1, ?2, ?3, ?4)",
-        rusqlite::params![input.goal_id, input.amount, input.description, date],
-    )?;
-
-    // Update fund balance
-    conn.execute(
-        "UPDATE financial_goals SET current_balance = current_balance - ?1 WHERE id = ?2",
-        rusqlite::params![input.amount, input.goal_id],
-    )?;
-
-    let id = conn.last_insert_rowid();
-
-    let withdrawal = conn.query_row(
-        "SELECT id, goal_id, amount, description, date, created_at FROM fund_withdrawals WHERE id = ?1",
-        [id],
-        |row| {
-            Ok(FundWithdrawal {
-                id: row.get(0)?,
-                goal_id: row.get(1)?,
-                amount: row.get(2)?,
-                description: row.get(3)?,
-                date: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        },
-    )?;
-
-    Ok(withdrawal)
+    with_transaction(|conn| {
+        conn.execute(
+            "INSERT INTO fund_withdrawals (goal_id, amount, description, date) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![input.goal_id, input.amount, input.description, date],
+        )?;
+
+        let id = conn.last_insert_rowid();
+
+        // Update fund balance
+        conn.execute(
+            "UPDATE financial_goals SET current_balance = current_balance - ?1 WHERE id = ?2",
+            rusqlite::params![input.amount, input.goal_id],
+        )?;
+
+        let withdrawal = conn.query_row(
+            "SELECT id, goal_id, amount, description, date, created_at FROM fund_withdrawals WHERE id = ?1",
+            [id],
+            |row| {
+                Ok(FundWithdrawal {
+                    id: row.get(0)?,
+                    goal_id: row.get(1)?,
+                    amount: row.get(2)?,
+                    description: row.get(3)?,
+                    date: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            },
+        )?;
+
+        Ok(withdrawal)
+    })
 }
 
 #[tauri::command]
@@ -196,7 +213,7 @@ pub fn get_fund_withdrawals(goal_id: i64) -> Result<Vec<FundWithdrawal>> {
 }
 
 #[tauri::command]
-pub fn add_fund_contribution(goal_id: i64, amount: f64) -> Result<FinancialGoal> {
+pub fn add_fund_contribution(goal_id: i64, amount: Money) -> Result<FinancialGoal> {
     let conn = get_connection()?;
     
     conn.execute(
@@ -258,8 +275,8 @@ pub fn create_or_update_monthly_plan(
     month: i32,
     plannedCount: i32,
     realizedCount: i32,
-    plannedAmount: f64,
-    realizedAmount: f64,
+    plannedAmount: Money,
+    realizedAmount: Money,
     notes: Option<String>,
 ) -> Result<MonthlyPlan> {
     let conn = get_connection()?;
@@ -1,4 +1,4 @@
-use crate::db::connection::get_connection;
+use crate::db::connection::{get_connection, with_transaction};
 use crate::models::{CreateFlowGroupInput, FlowGroup, UpdateFlowGroupInput};
 use crate::utils::error::Result;
 
@@ -81,17 +81,17 @@ pub fn update_flow_group(id: i64, input: UpdateFlowGroupInput) -> Result<FlowGro
 
 #[tauri::command]
 pub fn delete_flow_group(id: i64) -> Result<()> {
-    let conn = get_connection()?;
-
-    // Odebrat flow_group_id z transakcí
-    conn.execute(
-        "UPDATE transactions SET flow_group_id = NULL WHERE flow_group_id = ?1",
-        [id],
-    )?;
+    with_transaction(|conn| {
+        // Odebrat flow_group_id z transakcí
+        conn.execute(
+            "UPDATE transactions SET flow_group_id = NULL WHERE flow_group_id = ?1",
+            [id],
+        )?;
 
-    conn.execute("DELETE FROM flow_groups WHERE id = ?1", [id])?;
+        conn.execute("DELETE FROM flow_groups WHERE id = ?1", [id])?;
 
-    Ok(())
+        Ok(())
+    })
 }
 
 #[tauri::command]
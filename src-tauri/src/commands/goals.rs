@@ -1,10 +1,12 @@
 use crate::db::connection::get_connection;
+use crate::db::currency::resolve_currency;
 use crate::models::{CreateSavingsGoalInput, SavingsGoal, UpdateSavingsGoalInput};
 use crate::utils::error::Result;
 
 #[tauri::command]
 pub fn create_savings_goal(input: CreateSavingsGoalInput) -> Result<SavingsGoal> {
     let conn = get_connection()?;
+    let currency = resolve_currency(&conn, input.currency, input.account_id)?;
 
     conn.execute(
         "INSERT INTO savings_goals (name, target_amount, current_amount, currency, deadline,
@@ -13,7 +15,7 @@ pub fn create_savings_goal(input: CreateSavingsGoalInput) -> Result<SavingsGoal>
         rusqlite::params![
             input.name,
             input.target_amount,
-            input.currency.unwrap_or_else(|| "CZK".to_string()),
+            currency,
             input.deadline,
             input.account_id,
             input.auto_deposit_amount,
@@ -1,18 +1,32 @@
 use crate::db::connection::get_connection;
 use crate::models::household::*;
 use crate::utils::error::Result;
+use crate::utils::payment::PaymentDetails;
+use crate::utils::schedule::Schedule;
+
+/// Zadané pravidlo opakování v jednotném zápisu, jinak převedené z frekvence a dne v měsíci.
+fn household_schedule(
+    schedule: Option<&str>,
+    frequency: &str,
+    day_of_month: Option<i32>,
+) -> Result<String> {
+    let today = chrono::Local::now().date_naive();
+    Ok(Schedule::stored_or_legacy(schedule, frequency, 1, day_of_month, today)?.to_string())
+}
 
 // ============================================
 // HOUSEHOLD MEMBERS
 // ============================================
 
+/// Členové domácnosti jsou uživatelé kromě společného; smazaní (neaktivní) se nevrací.
 #[tauri::command]
 pub fn get_household_members() -> Result<Vec<HouseholdMember>> {
     let conn = get_connection()?;
     let mut stmt = conn.prepare(
         "SELECT id, name, color, avatar, created_at, updated_at 
-         FROM household_members 
-         ORDER BY created_at ASC"
+         FROM users 
+         WHERE is_shared_user = 0 AND active = 1
+         ORDER BY created_at ASC, id ASC"
     )?;
     
     let members = stmt.query_map([], |row| {
@@ -35,7 +49,7 @@ pub fn create_household_member(input: CreateMemberInput) -> Result<HouseholdMemb
     let color = input.color.unwrap_or_else(|| "#3B82F6".to_string());
     
     conn.execute(
-        "INSERT INTO household_members (name, color, avatar) VALUES (?1, ?2, ?3)",
+        "INSERT INTO users (name, color, avatar, role, is_shared_user, active) VALUES (?1, ?2, ?3, 'member', 0, 1)",
         (&input.name, &color, &input.avatar),
     )?;
     
@@ -57,13 +71,13 @@ pub fn update_household_member(id: i64, input: CreateMemberInput) -> Result<Hous
     let color = input.color.unwrap_or_else(|| "#3B82F6".to_string());
     
     conn.execute(
-        "UPDATE household_members SET name = ?1, color = ?2, avatar = ?3, updated_at = CURRENT_TIMESTAMP WHERE id = ?4",
+        "UPDATE users SET name = ?1, color = ?2, avatar = ?3, updated_at = CURRENT_TIMESTAMP WHERE id = ?4 AND is_shared_user = 0",
         (&input.name, &color, &input.avatar, id),
     )?;
     
     // Return updated member
     let member = conn.query_row(
-        "SELECT id, name, color, avatar, created_at, updated_at FROM household_members WHERE id = ?1",
+        "SELECT id, name, color, avatar, created_at, updated_at FROM users WHERE id = ?1",
         [id],
         |row| {
             Ok(HouseholdMember {
@@ -80,10 +94,14 @@ pub fn update_household_member(id: i64, input: CreateMemberInput) -> Result<Hous
     Ok(member)
 }
 
+/// Soft delete shodně s `delete_user` - osoba může vlastnit účty a transakce.
 #[tauri::command]
 pub fn delete_household_member(id: i64) -> Result<()> {
     let conn = get_connection()?;
-    conn.execute("DELETE FROM household_members WHERE id = ?1", [id])?;
+    conn.execute(
+        "UPDATE users SET active = 0, updated_at = CURRENT_TIMESTAMP WHERE id = ?1 AND is_shared_user = 0",
+        [id],
+    )?;
     Ok(())
 }
 
@@ -95,9 +113,9 @@ pub fn delete_household_member(id: i64) -> Result<()> {
 pub fn get_member_incomes(member_id: i64) -> Result<Vec<MemberIncome>> {
     let conn = get_connection()?;
     let mut stmt = conn.prepare(
-        "SELECT id, member_id, name, amount, frequency, day_of_month, account_id, is_active, created_at, updated_at 
+        "SELECT id, user_id, name, amount, frequency, day_of_month, account_id, is_active, created_at, updated_at, schedule
          FROM member_incomes 
-         WHERE member_id = ?1
+         WHERE user_id = ?1
          ORDER BY created_at ASC"
     )?;
     
@@ -113,6 +131,7 @@ pub fn get_member_incomes(member_id: i64) -> Result<Vec<MemberIncome>> {
             is_active: row.get::<_, i32>(7)? == 1,
             created_at: row.get(8)?,
             updated_at: row.get(9)?,
+            schedule: row.get(10)?,
         })
     })?.collect::<std::result::Result<Vec<_>, _>>()?;
     
@@ -124,10 +143,11 @@ pub fn get_all_incomes() -> Result<Vec<MemberIncome>> {
     log::info!("get_all_incomes called");
     let conn = get_connection()?;
     let mut stmt = conn.prepare(
-        "SELECT id, member_id, name, amount, frequency, day_of_month, account_id, is_active, created_at, updated_at 
-         FROM member_incomes 
-         WHERE is_active = 1
-         ORDER BY day_of_month ASC"
+        "SELECT mi.id, mi.user_id, mi.name, mi.amount, mi.frequency, mi.day_of_month, mi.account_id, mi.is_active, mi.created_at, mi.updated_at, mi.schedule 
+         FROM member_incomes mi
+         JOIN users u ON u.id = mi.user_id
+         WHERE mi.is_active = 1 AND u.active = 1
+         ORDER BY mi.day_of_month ASC"
     )?;
     
     let incomes = stmt.query_map([], |row| {
@@ -142,6 +162,7 @@ pub fn get_all_incomes() -> Result<Vec<MemberIncome>> {
             is_active: row.get::<_, i32>(7)? == 1,
             created_at: row.get(8)?,
             updated_at: row.get(9)?,
+            schedule: row.get(10)?,
         })
     })?.collect::<std::result::Result<Vec<_>, _>>()?;
     
@@ -153,10 +174,11 @@ pub fn get_all_incomes() -> Result<Vec<MemberIncome>> {
 pub fn create_member_income(input: CreateIncomeInput) -> Result<MemberIncome> {
     let conn = get_connection()?;
     let frequency = input.frequency.unwrap_or_else(|| "monthly".to_string());
+    let schedule = household_schedule(input.schedule.as_deref(), &frequency, input.day_of_month)?;
     
     conn.execute(
-        "INSERT INTO member_incomes (member_id, name, amount, frequency, day_of_month, account_id, is_active) 
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1)",
+        "INSERT INTO member_incomes (user_id, name, amount, frequency, day_of_month, account_id, is_active, schedule) 
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, ?7)",
         (
             input.member_id,
             &input.name,
@@ -164,6 +186,7 @@ pub fn create_member_income(input: CreateIncomeInput) -> Result<MemberIncome> {
             &frequency,
             input.day_of_month,
             input.account_id,
+            &schedule,
         ),
     )?;
     
@@ -180,6 +203,7 @@ pub fn create_member_income(input: CreateIncomeInput) -> Result<MemberIncome> {
         is_active: true,
         created_at: None,
         updated_at: None,
+        schedule: Some(schedule),
     })
 }
 
@@ -187,22 +211,24 @@ pub fn create_member_income(input: CreateIncomeInput) -> Result<MemberIncome> {
 pub fn update_member_income(id: i64, input: CreateIncomeInput) -> Result<MemberIncome> {
     let conn = get_connection()?;
     let frequency = input.frequency.unwrap_or_else(|| "monthly".to_string());
+    let schedule = household_schedule(input.schedule.as_deref(), &frequency, input.day_of_month)?;
     
     conn.execute(
-        "UPDATE member_incomes SET name = ?1, amount = ?2, frequency = ?3, day_of_month = ?4, account_id = ?5, updated_at = CURRENT_TIMESTAMP WHERE id = ?6",
+        "UPDATE member_incomes SET name = ?1, amount = ?2, frequency = ?3, day_of_month = ?4, account_id = ?5, schedule = ?6, updated_at = CURRENT_TIMESTAMP WHERE id = ?7",
         (
             &input.name,
             input.amount,
             &frequency,
             input.day_of_month,
             input.account_id,
+            &schedule,
             id,
         ),
     )?;
     
     // Return updated income
     let income = conn.query_row(
-        "SELECT id, member_id, name, amount, frequency, day_of_month, account_id, is_active, created_at, updated_at FROM member_incomes WHERE id = ?1",
+        "SELECT id, user_id, name, amount, frequency, day_of_month, account_id, is_active, created_at, updated_at, schedule FROM member_incomes WHERE id = ?1",
         [id],
         |row| {
             Ok(MemberIncome {
@@ -216,6 +242,7 @@ pub fn update_member_income(id: i64, input: CreateIncomeInput) -> Result<MemberI
                 is_active: row.get::<_, i32>(7)? == 1,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                schedule: row.get(10)?,
             })
         },
     )?;
@@ -234,11 +261,22 @@ pub fn delete_member_income(id: i64) -> Result<()> {
 // SCHEDULED TRANSFERS
 // ============================================
 
+/// Ověřené platební údaje převodu.
+fn transfer_payment_details(input: &CreateTransferInput) -> Result<PaymentDetails> {
+    PaymentDetails::validated(
+        input.variable_symbol.as_deref(),
+        input.constant_symbol.as_deref(),
+        input.specific_symbol.as_deref(),
+        input.counterparty_account.as_deref(),
+    )
+}
+
 #[tauri::command]
 pub fn get_scheduled_transfers() -> Result<Vec<ScheduledTransfer>> {
     let conn = get_connection()?;
     let mut stmt = conn.prepare(
-        "SELECT id, name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active, created_at, updated_at 
+        "SELECT id, name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active, created_at, updated_at,
+         variable_symbol, constant_symbol, specific_symbol, counterparty_account, schedule
          FROM scheduled_transfers 
          WHERE is_active = 1
          ORDER BY day_of_month ASC, display_order ASC"
@@ -258,6 +296,11 @@ pub fn get_scheduled_transfers() -> Result<Vec<ScheduledTransfer>> {
             is_active: row.get::<_, i32>(9)? == 1,
             created_at: row.get(10)?,
             updated_at: row.get(11)?,
+            variable_symbol: row.get(12)?,
+            constant_symbol: row.get(13)?,
+            specific_symbol: row.get(14)?,
+            counterparty_account: row.get(15)?,
+            schedule: row.get(16)?,
         })
     })?.collect::<std::result::Result<Vec<_>, _>>()?;
     
@@ -266,6 +309,8 @@ pub fn get_scheduled_transfers() -> Result<Vec<ScheduledTransfer>> {
 
 #[tauri::command]
 pub fn create_scheduled_transfer(input: CreateTransferInput) -> Result<ScheduledTransfer> {
+    let details = transfer_payment_details(&input)?;
+    let schedule = household_schedule(input.schedule.as_deref(), "monthly", Some(input.day_of_month))?;
     let conn = get_connection()?;
     let category = input.category.unwrap_or_else(|| "internal".to_string());
     
@@ -277,9 +322,10 @@ pub fn create_scheduled_transfer(input: CreateTransferInput) -> Result<Scheduled
     )?;
     
     conn.execute(
-        "INSERT INTO scheduled_transfers (name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active) 
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 1)",
-        (
+        "INSERT INTO scheduled_transfers (name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active,
+         variable_symbol, constant_symbol, specific_symbol, counterparty_account, schedule) 
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 1, ?9, ?10, ?11, ?12, ?13)",
+        rusqlite::params![
             &input.name,
             input.from_account_id,
             input.to_account_id,
@@ -288,7 +334,12 @@ pub fn create_scheduled_transfer(input: CreateTransferInput) -> Result<Scheduled
             &input.description,
             &category,
             max_order + 1,
-        ),
+            &details.variable_symbol,
+            &details.constant_symbol,
+            &details.specific_symbol,
+            &details.counterparty_account,
+            &schedule,
+        ],
     )?;
     
     let id = conn.last_insert_rowid();
@@ -306,20 +357,29 @@ pub fn create_scheduled_transfer(input: CreateTransferInput) -> Result<Scheduled
         is_active: true,
         created_at: None,
         updated_at: None,
+        variable_symbol: details.variable_symbol,
+        constant_symbol: details.constant_symbol,
+        specific_symbol: details.specific_symbol,
+        counterparty_account: details.counterparty_account,
+        schedule: Some(schedule),
     })
 }
 
 #[tauri::command]
 pub fn update_scheduled_transfer(id: i64, input: CreateTransferInput) -> Result<ScheduledTransfer> {
     log::info!("update_scheduled_transfer called: id={}", id);
+    let details = transfer_payment_details(&input)?;
+    let schedule = household_schedule(input.schedule.as_deref(), "monthly", Some(input.day_of_month))?;
     let conn = get_connection()?;
     let category = input.category.unwrap_or_else(|| "internal".to_string());
     
     conn.execute(
         "UPDATE scheduled_transfers SET name = ?1, from_account_id = ?2, to_account_id = ?3, 
-         amount = ?4, day_of_month = ?5, description = ?6, category = ?7, updated_at = CURRENT_TIMESTAMP
-         WHERE id = ?8",
-        (
+         amount = ?4, day_of_month = ?5, description = ?6, category = ?7,
+         variable_symbol = ?8, constant_symbol = ?9, specific_symbol = ?10, counterparty_account = ?11,
+         schedule = ?12, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?13",
+        rusqlite::params![
             &input.name,
             input.from_account_id,
             input.to_account_id,
@@ -327,13 +387,19 @@ pub fn update_scheduled_transfer(id: i64, input: CreateTransferInput) -> Result<
             input.day_of_month,
             &input.description,
             &category,
+            &details.variable_symbol,
+            &details.constant_symbol,
+            &details.specific_symbol,
+            &details.counterparty_account,
+            &schedule,
             id,
-        ),
+        ],
     )?;
     
     // Return updated transfer
     let transfer = conn.query_row(
-        "SELECT id, name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active, created_at, updated_at 
+        "SELECT id, name, from_account_id, to_account_id, amount, day_of_month, description, category, display_order, is_active, created_at, updated_at,
+         variable_symbol, constant_symbol, specific_symbol, counterparty_account, schedule
          FROM scheduled_transfers WHERE id = ?1",
         [id],
         |row| {
@@ -350,6 +416,11 @@ pub fn update_scheduled_transfer(id: i64, input: CreateTransferInput) -> Result<
                 is_active: row.get::<_, i32>(9)? == 1,
                 created_at: row.get(10)?,
                 updated_at: row.get(11)?,
+                variable_symbol: row.get(12)?,
+                constant_symbol: row.get(13)?,
+                specific_symbol: row.get(14)?,
+                counterparty_account: row.get(15)?,
+                schedule: row.get(16)?,
             })
         },
     )?;
@@ -374,7 +445,7 @@ pub fn get_fixed_expenses() -> Result<Vec<FixedExpense>> {
     log::info!("get_fixed_expenses called");
     let conn = get_connection()?;
     let mut stmt = conn.prepare(
-        "SELECT id, name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes, created_at, updated_at 
+        "SELECT id, name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes, created_at, updated_at, schedule
          FROM fixed_expenses 
          WHERE is_active = 1
          ORDER BY day_of_month ASC, category ASC, name ASC"
@@ -394,6 +465,7 @@ pub fn get_fixed_expenses() -> Result<Vec<FixedExpense>> {
             notes: row.get(9)?,
             created_at: row.get(10)?,
             updated_at: row.get(11)?,
+            schedule: row.get(12)?,
         })
     })?.collect::<std::result::Result<Vec<_>, _>>()?;
     
@@ -407,10 +479,11 @@ pub fn create_fixed_expense(input: CreateFixedExpenseInput) -> Result<FixedExpen
     let conn = get_connection()?;
     let frequency = input.frequency.unwrap_or_else(|| "monthly".to_string());
     let assigned_to = input.assigned_to.unwrap_or_else(|| "shared".to_string());
+    let schedule = household_schedule(input.schedule.as_deref(), &frequency, input.day_of_month)?;
     
     conn.execute(
-        "INSERT INTO fixed_expenses (name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes) 
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1, ?8)",
+        "INSERT INTO fixed_expenses (name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes, schedule) 
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1, ?8, ?9)",
         (
             &input.name,
             input.amount,
@@ -420,6 +493,7 @@ pub fn create_fixed_expense(input: CreateFixedExpenseInput) -> Result<FixedExpen
             input.account_id,
             &assigned_to,
             &input.notes,
+            &schedule,
         ),
     )?;
     
@@ -439,6 +513,7 @@ pub fn create_fixed_expense(input: CreateFixedExpenseInput) -> Result<FixedExpen
         notes: input.notes,
         created_at: None,
         updated_at: None,
+        schedule: Some(schedule),
     })
 }
 
@@ -448,11 +523,12 @@ pub fn update_fixed_expense(id: i64, input: CreateFixedExpenseInput) -> Result<F
     let conn = get_connection()?;
     let frequency = input.frequency.unwrap_or_else(|| "monthly".to_string());
     let assigned_to = input.assigned_to.unwrap_or_else(|| "shared".to_string());
+    let schedule = household_schedule(input.schedule.as_deref(), &frequency, input.day_of_month)?;
     
     conn.execute(
         "UPDATE fixed_expenses SET name = ?1, amount = ?2, category = ?3, frequency = ?4, 
-         day_of_month = ?5, account_id = ?6, assigned_to = ?7, notes = ?8, updated_at = CURRENT_TIMESTAMP
-         WHERE id = ?9",
+         day_of_month = ?5, account_id = ?6, assigned_to = ?7, notes = ?8, schedule = ?9, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?10",
         (
             &input.name,
             input.amount,
@@ -462,13 +538,14 @@ pub fn update_fixed_expense(id: i64, input: CreateFixedExpenseInput) -> Result<F
             input.account_id,
             &assigned_to,
             &input.notes,
+            &schedule,
             id,
         ),
     )?;
     
     // Return updated expense
     let expense = conn.query_row(
-        "SELECT id, name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes, created_at, updated_at 
+        "SELECT id, name, amount, category, frequency, day_of_month, account_id, assigned_to, is_active, notes, created_at, updated_at, schedule
          FROM fixed_expenses WHERE id = ?1",
         [id],
         |row| {
@@ -485,6 +562,7 @@ pub fn update_fixed_expense(id: i64, input: CreateFixedExpenseInput) -> Result<F
                 notes: row.get(9)?,
                 created_at: row.get(10)?,
                 updated_at: row.get(11)?,
+                schedule: row.get(12)?,
             })
         },
     )?;
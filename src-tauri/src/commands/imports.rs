@@ -0,0 +1,412 @@
+use crate::commands::transactions::{insert_transaction, Categorizer};
+use crate::db::connection::{get_connection, with_transaction};
+use crate::importers::{self, camt, csv_statement, mt940, ofx, qif, ParsedStatement};
+use crate::models::{
+    CreateTransactionInput, CsvImportInput, ImportPreviewRow, ImportProfile, ImportResult,
+    SaveImportProfileInput, StatementImportInput,
+};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::fs;
+
+const PROFILE_COLUMNS: &str = "id, name, bank_code, delimiter, encoding, date_format, decimal_comma,
+    date_column, amount_column, debit_column, direction_column, debit_value, currency_column,
+    description_column, counterparty_column, note_column, is_builtin, created_at, updated_at";
+
+fn row_to_profile(row: &rusqlite::Row) -> rusqlite::Result<ImportProfile> {
+    Ok(ImportProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        bank_code: row.get(2)?,
+        delimiter: row.get(3)?,
+        encoding: row.get(4)?,
+        date_format: row.get(5)?,
+        decimal_comma: row.get::<_, i32>(6)? != 0,
+        date_column: row.get(7)?,
+        amount_column: row.get(8)?,
+        debit_column: row.get(9)?,
+        direction_column: row.get(10)?,
+        debit_value: row.get(11)?,
+        currency_column: row.get(12)?,
+        description_column: row.get(13)?,
+        counterparty_column: row.get(14)?,
+        note_column: row.get(15)?,
+        is_builtin: row.get::<_, i32>(16)? != 0,
+        created_at: row.get(17)?,
+        updated_at: row.get(18)?,
+    })
+}
+
+fn query_profile(conn: &Connection, id: i64) -> Result<ImportProfile> {
+    conn.query_row(
+        &format!("SELECT {} FROM import_profiles WHERE id = ?1", PROFILE_COLUMNS),
+        [id],
+        row_to_profile,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::NotFound(format!("Profil importu {} neexistuje", id)))
+}
+
+fn ensure_editable(conn: &Connection, id: i64) -> Result<()> {
+    if query_profile(conn, id)?.is_builtin {
+        return Err(AppError::InvalidInput(
+            "Vestavěný profil nelze měnit ani mazat".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_profile(input: &SaveImportProfileInput) -> Result<()> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Název profilu je povinný".to_string()));
+    }
+    if input.date_column.trim().is_empty() || input.amount_column.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Profil musí určit sloupec data a částky".to_string(),
+        ));
+    }
+    if let Some(encoding) = &input.encoding {
+        importers::decode(b"", encoding)?;
+    }
+    if input.direction_column.is_some() != input.debit_value.is_some() {
+        return Err(AppError::InvalidInput(
+            "Sloupec směru platby vyžaduje hodnotu pro odchozí platby".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_import_profiles() -> Result<Vec<ImportProfile>> {
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM import_profiles ORDER BY is_builtin DESC, name",
+        PROFILE_COLUMNS
+    ))?;
+    let profiles = stmt
+        .query_map([], row_to_profile)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn create_import_profile(input: SaveImportProfileInput) -> Result<ImportProfile> {
+    validate_profile(&input)?;
+    let conn = get_connection()?;
+
+    conn.execute(
+        "INSERT INTO import_profiles (name, bank_code, delimiter, encoding, date_format, decimal_comma,
+         date_column, amount_column, debit_column, direction_column, debit_value, currency_column,
+         description_column, counterparty_column, note_column)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        rusqlite::params![
+            input.name.trim(),
+            input.bank_code,
+            input.delimiter.unwrap_or_else(|| ";".to_string()),
+            input.encoding.unwrap_or_else(|| "utf-8".to_string()),
+            input.date_format.unwrap_or_else(|| "%d.%m.%Y".to_string()),
+            input.decimal_comma.unwrap_or(true) as i32,
+            input.date_column,
+            input.amount_column,
+            input.debit_column,
+            input.direction_column,
+            input.debit_value,
+            input.currency_column,
+            input.description_column,
+            input.counterparty_column,
+            input.note_column,
+        ],
+    )?;
+
+    query_profile(&conn, conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub fn update_import_profile(id: i64, input: SaveImportProfileInput) -> Result<ImportProfile> {
+    validate_profile(&input)?;
+    let conn = get_connection()?;
+    ensure_editable(&conn, id)?;
+
+    conn.execute(
+        "UPDATE import_profiles SET name = ?1, bank_code = ?2, delimiter = ?3, encoding = ?4,
+         date_format = ?5, decimal_comma = ?6, date_column = ?7, amount_column = ?8,
+         debit_column = ?9, direction_column = ?10, debit_value = ?11, currency_column = ?12,
+         description_column = ?13, counterparty_column = ?14, note_column = ?15,
+         updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?16",
+        rusqlite::params![
+            input.name.trim(),
+            input.bank_code,
+            input.delimiter.unwrap_or_else(|| ";".to_string()),
+            input.encoding.unwrap_or_else(|| "utf-8".to_string()),
+            input.date_format.unwrap_or_else(|| "%d.%m.%Y".to_string()),
+            input.decimal_comma.unwrap_or(true) as i32,
+            input.date_column,
+            input.amount_column,
+            input.debit_column,
+            input.direction_column,
+            input.debit_value,
+            input.currency_column,
+            input.description_column,
+            input.counterparty_column,
+            input.note_column,
+            id,
+        ],
+    )?;
+
+    query_profile(&conn, id)
+}
+
+#[tauri::command]
+pub fn delete_import_profile(id: i64) -> Result<()> {
+    let conn = get_connection()?;
+    ensure_editable(&conn, id)?;
+    conn.execute("DELETE FROM import_profiles WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+fn account_balance(conn: &Connection, account_id: i64) -> Result<Money> {
+    let balance = conn.query_row(
+        "SELECT current_balance FROM accounts WHERE id = ?1",
+        [account_id],
+        |row| row.get(0),
+    )?;
+    Ok(balance)
+}
+
+/// Pohyb se stejným externím ID už na účtu je (opakovaný import téhož výpisu).
+fn external_id_exists(conn: &Connection, account_id: i64, external_id: &str) -> Result<bool> {
+    let exists = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM transactions
+         WHERE external_id = ?1 AND (from_account_id = ?2 OR to_account_id = ?2))",
+        rusqlite::params![external_id, account_id],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+/// Převede řádky výpisu na transakce účtu: připsání jako příjem, odepsání jako výdaj.
+/// Chybné řádky se přeskočí a vrátí v přehledu chyb, již importované pohyby (podle
+/// externího ID) se jen započítají jako duplicity; při `dry_run` se nic neuloží.
+pub(crate) fn import_statement(
+    conn: &Connection,
+    account_id: i64,
+    category_id: Option<i64>,
+    mut statement: ParsedStatement,
+    dry_run: bool,
+) -> Result<ImportResult> {
+    let account_currency: String = conn
+        .query_row(
+            "SELECT currency FROM accounts WHERE id = ?1",
+            [account_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Účet {} neexistuje", account_id)))?;
+
+    if let Some(category_id) = category_id {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM categories WHERE id = ?1)",
+            [category_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(AppError::InvalidInput(format!(
+                "Kategorie {} neexistuje",
+                category_id
+            )));
+        }
+    }
+
+    let rows = std::mem::take(&mut statement.rows);
+    let total_rows = rows.len() + statement.errors.len();
+    let mut preview = Vec::new();
+    let mut duplicates = 0;
+    let mut seen_ids = HashSet::new();
+    let mut projected_balance = account_balance(conn, account_id)?;
+    // Pravidla a obchodníci se zkompilují jednou pro celý výpis
+    let categorizer = Categorizer::load(conn)?;
+
+    for row in rows {
+        if let Some(external_id) = &row.external_id {
+            if !seen_ids.insert(external_id.clone())
+                || external_id_exists(conn, account_id, external_id)?
+            {
+                duplicates += 1;
+                continue;
+            }
+        }
+
+        let currency = row.currency.clone().unwrap_or_else(|| account_currency.clone());
+        if currency != account_currency {
+            statement.error(
+                row.line,
+                format!("Měna {} neodpovídá měně účtu {}", currency, account_currency),
+            );
+            continue;
+        }
+        if row.amount.is_zero() {
+            statement.error(row.line, "Nulová částka");
+            continue;
+        }
+
+        let is_income = row.amount > Money::ZERO;
+        let transaction_type = if is_income { "income" } else { "expense" };
+        let description = row.description.clone().or_else(|| row.counterparty.clone());
+
+        let transaction_id = if dry_run {
+            None
+        } else {
+            let input = CreateTransactionInput {
+                date: row.date.clone(),
+                amount: if is_income { row.amount } else { -row.amount },
+                currency: Some(currency.clone()),
+                transaction_type: transaction_type.to_string(),
+                from_account_id: if is_income { None } else { Some(account_id) },
+                to_account_id: if is_income { Some(account_id) } else { None },
+                category_id,
+                description: description.clone(),
+                owner_user_id: None,
+                is_shared: None,
+                status: None,
+                notes: row.note.clone(),
+                destination_amount: None,
+                exchange_rate: None,
+                fee_amount: None,
+                external_id: row.external_id.clone(),
+                value_date: row.value_date.clone(),
+                variable_symbol: row.variable_symbol.clone(),
+                constant_symbol: row.constant_symbol.clone(),
+                specific_symbol: row.specific_symbol.clone(),
+                counterparty_account: row.counterparty_account.clone(),
+                payee_id: None,
+                recurring_payment_id: None,
+                occurrence_date: None,
+            };
+            Some(insert_transaction(conn, &categorizer, &input)?.id)
+        };
+        projected_balance += row.amount;
+
+        preview.push(ImportPreviewRow {
+            line: row.line,
+            date: row.date,
+            amount: row.amount,
+            currency,
+            transaction_type: transaction_type.to_string(),
+            description,
+            external_id: row.external_id,
+            variable_symbol: row.variable_symbol,
+            counterparty_account: row.counterparty_account,
+            transaction_id,
+        });
+    }
+
+    statement.errors.sort_by_key(|error| error.line);
+
+    let account_balance = if dry_run {
+        projected_balance
+    } else {
+        account_balance(conn, account_id)?
+    };
+    let opening = statement.opening_balance;
+    let closing = statement.closing_balance;
+
+    Ok(ImportResult {
+        dry_run,
+        total_rows,
+        imported: preview.len(),
+        duplicates,
+        rows: preview,
+        errors: statement.errors,
+        opening_balance: opening.as_ref().map(|o| o.amount),
+        opening_balance_date: opening.and_then(|o| o.date),
+        statement_balance: closing.as_ref().map(|c| c.amount),
+        statement_balance_date: closing.as_ref().and_then(|c| c.date.clone()),
+        account_balance,
+        balance_difference: closing.map(|c| c.amount - account_balance),
+    })
+}
+
+/// Import v jedné SQL transakci; chyba databáze vrátí vše zpět.
+fn run_import(
+    command: &str,
+    account_id: i64,
+    category_id: Option<i64>,
+    dry_run: bool,
+    statement: ParsedStatement,
+) -> Result<ImportResult> {
+    with_transaction(|conn| {
+        let result = import_statement(conn, account_id, category_id, statement, dry_run)?;
+        log::info!(
+            "{}: {} of {} rows, {} duplicates, {} errors",
+            command,
+            result.imported,
+            result.total_rows,
+            result.duplicates,
+            result.errors.len()
+        );
+        Ok(result)
+    })
+}
+
+/// Importuje bankovní výpis v CSV podle profilu na zvolený účet.
+#[tauri::command]
+pub fn import_transactions_csv(input: CsvImportInput) -> Result<ImportResult> {
+    log::info!(
+        "import_transactions_csv called: {} (profile {}, dry_run {})",
+        input.path,
+        input.profile_id,
+        input.dry_run
+    );
+
+    let bytes = fs::read(&input.path)?;
+    let profile = {
+        let conn = get_connection()?;
+        query_profile(&conn, input.profile_id)?
+    };
+    let text = importers::decode(&bytes, &profile.encoding)?;
+    let statement = csv_statement::parse(&text, &profile)?;
+    run_import("import_transactions_csv", input.account_id, input.category_id, input.dry_run, statement)
+}
+
+/// Importuje výpis OFX/QFX (1.x SGML i 2.x XML) na zvolený účet.
+#[tauri::command]
+pub fn import_transactions_ofx(input: StatementImportInput) -> Result<ImportResult> {
+    log::info!("import_transactions_ofx called: {} (dry_run {})", input.path, input.dry_run);
+
+    let bytes = fs::read(&input.path)?;
+    let statement = ofx::parse(&ofx::decode(&bytes))?;
+    run_import("import_transactions_ofx", input.account_id, input.category_id, input.dry_run, statement)
+}
+
+/// Importuje soubor QIF (sekce Bank, Cash, CCard) na zvolený účet.
+#[tauri::command]
+pub fn import_transactions_qif(input: StatementImportInput) -> Result<ImportResult> {
+    log::info!("import_transactions_qif called: {} (dry_run {})", input.path, input.dry_run);
+
+    let bytes = fs::read(&input.path)?;
+    let statement = qif::parse(&importers::decode_auto(&bytes), input.date_format.as_deref())?;
+    run_import("import_transactions_qif", input.account_id, input.category_id, input.dry_run, statement)
+}
+
+/// Importuje výpis ISO 20022 camt.053 (případně avízo camt.052/054) na zvolený účet.
+#[tauri::command]
+pub fn import_transactions_camt(input: StatementImportInput) -> Result<ImportResult> {
+    log::info!("import_transactions_camt called: {} (dry_run {})", input.path, input.dry_run);
+
+    let bytes = fs::read(&input.path)?;
+    let statement = camt::parse(&importers::decode_auto(&bytes))?;
+    run_import("import_transactions_camt", input.account_id, input.category_id, input.dry_run, statement)
+}
+
+/// Importuje výpis SWIFT MT940 na zvolený účet.
+#[tauri::command]
+pub fn import_transactions_mt940(input: StatementImportInput) -> Result<ImportResult> {
+    log::info!("import_transactions_mt940 called: {} (dry_run {})", input.path, input.dry_run);
+
+    let bytes = fs::read(&input.path)?;
+    let statement = mt940::parse(&importers::decode_auto(&bytes))?;
+    run_import("import_transactions_mt940", input.account_id, input.category_id, input.dry_run, statement)
+}
@@ -3,6 +3,8 @@ pub mod banks;
 pub mod accounts;
 pub mod categories;
 pub mod transactions;
+pub mod splits;
+pub mod shared_expenses;
 pub mod recurring;
 pub mod flows;
 pub mod goals;
@@ -10,12 +12,24 @@ pub mod backup;
 pub mod reports;
 pub mod household;
 pub mod financial_goals;
+pub mod exchange_rates;
+pub mod imports;
+pub mod duplicates;
+pub mod rules;
+pub mod payees;
+pub mod search;
+pub mod saved_views;
+pub mod reconciliation;
+pub mod payment_qr;
+pub mod schedules;
 
 pub use users::*;
 pub use banks::*;
 pub use accounts::*;
 pub use categories::*;
 pub use transactions::*;
+pub use splits::*;
+pub use shared_expenses::*;
 pub use recurring::*;
 pub use flows::*;
 pub use goals::*;
@@ -23,4 +37,14 @@ pub use backup::*;
 pub use reports::*;
 pub use household::*;
 pub use financial_goals::*;
+pub use exchange_rates::*;
+pub use imports::*;
+pub use duplicates::*;
+pub use rules::*;
+pub use payees::*;
+pub use search::*;
+pub use saved_views::*;
+pub use reconciliation::*;
+pub use payment_qr::*;
+pub use schedules::*;
 
@@ -0,0 +1,271 @@
+use crate::commands::transactions::query_transaction;
+use crate::db::connection::{get_connection, with_transaction};
+use crate::models::{Payee, PayeeAlias, PayeeAliasInput, RuleRunResult, SavePayeeInput, Transaction};
+use crate::utils::error::{AppError, Result};
+use regex::{Regex, RegexBuilder};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+
+const PAYEE_COLUMNS: &str =
+    "id, name, default_category_id, default_owner_user_id, created_at, updated_at";
+
+fn row_to_payee(row: &rusqlite::Row) -> rusqlite::Result<Payee> {
+    Ok(Payee {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        default_category_id: row.get(2)?,
+        default_owner_user_id: row.get(3)?,
+        aliases: Vec::new(),
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+fn load_aliases(conn: &Connection, payee_id: i64) -> Result<Vec<PayeeAlias>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, payee_id, pattern, is_regex FROM payee_aliases WHERE payee_id = ?1 ORDER BY id",
+    )?;
+    let aliases = stmt
+        .query_map([payee_id], |row| {
+            Ok(PayeeAlias {
+                id: row.get(0)?,
+                payee_id: row.get(1)?,
+                pattern: row.get(2)?,
+                is_regex: row.get::<_, i32>(3)? != 0,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(aliases)
+}
+
+fn query_payee(conn: &Connection, id: i64) -> Result<Payee> {
+    let mut payee = conn
+        .query_row(
+            &format!("SELECT {} FROM payees WHERE id = ?1", PAYEE_COLUMNS),
+            [id],
+            row_to_payee,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Obchodník {} neexistuje", id)))?;
+    payee.aliases = load_aliases(conn, id)?;
+    Ok(payee)
+}
+
+fn compile_alias(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| AppError::InvalidInput(format!("Neplatný regulární výraz: {}", e)))
+}
+
+fn validate_payee(input: &SavePayeeInput) -> Result<()> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Název obchodníka je povinný".to_string()));
+    }
+    for alias in input.aliases.iter().flatten() {
+        if alias.pattern.trim().is_empty() {
+            return Err(AppError::InvalidInput("Alias nesmí být prázdný".to_string()));
+        }
+        if alias.is_regex.unwrap_or(false) {
+            compile_alias(&alias.pattern)?;
+        }
+    }
+    Ok(())
+}
+
+fn replace_aliases(conn: &Connection, payee_id: i64, aliases: &[PayeeAliasInput]) -> Result<()> {
+    conn.execute("DELETE FROM payee_aliases WHERE payee_id = ?1", [payee_id])?;
+    for alias in aliases {
+        conn.execute(
+            "INSERT INTO payee_aliases (payee_id, pattern, is_regex) VALUES (?1, ?2, ?3)",
+            rusqlite::params![payee_id, alias.pattern.trim(), alias.is_regex.unwrap_or(false) as i32],
+        )?;
+    }
+    Ok(())
+}
+
+enum AliasPattern {
+    Text(String),
+    Regex(Regex),
+}
+
+/// Aliasy všech obchodníků a jejich výchozí kategorie a vlastník.
+pub(crate) struct PayeeMatchers {
+    defaults: HashMap<i64, (Option<i64>, Option<i64>)>,
+    aliases: Vec<(i64, AliasPattern)>,
+}
+
+impl PayeeMatchers {
+    /// Obchodník podle popisu nebo protiúčtu; při více shodách vyhraje nejdelší shoda.
+    fn find(&self, transaction: &Transaction) -> Option<i64> {
+        let texts: Vec<String> = [
+            transaction.description.as_deref(),
+            transaction.counterparty_account.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(str::to_lowercase)
+        .collect();
+
+        self.aliases
+            .iter()
+            .filter_map(|(payee_id, alias)| {
+                let matched = texts.iter().filter_map(|text| match alias {
+                    AliasPattern::Text(pattern) => text.contains(pattern.as_str()).then_some(pattern.len()),
+                    AliasPattern::Regex(regex) => regex.find(text).map(|m| m.len()),
+                });
+                matched.max().map(|len| (len, *payee_id))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, payee_id)| payee_id)
+    }
+}
+
+pub(crate) fn load_payee_matchers(conn: &Connection) -> Result<PayeeMatchers> {
+    let mut stmt = conn.prepare("SELECT id, default_category_id, default_owner_user_id FROM payees")?;
+    let defaults = stmt
+        .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+        .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+
+    let mut stmt = conn.prepare("SELECT payee_id, pattern, is_regex FROM payee_aliases ORDER BY id")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i32>(2)? != 0))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut aliases = Vec::new();
+    for (payee_id, pattern, is_regex) in rows {
+        let alias = if is_regex {
+            AliasPattern::Regex(compile_alias(&pattern)?)
+        } else {
+            AliasPattern::Text(pattern.to_lowercase())
+        };
+        aliases.push((payee_id, alias));
+    }
+    Ok(PayeeMatchers { defaults, aliases })
+}
+
+/// Přiřadí transakci obchodníka podle aliasů (ručně zvoleného bez `overwrite` ponechá)
+/// a doplní jeho výchozí kategorii a vlastníka, pokud chybí. Vrací, zda se něco změnilo.
+pub(crate) fn assign_payee(
+    conn: &Connection,
+    matchers: &PayeeMatchers,
+    transaction: &Transaction,
+    overwrite: bool,
+) -> Result<bool> {
+    let payee_id = match transaction.payee_id {
+        Some(id) if !overwrite => Some(id),
+        current => matchers.find(transaction).or(current),
+    };
+    let Some(payee_id) = payee_id else {
+        return Ok(false);
+    };
+
+    let (default_category, default_owner) =
+        matchers.defaults.get(&payee_id).copied().unwrap_or_default();
+    let category_id = transaction.category_id.or(default_category);
+    let owner_user_id = transaction.owner_user_id.or(default_owner);
+
+    if Some(payee_id) == transaction.payee_id
+        && category_id == transaction.category_id
+        && owner_user_id == transaction.owner_user_id
+    {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "UPDATE transactions SET payee_id = ?1, category_id = ?2, owner_user_id = ?3,
+         updated_at = CURRENT_TIMESTAMP WHERE id = ?4",
+        rusqlite::params![payee_id, category_id, owner_user_id, transaction.id],
+    )?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn get_payees() -> Result<Vec<Payee>> {
+    let conn = get_connection()?;
+
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM payees ORDER BY name", PAYEE_COLUMNS))?;
+    let mut payees = stmt
+        .query_map([], row_to_payee)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for payee in &mut payees {
+        payee.aliases = load_aliases(&conn, payee.id)?;
+    }
+    Ok(payees)
+}
+
+#[tauri::command]
+pub fn create_payee(input: SavePayeeInput) -> Result<Payee> {
+    validate_payee(&input)?;
+    with_transaction(|conn| {
+        conn.execute(
+            "INSERT INTO payees (name, default_category_id, default_owner_user_id) VALUES (?1, ?2, ?3)",
+            rusqlite::params![input.name.trim(), input.default_category_id, input.default_owner_user_id],
+        )?;
+        let id = conn.last_insert_rowid();
+        replace_aliases(conn, id, input.aliases.as_deref().unwrap_or_default())?;
+        query_payee(conn, id)
+    })
+}
+
+/// Upraví obchodníka; aliasy se nahradí, jen pokud jsou ve vstupu.
+#[tauri::command]
+pub fn update_payee(id: i64, input: SavePayeeInput) -> Result<Payee> {
+    validate_payee(&input)?;
+    with_transaction(|conn| {
+        query_payee(conn, id)?;
+        conn.execute(
+            "UPDATE payees SET name = ?1, default_category_id = ?2, default_owner_user_id = ?3,
+             updated_at = CURRENT_TIMESTAMP WHERE id = ?4",
+            rusqlite::params![input.name.trim(), input.default_category_id, input.default_owner_user_id, id],
+        )?;
+        if let Some(aliases) = &input.aliases {
+            replace_aliases(conn, id, aliases)?;
+        }
+        query_payee(conn, id)
+    })
+}
+
+#[tauri::command]
+pub fn delete_payee(id: i64) -> Result<()> {
+    let conn = get_connection()?;
+    conn.execute("DELETE FROM payees WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Přiřadí obchodníky transakcím v období podle aktuálních aliasů.
+#[tauri::command]
+pub fn assign_payees_to_range(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<RuleRunResult> {
+    with_transaction(|conn| {
+        let matchers = load_payee_matchers(conn)?;
+        let mut stmt = conn.prepare(
+            "SELECT id FROM transactions
+             WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+             ORDER BY date, id",
+        )?;
+        let ids = stmt
+            .query_map(rusqlite::params![start_date, end_date], |row| row.get::<_, i64>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut updated = 0;
+        for id in &ids {
+            let transaction = query_transaction(conn, *id)?;
+            if assign_payee(conn, &matchers, &transaction, overwrite.unwrap_or(false))? {
+                updated += 1;
+            }
+        }
+
+        log::info!("assign_payees_to_range: {} of {} transactions updated", updated, ids.len());
+        Ok(RuleRunResult {
+            checked: ids.len(),
+            updated,
+        })
+    })
+}
@@ -0,0 +1,254 @@
+use crate::db::connection::get_connection;
+use crate::models::{PaymentDraft, PaymentQr, PaymentQrInput};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use crate::utils::payment::{to_iban, validate_account};
+use crate::utils::schedule::Schedule;
+use crate::utils::spayd::Spayd;
+use base64::Engine;
+use chrono::NaiveDate;
+use qrcode::{EcLevel, QrCode};
+use rusqlite::{Connection, OptionalExtension};
+
+/// Velikost jednoho modulu QR kódu v PNG (px).
+const PNG_MODULE_SIZE: usize = 8;
+/// Tichá zóna kolem kódu v modulech.
+const QUIET_ZONE: usize = 4;
+
+/// Údaje převzaté ze zdrojového záznamu.
+#[derive(Default)]
+struct SourcePayment {
+    account: Option<String>,
+    amount: Option<Money>,
+    currency: Option<String>,
+    due_date: Option<NaiveDate>,
+    message: Option<String>,
+    recipient_name: Option<String>,
+    variable_symbol: Option<String>,
+    constant_symbol: Option<String>,
+    specific_symbol: Option<String>,
+    /// Opakování záznamu (pravidlo, frekvence, den v měsíci), z něhož plyne splatnost
+    due_rule: Option<(Option<String>, String, Option<i32>)>,
+}
+
+fn load_source(
+    conn: &Connection,
+    source_type: &str,
+    id: i64,
+    today: NaiveDate,
+) -> Result<SourcePayment> {
+    let not_found =
+        || AppError::NotFound(format!("Zdroj platby {} {} neexistuje", source_type, id));
+    let source = match source_type {
+        // Převod na účet domácnosti: příjemcem je cílový účet
+        "scheduled_transfer" => conn
+            .query_row(
+                "SELECT COALESCE(st.counterparty_account, a.account_number), st.amount, a.currency,
+                 st.day_of_month, COALESCE(st.description, st.name), a.name,
+                 st.variable_symbol, st.constant_symbol, st.specific_symbol, st.schedule
+                 FROM scheduled_transfers st
+                 LEFT JOIN accounts a ON a.id = st.to_account_id
+                 WHERE st.id = ?1",
+                [id],
+                |row| {
+                    Ok(SourcePayment {
+                        account: row.get(0)?,
+                        amount: row.get(1)?,
+                        currency: row.get(2)?,
+                        due_rule: Some((row.get(9)?, "monthly".to_string(), row.get(3)?)),
+                        message: row.get(4)?,
+                        recipient_name: row.get(5)?,
+                        variable_symbol: row.get(6)?,
+                        constant_symbol: row.get(7)?,
+                        specific_symbol: row.get(8)?,
+                        ..Default::default()
+                    })
+                },
+            )
+            .optional()?,
+        "fixed_expense" => conn
+            .query_row(
+                "SELECT fe.amount, a.currency, fe.day_of_month, fe.name, fe.schedule, fe.frequency
+                 FROM fixed_expenses fe
+                 LEFT JOIN accounts a ON a.id = fe.account_id
+                 WHERE fe.id = ?1",
+                [id],
+                |row| {
+                    Ok(SourcePayment {
+                        amount: row.get(0)?,
+                        currency: row.get(1)?,
+                        message: row.get(3)?,
+                        due_rule: Some((row.get(4)?, row.get(5)?, row.get(2)?)),
+                        ..Default::default()
+                    })
+                },
+            )
+            .optional()?,
+        "recurring_payment" => conn
+            .query_row(
+                "SELECT counterparty_account, amount, currency, next_execution_date,
+                 COALESCE(description, name), variable_symbol, constant_symbol, specific_symbol
+                 FROM recurring_payments WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(SourcePayment {
+                        account: row.get(0)?,
+                        amount: row.get(1)?,
+                        currency: row.get(2)?,
+                        due_date: row
+                            .get::<_, Option<String>>(3)?
+                            .and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()),
+                        message: row.get(4)?,
+                        variable_symbol: row.get(5)?,
+                        constant_symbol: row.get(6)?,
+                        specific_symbol: row.get(7)?,
+                        ..Default::default()
+                    })
+                },
+            )
+            .optional()?,
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "Neznámý zdroj platby '{}'",
+                other
+            )))
+        }
+    };
+    let mut source = source.ok_or_else(not_found)?;
+    if let Some((schedule, frequency, day)) = source.due_rule.take() {
+        source.due_date =
+            Schedule::stored_or_legacy(schedule.as_deref(), &frequency, 1, day, today)?
+                .next_on_or_after(today);
+    }
+    Ok(source)
+}
+
+fn filled(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+fn render_svg(code: &QrCode) -> String {
+    code.render::<qrcode::render::svg::Color>()
+        .min_dimensions(240, 240)
+        .build()
+}
+
+/// Černobílé PNG (8 bitů šedi) jako data URL.
+fn render_png(code: &QrCode) -> Result<String> {
+    let width = code.width();
+    let size = (width + 2 * QUIET_ZONE) * PNG_MODULE_SIZE;
+    let mut pixels = vec![255u8; size * size];
+    for (index, color) in code.to_colors().into_iter().enumerate() {
+        if color != qrcode::Color::Dark {
+            continue;
+        }
+        let x = (index % width + QUIET_ZONE) * PNG_MODULE_SIZE;
+        let y = (index / width + QUIET_ZONE) * PNG_MODULE_SIZE;
+        for row in y..y + PNG_MODULE_SIZE {
+            pixels[row * size + x..row * size + x + PNG_MODULE_SIZE].fill(0);
+        }
+    }
+
+    let png_error = |e: png::EncodingError| AppError::Internal(format!("Chyba zápisu PNG: {}", e));
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(png_error)?;
+    writer.write_image_data(&pixels).map_err(png_error)?;
+    writer.finish().map_err(png_error)?;
+
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(data)
+    ))
+}
+
+/// Vytvoří řetězec QR Platby (SPD) a jeho QR kód jako SVG nebo PNG.
+#[tauri::command]
+pub fn generate_payment_qr(input: PaymentQrInput) -> Result<PaymentQr> {
+    let today = chrono::Local::now().date_naive();
+    let source = match (&input.source_type, input.source_id) {
+        (Some(source_type), Some(id)) => {
+            let conn = get_connection()?;
+            load_source(&conn, source_type, id, today)?
+        }
+        (None, None) => SourcePayment::default(),
+        _ => {
+            return Err(AppError::InvalidInput(
+                "Zdroj platby vyžaduje typ i id".to_string(),
+            ))
+        }
+    };
+
+    let account = filled(input.account)
+        .or(filled(source.account))
+        .ok_or_else(|| AppError::InvalidInput("Chybí účet příjemce".to_string()))?;
+    let due_date =
+        match filled(input.due_date) {
+            Some(date) => Some(NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|_| {
+                AppError::InvalidInput(format!("Neplatné datum splatnosti: {}", date))
+            })?),
+            None => source.due_date,
+        };
+
+    let spayd = Spayd {
+        iban: to_iban(&account)?,
+        bic: filled(input.bic),
+        amount: input.amount.or(source.amount),
+        currency: filled(input.currency).or(source.currency),
+        due_date,
+        message: filled(input.message).or(filled(source.message)),
+        recipient_name: filled(input.recipient_name).or(filled(source.recipient_name)),
+        variable_symbol: filled(input.variable_symbol).or(source.variable_symbol),
+        constant_symbol: filled(input.constant_symbol).or(source.constant_symbol),
+        specific_symbol: filled(input.specific_symbol).or(source.specific_symbol),
+    }
+    .encode()?;
+
+    // Doporučená úroveň opravy chyb pro QR Platbu je M
+    let code = QrCode::with_error_correction_level(spayd.as_bytes(), EcLevel::M)
+        .map_err(|e| AppError::InvalidInput(format!("QR kód nelze vytvořit: {}", e)))?;
+    let (mime_type, image) = match input.format.as_deref().unwrap_or("svg") {
+        "svg" => ("image/svg+xml", render_svg(&code)),
+        "png" => ("image/png", render_png(&code)?),
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "Neznámý formát QR kódu '{}'",
+                other
+            )))
+        }
+    };
+
+    Ok(PaymentQr {
+        spayd,
+        mime_type: mime_type.to_string(),
+        image,
+    })
+}
+
+/// Načte řetězec QR Platby (např. z naskenované faktury) jako předvyplněnou platbu.
+#[tauri::command]
+pub fn parse_payment_qr(text: String) -> Result<PaymentDraft> {
+    let spayd = Spayd::parse(&text)?;
+    let today = chrono::Local::now().date_naive();
+    let date = spayd.due_date.unwrap_or(today);
+
+    Ok(PaymentDraft {
+        date: date.format("%Y-%m-%d").to_string(),
+        amount: spayd.amount,
+        // Bez CC je platba podle standardu v korunách
+        currency: spayd.currency.unwrap_or_else(|| "CZK".to_string()),
+        transaction_type: "expense".to_string(),
+        status: if date > today { "planned" } else { "completed" }.to_string(),
+        description: spayd.message,
+        counterparty_account: validate_account(&spayd.iban)?,
+        iban: spayd.iban,
+        bic: spayd.bic,
+        recipient_name: spayd.recipient_name,
+        due_date: spayd.due_date.map(|d| d.format("%Y-%m-%d").to_string()),
+        variable_symbol: spayd.variable_symbol,
+        constant_symbol: spayd.constant_symbol,
+        specific_symbol: spayd.specific_symbol,
+    })
+}
@@ -0,0 +1,291 @@
+use crate::commands::transactions::{row_to_transaction, TRANSACTION_COLUMNS};
+use crate::db::connection::{get_connection, with_transaction};
+use crate::db::ledger::cleared_balance;
+use crate::models::{Reconciliation, StartReconciliationInput, Transaction};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension};
+
+const RECONCILIATION_COLUMNS: &str = "id, account_id, statement_date, statement_balance,
+    cleared_balance, status, created_at, finished_at";
+
+/// Transakce účtu `?1` (na kterékoli straně převodu).
+const ACCOUNT_CONDITION: &str = "(from_account_id = ?1 OR to_account_id = ?1)";
+
+fn query_reconciliation(conn: &Connection, id: i64) -> Result<Reconciliation> {
+    let mut reconciliation = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM reconciliations WHERE id = ?1",
+                RECONCILIATION_COLUMNS
+            ),
+            [id],
+            |row| {
+                Ok(Reconciliation {
+                    id: row.get(0)?,
+                    account_id: row.get(1)?,
+                    statement_date: row.get(2)?,
+                    statement_balance: row.get(3)?,
+                    cleared_balance: row.get::<_, Option<Money>>(4)?.unwrap_or_default(),
+                    difference: Money::ZERO,
+                    cleared_count: 0,
+                    status: row.get(5)?,
+                    created_at: row.get(6)?,
+                    finished_at: row.get(7)?,
+                })
+            },
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Odsouhlasení {} neexistuje", id)))?;
+
+    if reconciliation.status == "open" {
+        reconciliation.cleared_balance = cleared_balance(conn, reconciliation.account_id)?;
+        reconciliation.cleared_count = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM transactions
+                 WHERE {} AND status != 'planned' AND reconciliation_status = 'cleared'",
+                ACCOUNT_CONDITION
+            ),
+            [reconciliation.account_id],
+            |row| row.get(0),
+        )?;
+    } else {
+        reconciliation.cleared_count = conn.query_row(
+            "SELECT COUNT(*) FROM transactions WHERE reconciliation_id = ?1",
+            [id],
+            |row| row.get(0),
+        )?;
+    }
+    reconciliation.difference = reconciliation.statement_balance - reconciliation.cleared_balance;
+    Ok(reconciliation)
+}
+
+fn query_open(conn: &Connection, id: i64) -> Result<Reconciliation> {
+    let reconciliation = query_reconciliation(conn, id)?;
+    if reconciliation.status != "open" {
+        return Err(AppError::InvalidInput(
+            "Odsouhlasení je již dokončené".to_string(),
+        ));
+    }
+    Ok(reconciliation)
+}
+
+fn validate_date(date: &str) -> Result<()> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| AppError::InvalidInput(format!("Neplatné datum výpisu '{}'", date)))
+}
+
+/// Zahájí odsouhlasení účtu; na účtu smí být nejvýše jedno otevřené.
+#[tauri::command]
+pub fn start_reconciliation(input: StartReconciliationInput) -> Result<Reconciliation> {
+    validate_date(&input.statement_date)?;
+    with_transaction(|conn| {
+        let account: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM accounts WHERE id = ?1",
+                [input.account_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if account.is_none() {
+            return Err(AppError::NotFound(format!(
+                "Účet {} neexistuje",
+                input.account_id
+            )));
+        }
+
+        let open: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM reconciliations WHERE account_id = ?1 AND status = 'open'",
+            [input.account_id],
+            |row| row.get(0),
+        )?;
+        if open > 0 {
+            return Err(AppError::InvalidInput(
+                "Účet už má otevřené odsouhlasení".to_string(),
+            ));
+        }
+
+        conn.execute(
+            "INSERT INTO reconciliations (account_id, statement_date, statement_balance)
+             VALUES (?1, ?2, ?3)",
+            rusqlite::params![
+                input.account_id,
+                input.statement_date,
+                input.statement_balance
+            ],
+        )?;
+        query_reconciliation(conn, conn.last_insert_rowid())
+    })
+}
+
+#[tauri::command]
+pub fn get_reconciliation(id: i64) -> Result<Reconciliation> {
+    let conn = get_connection()?;
+    query_reconciliation(&conn, id)
+}
+
+/// Odsouhlasení účtu od nejnovějšího výpisu.
+#[tauri::command]
+pub fn get_reconciliations(account_id: i64) -> Result<Vec<Reconciliation>> {
+    let conn = get_connection()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id FROM reconciliations WHERE account_id = ?1
+         ORDER BY statement_date DESC, id DESC",
+    )?;
+    let ids = stmt
+        .query_map([account_id], |row| row.get::<_, i64>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    ids.into_iter()
+        .map(|id| query_reconciliation(&conn, id))
+        .collect()
+}
+
+/// Transakce k odškrtnutí (neodsouhlasené do data výpisu a všechny odškrtnuté),
+/// u dokončeného odsouhlasení transakce, které uzamklo.
+#[tauri::command]
+pub fn get_reconciliation_transactions(id: i64) -> Result<Vec<Transaction>> {
+    let conn = get_connection()?;
+    let reconciliation = query_reconciliation(&conn, id)?;
+
+    let (condition, params) = if reconciliation.status == "open" {
+        (
+            format!(
+                "{} AND status != 'planned' AND (reconciliation_status = 'cleared'
+                 OR (reconciliation_status = 'uncleared' AND SUBSTR(date, 1, 10) <= ?2))",
+                ACCOUNT_CONDITION
+            ),
+            rusqlite::params![reconciliation.account_id, reconciliation.statement_date].to_vec(),
+        )
+    } else {
+        (
+            "reconciliation_id = ?1".to_string(),
+            rusqlite::params![id].to_vec(),
+        )
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM transactions WHERE {} ORDER BY date, id",
+        TRANSACTION_COLUMNS, condition
+    ))?;
+    let transactions = stmt
+        .query_map(params.as_slice(), row_to_transaction)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(transactions)
+}
+
+/// Odškrtne (nebo vrátí) transakce proti výpisu; odsouhlasené se nemění.
+#[tauri::command]
+pub fn set_transactions_cleared(transaction_ids: Vec<i64>, cleared: bool) -> Result<()> {
+    with_transaction(|conn| {
+        for &id in &transaction_ids {
+            let status: Option<String> = conn
+                .query_row(
+                    "SELECT reconciliation_status FROM transactions WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match status.as_deref() {
+                None => {
+                    return Err(AppError::NotFound(format!("Transakce {} neexistuje", id)));
+                }
+                Some("reconciled") => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Transakce {} je odsouhlasená s výpisem, nejprve ji odemkněte",
+                        id
+                    )));
+                }
+                Some(_) => {}
+            }
+            conn.execute(
+                "UPDATE transactions SET reconciliation_status = ?1 WHERE id = ?2",
+                rusqlite::params![if cleared { "cleared" } else { "uncleared" }, id],
+            )?;
+        }
+        Ok(())
+    })
+}
+
+/// Opraví datum a konečný zůstatek výpisu otevřeného odsouhlasení.
+#[tauri::command]
+pub fn update_reconciliation(
+    id: i64,
+    statement_date: String,
+    statement_balance: Money,
+) -> Result<Reconciliation> {
+    validate_date(&statement_date)?;
+    with_transaction(|conn| {
+        query_open(conn, id)?;
+        conn.execute(
+            "UPDATE reconciliations SET statement_date = ?1, statement_balance = ?2 WHERE id = ?3",
+            rusqlite::params![statement_date, statement_balance, id],
+        )?;
+        query_reconciliation(conn, id)
+    })
+}
+
+/// Dokončí odsouhlasení při nulovém rozdílu a uzamkne odškrtnuté transakce.
+#[tauri::command]
+pub fn finish_reconciliation(id: i64) -> Result<Reconciliation> {
+    with_transaction(|conn| {
+        let reconciliation = query_open(conn, id)?;
+        if !reconciliation.difference.is_zero() {
+            return Err(AppError::InvalidInput(format!(
+                "Odsouhlasení nelze dokončit, rozdíl proti výpisu je {}",
+                reconciliation.difference
+            )));
+        }
+
+        conn.execute(
+            &format!(
+                "UPDATE transactions SET reconciliation_status = 'reconciled', reconciliation_id = ?2
+                 WHERE {} AND status != 'planned' AND reconciliation_status = 'cleared'",
+                ACCOUNT_CONDITION
+            ),
+            [reconciliation.account_id, id],
+        )?;
+        conn.execute(
+            "UPDATE reconciliations SET status = 'finished', cleared_balance = ?1,
+             finished_at = CURRENT_TIMESTAMP WHERE id = ?2",
+            rusqlite::params![reconciliation.cleared_balance, id],
+        )?;
+
+        log::info!(
+            "finish_reconciliation: account {} reconciled at {}",
+            reconciliation.account_id,
+            reconciliation.statement_date
+        );
+        query_reconciliation(conn, id)
+    })
+}
+
+/// Zruší otevřené odsouhlasení; odškrtnutí transakcí zůstane.
+#[tauri::command]
+pub fn cancel_reconciliation(id: i64) -> Result<()> {
+    with_transaction(|conn| {
+        query_open(conn, id)?;
+        conn.execute("DELETE FROM reconciliations WHERE id = ?1", [id])?;
+        Ok(())
+    })
+}
+
+/// Odemkne odsouhlasenou transakci pro opravu (vrátí ji mezi odškrtnuté).
+#[tauri::command]
+pub fn unlock_transaction(id: i64) -> Result<()> {
+    let conn = get_connection()?;
+    let updated = conn.execute(
+        "UPDATE transactions SET reconciliation_status = 'cleared'
+         WHERE id = ?1 AND reconciliation_status = 'reconciled'",
+        [id],
+    )?;
+    if updated == 0 {
+        return Err(AppError::InvalidInput(format!(
+            "Transakce {} není odsouhlasená",
+            id
+        )));
+    }
+    Ok(())
+}
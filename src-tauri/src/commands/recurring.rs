@@ -1,26 +1,101 @@
-use crate::db::connection::get_connection;
-use crate::models::{CreateRecurringPaymentInput, RecurringPayment, UpdateRecurringPaymentInput};
+use crate::commands::duplicates::absorb_duplicate;
+use crate::commands::transactions::{
+    insert_transaction, row_to_transaction, Categorizer, TRANSACTION_COLUMNS,
+};
+use crate::db::connection::{get_connection, with_transaction};
+use crate::db::currency::resolve_currency;
+use crate::models::{
+    CreateRecurringPaymentInput, CreateTransactionInput, RecurringOccurrence, RecurringPayment,
+    RecurringProcessReport, Transaction, UpdateRecurringPaymentInput,
+};
 use crate::utils::error::Result;
-use chrono::{Datelike, Duration, NaiveDate, Utc};
+use crate::utils::payment::PaymentDetails;
+use crate::utils::schedule::Schedule;
+use chrono::{NaiveDate, Utc};
+use rusqlite::{Connection, OptionalExtension};
+
+fn row_to_payment(row: &rusqlite::Row) -> rusqlite::Result<RecurringPayment> {
+    Ok(RecurringPayment {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        amount: row.get(2)?,
+        currency: row.get(3)?,
+        frequency: row.get(4)?,
+        frequency_value: row.get(5)?,
+        day_of_period: row.get(6)?,
+        account_id: row.get(7)?,
+        category_id: row.get(8)?,
+        description: row.get(9)?,
+        active: row.get::<_, i32>(10)? != 0,
+        next_execution_date: row.get(11)?,
+        last_execution_date: row.get(12)?,
+        created_at: row.get(13)?,
+        updated_at: row.get(14)?,
+        variable_symbol: row.get(15)?,
+        constant_symbol: row.get(16)?,
+        specific_symbol: row.get(17)?,
+        counterparty_account: row.get(18)?,
+        schedule: row.get(19)?,
+    })
+}
+
+/// Jak daleko od data platby se hledá vygenerovaný termín, který platba nahradí.
+const MATCH_WINDOW_DAYS: i64 = 31;
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Pravidlo opakování platby; u záznamu bez něj se odvodí z frekvence od příštího termínu.
+pub(crate) fn payment_schedule(payment: &RecurringPayment) -> Result<Schedule> {
+    let start = payment
+        .next_execution_date
+        .as_deref()
+        .and_then(parse_date)
+        .or_else(|| parse_date(&payment.created_at))
+        .unwrap_or_else(|| Utc::now().date_naive());
+    Schedule::stored_or_legacy(
+        payment.schedule.as_deref(),
+        &payment.frequency,
+        payment.frequency_value,
+        payment.day_of_period,
+        start,
+    )
+}
 
 #[tauri::command]
 pub fn create_recurring_payment(input: CreateRecurringPaymentInput) -> Result<RecurringPayment> {
-    let conn = get_connection()?;
-
-    let next_date = calculate_next_execution_date(
+    let details = PaymentDetails::validated(
+        input.variable_symbol.as_deref(),
+        input.constant_symbol.as_deref(),
+        input.specific_symbol.as_deref(),
+        input.counterparty_account.as_deref(),
+    )?;
+    let today = Utc::now().date_naive();
+    let schedule = Schedule::stored_or_legacy(
+        input.schedule.as_deref(),
         &input.frequency,
         input.frequency_value.unwrap_or(1),
         input.day_of_period,
-    );
+        today,
+    )?;
+    let next_date = schedule.next_on_or_after(today).map(format_date);
+    let conn = get_connection()?;
+    let currency = resolve_currency(&conn, input.currency, Some(input.account_id))?;
 
     conn.execute(
         "INSERT INTO recurring_payments (name, amount, currency, frequency, frequency_value,
-         day_of_period, account_id, category_id, description, active, next_execution_date)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 1, ?10)",
+         day_of_period, account_id, category_id, description, active, next_execution_date,
+         variable_symbol, constant_symbol, specific_symbol, counterparty_account, schedule)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 1, ?10, ?11, ?12, ?13, ?14, ?15)",
         rusqlite::params![
             input.name,
             input.amount,
-            input.currency.unwrap_or_else(|| "CZK".to_string()),
+            currency,
             input.frequency,
             input.frequency_value.unwrap_or(1),
             input.day_of_period,
@@ -28,10 +103,16 @@ pub fn create_recurring_payment(input: CreateRecurringPaymentInput) -> Result<Re
             input.category_id,
             input.description,
             next_date,
+            details.variable_symbol,
+            details.constant_symbol,
+            details.specific_symbol,
+            details.counterparty_account,
+            schedule.to_string(),
         ],
     )?;
 
     let id = conn.last_insert_rowid();
+    drop(conn);
     get_recurring_payment_by_id(id)
 }
 
@@ -41,25 +122,7 @@ fn get_recurring_payment_by_id(id: i64) -> Result<RecurringPayment> {
     conn.query_row(
         "SELECT * FROM recurring_payments WHERE id = ?1",
         [id],
-        |row| {
-            Ok(RecurringPayment {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                amount: row.get(2)?,
-                currency: row.get(3)?,
-                frequency: row.get(4)?,
-                frequency_value: row.get(5)?,
-                day_of_period: row.get(6)?,
-                account_id: row.get(7)?,
-                category_id: row.get(8)?,
-                description: row.get(9)?,
-                active: row.get::<_, i32>(10)? != 0,
-                next_execution_date: row.get(11)?,
-                last_execution_date: row.get(12)?,
-                created_at: row.get(13)?,
-                updated_at: row.get(14)?,
-            })
-        },
+        row_to_payment,
     )
     .map_err(Into::into)
 }
@@ -70,25 +133,7 @@ pub fn get_recurring_payments() -> Result<Vec<RecurringPayment>> {
 
     let mut stmt = conn.prepare("SELECT * FROM recurring_payments ORDER BY name")?;
     let payments = stmt
-        .query_map([], |row| {
-            Ok(RecurringPayment {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                amount: row.get(2)?,
-                currency: row.get(3)?,
-                frequency: row.get(4)?,
-                frequency_value: row.get(5)?,
-                day_of_period: row.get(6)?,
-                account_id: row.get(7)?,
-                category_id: row.get(8)?,
-                description: row.get(9)?,
-                active: row.get::<_, i32>(10)? != 0,
-                next_execution_date: row.get(11)?,
-                last_execution_date: row.get(12)?,
-                created_at: row.get(13)?,
-                updated_at: row.get(14)?,
-            })
-        })?
+        .query_map([], row_to_payment)?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
     Ok(payments)
@@ -99,13 +144,45 @@ pub fn update_recurring_payment(
     id: i64,
     input: UpdateRecurringPaymentInput,
 ) -> Result<RecurringPayment> {
+    let details = PaymentDetails::validated(
+        input.variable_symbol.as_deref(),
+        input.constant_symbol.as_deref(),
+        input.specific_symbol.as_deref(),
+        input.counterparty_account.as_deref(),
+    )?;
     let conn = get_connection()?;
+    let today = Utc::now().date_naive();
+
+    // Další termín podle (případně změněného) pravidla: po posledním provedení,
+    // jinak od dosavadního termínu, aby zmeškaný výskyt nezmizel
+    let (next_execution, last_execution): (Option<String>, Option<String>) = conn.query_row(
+        "SELECT next_execution_date, last_execution_date FROM recurring_payments WHERE id = ?1",
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let current_next = next_execution
+        .as_deref()
+        .and_then(parse_date)
+        .unwrap_or(today);
+    let schedule = Schedule::stored_or_legacy(
+        input.schedule.as_deref(),
+        &input.frequency,
+        input.frequency_value,
+        input.day_of_period,
+        current_next,
+    )?;
+    let next_date = match last_execution.as_deref().and_then(parse_date) {
+        Some(last) => schedule.next_after(last),
+        None => schedule.next_on_or_after(current_next),
+    };
 
     conn.execute(
         "UPDATE recurring_payments SET name = ?1, amount = ?2, currency = ?3, frequency = ?4,
          frequency_value = ?5, day_of_period = ?6, account_id = ?7, category_id = ?8,
-         description = ?9, active = ?10
-         WHERE id = ?11",
+         description = ?9, active = ?10, variable_symbol = ?11, constant_symbol = ?12,
+         specific_symbol = ?13, counterparty_account = ?14, schedule = ?15,
+         next_execution_date = ?16
+         WHERE id = ?17",
         rusqlite::params![
             input.name,
             input.amount,
@@ -117,10 +194,17 @@ pub fn update_recurring_payment(
             input.category_id,
             input.description,
             input.active as i32,
+            details.variable_symbol,
+            details.constant_symbol,
+            details.specific_symbol,
+            details.counterparty_account,
+            schedule.to_string(),
+            next_date.map(format_date),
             id
         ],
     )?;
 
+    drop(conn);
     get_recurring_payment_by_id(id)
 }
 
@@ -133,106 +217,318 @@ pub fn delete_recurring_payment(id: i64) -> Result<()> {
     Ok(())
 }
 
+/// Založí transakce všech splatných termínů, včetně zmeškaných (aplikace byla zavřená),
+/// každou s datem svého termínu. Termín, k němuž transakce už existuje, přeskočí.
 #[tauri::command]
-pub fn process_recurring_payments() -> Result<()> {
-    let conn = get_connection()?;
-    let today = Utc::now().format("%Y-%m-%d").to_string();
+pub fn process_recurring_payments() -> Result<RecurringProcessReport> {
+    let today = Utc::now().date_naive();
 
-    let mut stmt = conn.prepare(
-        "SELECT * FROM recurring_payments WHERE active = 1 AND next_execution_date <= ?1",
-    )?;
+    with_transaction(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT * FROM recurring_payments WHERE active = 1 AND next_execution_date <= ?1",
+        )?;
 
-    let payments: Vec<RecurringPayment> = stmt
-        .query_map([&today], |row| {
-            Ok(RecurringPayment {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                amount: row.get(2)?,
-                currency: row.get(3)?,
-                frequency: row.get(4)?,
-                frequency_value: row.get(5)?,
-                day_of_period: row.get(6)?,
-                account_id: row.get(7)?,
-                category_id: row.get(8)?,
-                description: row.get(9)?,
-                active: row.get::<_, i32>(10)? != 0,
-                next_execution_date: row.get(11)?,
-                last_execution_date: row.get(12)?,
-                created_at: row.get(13)?,
-                updated_at: row.get(14)?,
-            })
-        })?
-        .collect::<std::result::Result<Vec<_>, _>>()?;
+        let payments: Vec<RecurringPayment> = stmt
+            .query_map([format_date(today)], row_to_payment)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-    for payment in payments {
-        // Vytvořit transakci
-        conn.execute(
-            "INSERT INTO transactions (date, amount, currency, transaction_type, from_account_id,
-             category_id, description, status, recurring_payment_id)
-             VALUES (?1, ?2, ?3, 'expense', ?4, ?5, ?6, 'completed', ?7)",
-            rusqlite::params![
-                today,
-                payment.amount,
-                payment.currency,
-                payment.account_id,
-                payment.category_id,
-                payment.description,
-                payment.id,
-            ],
-        )?;
+        let categorizer = Categorizer::load(conn)?;
+        let mut report = RecurringProcessReport {
+            created: Vec::new(),
+            skipped: 0,
+        };
+        for payment in payments {
+            let schedule = payment_schedule(&payment)?;
+            let mut due = payment.next_execution_date.as_deref().and_then(parse_date);
+            let mut last_occurrence = None;
 
-        // Aktualizovat zůstatek účtu
-        conn.execute(
-            "UPDATE accounts SET current_balance = current_balance - ?1 WHERE id = ?2",
-            rusqlite::params![payment.amount, payment.account_id],
-        )?;
+            while let Some(occurrence) = due.filter(|date| *date <= today) {
+                let occurrence_date = format_date(occurrence);
+                let exists: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM transactions
+                     WHERE recurring_payment_id = ?1 AND occurrence_date = ?2)",
+                    rusqlite::params![payment.id, occurrence_date],
+                    |row| row.get(0),
+                )?;
 
-        // Aktualizovat recurring payment
-        let next_date = calculate_next_execution_date(
-            &payment.frequency,
-            payment.frequency_value,
-            payment.day_of_period,
-        );
+                if exists {
+                    report.skipped += 1;
+                } else {
+                    // Stejnou cestou jako ruční zadání: otisk, pravidla, obchodník i zůstatky
+                    let transaction = insert_transaction(
+                        conn,
+                        &categorizer,
+                        &CreateTransactionInput {
+                            date: occurrence_date.clone(),
+                            amount: payment.amount,
+                            currency: Some(payment.currency.clone()),
+                            transaction_type: "expense".to_string(),
+                            from_account_id: Some(payment.account_id),
+                            to_account_id: None,
+                            category_id: payment.category_id,
+                            description: payment.description.clone(),
+                            owner_user_id: None,
+                            is_shared: None,
+                            status: Some("completed".to_string()),
+                            notes: None,
+                            destination_amount: None,
+                            exchange_rate: None,
+                            fee_amount: None,
+                            external_id: None,
+                            value_date: None,
+                            variable_symbol: payment.variable_symbol.clone(),
+                            constant_symbol: payment.constant_symbol.clone(),
+                            specific_symbol: payment.specific_symbol.clone(),
+                            counterparty_account: payment.counterparty_account.clone(),
+                            payee_id: None,
+                            recurring_payment_id: Some(payment.id),
+                            occurrence_date: Some(occurrence_date.clone()),
+                        },
+                    )?;
+                    let transaction_id = transaction.id;
 
-        conn.execute(
-            "UPDATE recurring_payments SET last_execution_date = ?1, next_execution_date = ?2 WHERE id = ?3",
-            rusqlite::params![today, next_date, payment.id],
-        )?;
-    }
+                    report.created.push(RecurringOccurrence {
+                        recurring_payment_id: payment.id,
+                        name: payment.name.clone(),
+                        occurrence_date,
+                        transaction_id,
+                        amount: payment.amount,
+                        currency: payment.currency.clone(),
+                    });
+                }
 
-    Ok(())
+                last_occurrence = Some(occurrence);
+                // Další termín podle pravidla od právě zpracovaného výskytu, ne od dneška
+                due = schedule.next_after(occurrence);
+            }
+
+            conn.execute(
+                "UPDATE recurring_payments SET last_execution_date = COALESCE(?1, last_execution_date),
+                 next_execution_date = ?2 WHERE id = ?3",
+                rusqlite::params![
+                    last_occurrence.map(format_date),
+                    due.map(format_date),
+                    payment.id
+                ],
+            )?;
+        }
+
+        Ok(report)
+    })
 }
 
-fn calculate_next_execution_date(
-    frequency: &str,
-    frequency_value: i32,
-    day_of_period: Option<i32>,
-) -> String {
-    let today = Utc::now().date_naive();
+/// Spáruje odchozí platbu (např. z importu výpisu) s očekávanou pravidelnou platbou
+/// ze stejného účtu: podle variabilního symbolu (bez úvodních nul) a protiúčtu, nebo
+/// jen podle protiúčtu a shodné částky.
+///
+/// Platba uhradí termín: nahradí transakci vygenerovanou k termínu nejbližšímu datu
+/// platby (do `MATCH_WINDOW_DAYS`), jinak uhradí příští termín a posune ho, aby ho
+/// `process_recurring_payments` nezaložil znovu. Platba starší než poslední provedení
+/// se jen propojí.
+pub(crate) fn match_recurring_payment(
+    conn: &Connection,
+    transaction: &Transaction,
+) -> Result<bool> {
+    let Some(account_id) = transaction.from_account_id else {
+        return Ok(false);
+    };
+    if transaction.recurring_payment_id.is_some()
+        || transaction.transaction_type != "expense"
+        || transaction.status == "planned"
+        || (transaction.variable_symbol.is_none() && transaction.counterparty_account.is_none())
+    {
+        return Ok(false);
+    }
 
-    let next = match frequency {
-        "daily" => today + Duration::days(frequency_value as i64),
-        "weekly" => today + Duration::weeks(frequency_value as i64),
-        "monthly" => {
-            let day = day_of_period.unwrap_or(today.day() as i32);
-            let mut next_month = today.month() + frequency_value as u32;
-            let mut next_year = today.year();
-
-            while next_month > 12 {
-                next_month -= 12;
-                next_year += 1;
-            }
+    let payment = conn
+        .query_row(
+            "SELECT * FROM recurring_payments
+             WHERE active = 1 AND account_id = ?1 AND currency = ?2
+               AND (variable_symbol IS NOT NULL OR counterparty_account IS NOT NULL)
+               AND (variable_symbol IS NULL OR LTRIM(variable_symbol, '0') = LTRIM(?3, '0'))
+               AND (counterparty_account IS NULL OR counterparty_account = ?4)
+               AND (variable_symbol IS NOT NULL OR amount = ?5)
+             ORDER BY (variable_symbol IS NOT NULL) + (counterparty_account IS NOT NULL) DESC, id
+             LIMIT 1",
+            rusqlite::params![
+                account_id,
+                transaction.currency,
+                transaction.variable_symbol,
+                transaction.counterparty_account,
+                transaction.amount,
+            ],
+            row_to_payment,
+        )
+        .optional()?;
+    let Some(payment) = payment else {
+        return Ok(false);
+    };
 
-            NaiveDate::from_ymd_opt(next_year, next_month, day.min(28) as u32)
-                .unwrap_or(today + Duration::days(30))
+    let Some(paid) = parse_date(&transaction.date) else {
+        return Ok(false);
+    };
+    let generated = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM transactions
+                 WHERE recurring_payment_id = ?1 AND recurring_generated = 1 AND id != ?2
+                   AND ABS(julianday(occurrence_date) - julianday(?3)) <= ?4
+                 ORDER BY ABS(julianday(occurrence_date) - julianday(?3)), occurrence_date
+                 LIMIT 1",
+                TRANSACTION_COLUMNS
+            ),
+            rusqlite::params![
+                payment.id,
+                transaction.id,
+                format_date(paid),
+                MATCH_WINDOW_DAYS
+            ],
+            row_to_transaction,
+        )
+        .optional()?
+        .filter(|generated| generated.reconciliation_status != "reconciled");
+    if let Some(generated) = &generated {
+        // Rozpad nebo pevné podíly k jiné částce nejde převzít, sloučí se ručně
+        let fixed_allocation: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM transaction_splits WHERE transaction_id = ?1)
+                 OR EXISTS (SELECT 1 FROM shared_expense_splits
+                            WHERE transaction_id = ?1 AND percentage IS NULL)",
+            [generated.id],
+            |row| row.get(0),
+        )?;
+        if fixed_allocation && generated.amount != transaction.amount {
+            return Ok(false);
         }
-        "yearly" => {
-            NaiveDate::from_ymd_opt(today.year() + frequency_value, today.month(), today.day())
-                .unwrap_or(today + Duration::days(365))
+    }
+
+    let due = payment.next_execution_date.as_deref().and_then(parse_date);
+    let last = payment.last_execution_date.as_deref().and_then(parse_date);
+    let occurrence = match generated {
+        // Skutečná platba nahradí transakci vygenerovanou za ni i s jejím dopadem na zůstatek,
+        // štítky, rozpadem a úpravami
+        Some(generated) => {
+            absorb_duplicate(conn, transaction, &generated)?;
+            generated.occurrence_date
         }
-        _ => today + Duration::days(frequency_value as i64),
+        None if last.map_or(true, |last| paid >= last) => match due {
+            Some(due) => {
+                conn.execute(
+                    "UPDATE recurring_payments SET last_execution_date = ?1, next_execution_date = ?2
+                     WHERE id = ?3",
+                    rusqlite::params![
+                        transaction.date,
+                        payment_schedule(&payment)?.next_after(due).map(format_date),
+                        payment.id
+                    ],
+                )?;
+                Some(format_date(due))
+            }
+            None => None,
+        },
+        None => None,
     };
 
-    next.format("%Y-%m-%d").to_string()
+    conn.execute(
+        "UPDATE transactions SET recurring_payment_id = ?1, occurrence_date = ?2,
+         updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+        rusqlite::params![payment.id, occurrence, transaction.id],
+    )?;
+    Ok(true)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::transactions::query_transaction;
+    use crate::db::migrations::run_migrations;
+
+    /// Vygenerovaný termín nájmu (50 Kč, id 1) se štítkem, poznámkou a rozdělením 60/40
+    /// a skutečná platba 52 Kč s variabilním symbolem (id 2); obě už jsou v zůstatku.
+    fn setup() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, name) VALUES (11, 'Petr'), (12, 'Jana');
+             INSERT INTO accounts (id, name, account_type, initial_balance, current_balance)
+             VALUES (1, 'A', 'checking', 100000, 89800);
+             INSERT INTO tags (id, name) VALUES (1, 'byt');
+             INSERT INTO recurring_payments (id, name, amount, frequency, account_id, variable_symbol)
+             VALUES (1, 'Nájem', 5000, 'monthly', 1, '42');
+             INSERT INTO transactions (id, date, amount, transaction_type, status, from_account_id,
+                                       notes, recurring_payment_id, occurrence_date,
+                                       recurring_generated, is_shared)
+             VALUES (1, '2024-03-01', 5000, 'expense', 'completed', 1, 'zálohy', 1, '2024-03-01',
+                     1, 1);
+             INSERT INTO transactions (id, date, amount, transaction_type, status, from_account_id,
+                                       variable_symbol)
+             VALUES (2, '2024-03-02', 5200, 'expense', 'completed', 1, '0042');
+             INSERT INTO transaction_tags (transaction_id, tag_id) VALUES (1, 1);
+             INSERT INTO shared_expense_splits (transaction_id, user_id, amount, percentage)
+             VALUES (1, 11, 3000, 60), (1, 12, 2000, 40);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn balance(conn: &Connection) -> i64 {
+        conn.query_row(
+            "SELECT current_balance FROM accounts WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn payment_takes_over_generated_occurrence() {
+        let conn = setup();
+        let payment = query_transaction(&conn, 2).unwrap();
+        assert!(match_recurring_payment(&conn, &payment).unwrap());
+
+        let matched = query_transaction(&conn, 2).unwrap();
+        assert_eq!(matched.recurring_payment_id, Some(1));
+        assert_eq!(matched.occurrence_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(matched.notes.as_deref(), Some("zálohy"));
+        assert!(matched.is_shared);
+        assert!(query_transaction(&conn, 1).is_err());
+        assert_eq!(balance(&conn), 94800);
+
+        let tags: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM transaction_tags WHERE transaction_id = 2",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tags, 1);
+        let mut stmt = conn
+            .prepare(
+                "SELECT amount FROM shared_expense_splits WHERE transaction_id = 2 ORDER BY id",
+            )
+            .unwrap();
+        let shares: Vec<i64> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(shares, [3120, 2080]);
+    }
+
+    #[test]
+    fn split_occurrence_with_other_amount_is_kept() {
+        let conn = setup();
+        conn.execute(
+            "INSERT INTO transaction_splits (transaction_id, amount) VALUES (1, 2000), (1, 3000)",
+            [],
+        )
+        .unwrap();
+        let payment = query_transaction(&conn, 2).unwrap();
+        assert!(!match_recurring_payment(&conn, &payment).unwrap());
+        assert!(query_transaction(&conn, 1).is_ok());
+        assert_eq!(
+            query_transaction(&conn, 2).unwrap().recurring_payment_id,
+            None
+        );
+        assert_eq!(balance(&conn), 89800);
+    }
+}
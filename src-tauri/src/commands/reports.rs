@@ -1,14 +1,30 @@
 use crate::db::connection::get_connection;
+use crate::db::currency::CurrencyConverter;
 use crate::utils::error::Result;
+use crate::utils::money::Money;
+use rusqlite::Connection;
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[derive(Debug, Serialize)]
 pub struct MonthlySummary {
     pub month: String,
-    pub total_income: f64,
-    pub total_expense: f64,
-    pub net_change: f64,
+    pub currency: String,
+    pub total_income: Money,
+    pub total_expense: Money,
+    pub net_change: Money,
     pub by_category: Vec<CategoryBreakdown>,
+    /// Chybějící kurzy; transakce bez kurzu nejsou v součtech
+    pub warnings: Vec<String>,
+}
+
+/// Položky reportu za období v měně reportu.
+#[derive(Debug, Serialize)]
+pub struct ReportList<T> {
+    pub currency: String,
+    pub items: Vec<T>,
+    /// Chybějící kurzy; transakce bez kurzu nejsou v položkách
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -16,7 +32,16 @@ pub struct CategoryBreakdown {
     pub category_id: i64,
     pub category_name: String,
     pub category_color: String,
-    pub amount: f64,
+    pub amount: Money,
+    pub percentage: f64,
+    pub transaction_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayeeBreakdown {
+    pub payee_id: i64,
+    pub payee_name: String,
+    pub amount: Money,
     pub percentage: f64,
     pub transaction_count: i64,
 }
@@ -24,14 +49,223 @@ pub struct CategoryBreakdown {
 #[derive(Debug, Serialize)]
 pub struct CashFlowData {
     pub date: String,
-    pub income: f64,
-    pub expense: f64,
-    pub balance: f64,
+    pub income: Money,
+    pub expense: Money,
+    pub balance: Money,
+}
+
+/// Realizovaný kurzový zisk/ztráta jednoho převodu mezi měnami (hodnoty v měně reportu).
+#[derive(Debug, Serialize)]
+pub struct FxTransferResult {
+    pub transaction_id: i64,
+    pub date: String,
+    pub from_account_id: Option<i64>,
+    pub to_account_id: Option<i64>,
+    pub source_amount: Money,
+    pub source_currency: String,
+    pub destination_amount: Money,
+    pub destination_currency: String,
+    pub applied_rate: f64,
+    pub market_rate: f64,
+    pub fee_amount: Money,
+    pub source_value: Money,
+    pub destination_value: Money,
+    pub fee_value: Money,
+    pub gain_loss: Money,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FxGainLossReport {
+    pub currency: String,
+    pub transfers: Vec<FxTransferResult>,
+    pub total_gain_loss: Money,
+    pub total_fees: Money,
+    pub net_result: Money,
+    /// Chybějící kurzy; převody bez kurzu nejsou v reportu
+    pub warnings: Vec<String>,
+}
+
+/// Příjem nebo výdaj přepočtený do měny reportu kurzem ke dni transakce.
+struct ReportEntry {
+    transaction_id: i64,
+    day: String,
+    transaction_type: String,
+    category: Option<(i64, String, String)>,
+    payee: Option<(i64, String)>,
+    amount: Money,
+}
+
+/// Načte dokončené příjmy a výdaje v rozsahu dat a přepočte je do výchozí měny.
+/// `date_condition` je podmínka nad `t.date` s parametry `?1` a `?2`. Položky bez kurzu
+/// vynechá a zaznamená je v převodníku (`missing_rates`).
+///
+/// Rozdělená transakce přispívá každým řádkem rozpadu do jeho kategorie,
+/// nerozdělený zbytek patří kategorii transakce.
+fn load_report_entries(
+    conn: &Connection,
+    converter: &mut CurrencyConverter,
+    date_condition: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<ReportEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT date(t.date) AS day, t.transaction_type,
+         t.amount - COALESCE((SELECT SUM(s.amount) FROM transaction_splits s WHERE s.transaction_id = t.id), 0),
+         t.currency, c.id, c.name, c.color, p.id, p.name, t.id
+         FROM transactions t
+         LEFT JOIN categories c ON t.category_id = c.id
+         LEFT JOIN payees p ON t.payee_id = p.id
+         WHERE t.status = 'completed' AND t.transaction_type IN ('income', 'expense')
+         AND {cond}
+         UNION ALL
+         SELECT date(t.date) AS day, t.transaction_type, s.amount, t.currency, c.id, c.name, c.color,
+         p.id, p.name, t.id
+         FROM transaction_splits s
+         JOIN transactions t ON s.transaction_id = t.id
+         LEFT JOIN categories c ON s.category_id = c.id
+         LEFT JOIN payees p ON t.payee_id = p.id
+         WHERE t.status = 'completed' AND t.transaction_type IN ('income', 'expense')
+         AND {cond}
+         ORDER BY day",
+        cond = date_condition
+    ))?;
+
+    let rows = stmt
+        .query_map([start_date, end_date], |row| {
+            let category = match row.get::<_, Option<i64>>(4)? {
+                Some(id) => Some((id, row.get(5)?, row.get(6)?)),
+                None => None,
+            };
+            let payee = match row.get::<_, Option<i64>>(7)? {
+                Some(id) => Some((id, row.get(8)?)),
+                None => None,
+            };
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Money>(2)?,
+                row.get::<_, String>(3)?,
+                category,
+                payee,
+                row.get::<_, i64>(9)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut entries = Vec::new();
+    // Plně rozdělená transakce nemá zbytek
+    for (day, transaction_type, amount, currency, category, payee, transaction_id) in
+        rows.into_iter().filter(|row| !row.2.is_zero())
+    {
+        if let Some(amount) = converter.try_convert(amount, &currency, &day)? {
+            entries.push(ReportEntry {
+                transaction_id,
+                day,
+                transaction_type,
+                category,
+                payee,
+                amount,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn total_of(entries: &[ReportEntry], transaction_type: &str) -> Money {
+    entries
+        .iter()
+        .filter(|e| e.transaction_type == transaction_type)
+        .map(|e| e.amount)
+        .sum()
+}
+
+/// Výdaje podle kategorií, seřazené od největší částky.
+fn expense_breakdown(entries: &[ReportEntry], total_expense: Money) -> Vec<CategoryBreakdown> {
+    let mut by_id: HashMap<i64, CategoryBreakdown> = HashMap::new();
+    // Rozdělená transakce se do počtu kategorie započte jen jednou
+    let mut counted = HashSet::new();
+
+    for entry in entries.iter().filter(|e| e.transaction_type == "expense") {
+        if let Some((id, name, color)) = &entry.category {
+            let item = by_id.entry(*id).or_insert_with(|| CategoryBreakdown {
+                category_id: *id,
+                category_name: name.clone(),
+                category_color: color.clone(),
+                amount: Money::ZERO,
+                percentage: 0.0,
+                transaction_count: 0,
+            });
+            item.amount += entry.amount;
+            if counted.insert((*id, entry.transaction_id)) {
+                item.transaction_count += 1;
+            }
+        }
+    }
+
+    let mut categories: Vec<CategoryBreakdown> = by_id
+        .into_values()
+        .map(|mut item| {
+            item.percentage = percentage_of(item.amount, total_expense);
+            item
+        })
+        .collect();
+    categories.sort_by(|a, b| {
+        b.amount
+            .cmp(&a.amount)
+            .then_with(|| a.category_name.cmp(&b.category_name))
+    });
+    categories
+}
+
+/// Výdaje podle obchodníků, seřazené od největší částky.
+fn payee_breakdown(entries: &[ReportEntry], total_expense: Money) -> Vec<PayeeBreakdown> {
+    let mut by_id: HashMap<i64, PayeeBreakdown> = HashMap::new();
+    // Řádky rozpadu i zbytek patří jedné transakci obchodníka
+    let mut counted = HashSet::new();
+
+    for entry in entries.iter().filter(|e| e.transaction_type == "expense") {
+        if let Some((id, name)) = &entry.payee {
+            let item = by_id.entry(*id).or_insert_with(|| PayeeBreakdown {
+                payee_id: *id,
+                payee_name: name.clone(),
+                amount: Money::ZERO,
+                percentage: 0.0,
+                transaction_count: 0,
+            });
+            item.amount += entry.amount;
+            if counted.insert(entry.transaction_id) {
+                item.transaction_count += 1;
+            }
+        }
+    }
+
+    let mut payees: Vec<PayeeBreakdown> = by_id
+        .into_values()
+        .map(|mut item| {
+            item.percentage = percentage_of(item.amount, total_expense);
+            item
+        })
+        .collect();
+    payees.sort_by(|a, b| {
+        b.amount
+            .cmp(&a.amount)
+            .then_with(|| a.payee_name.cmp(&b.payee_name))
+    });
+    payees
+}
+
+/// Podíl částky na celku v procentech, zaokrouhlený na setiny procenta.
+fn percentage_of(amount: Money, total: Money) -> f64 {
+    if total.minor() <= 0 {
+        return 0.0;
+    }
+    (amount.minor() as f64 * 10_000.0 / total.minor() as f64).round() / 100.0
 }
 
 #[tauri::command]
 pub fn get_monthly_summary(year: i32, month: i32) -> Result<MonthlySummary> {
     let conn = get_connection()?;
+    let mut converter = CurrencyConverter::to_default(&conn)?;
 
     let start_date = format!("{:04}-{:02}-01", year, month);
     let end_date = if month == 12 {
@@ -40,134 +274,97 @@ pub fn get_monthly_summary(year: i32, month: i32) -> Result<MonthlySummary> {
         format!("{:04}-{:02}-01", year, month + 1)
     };
 
-    // Celkový příjem
-    let total_income: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions 
-             WHERE transaction_type = 'income' AND status = 'completed'
-             AND date >= ?1 AND date < ?2",
-            [&start_date, &end_date],
-            |row| row.get(0),
-        )
-        .unwrap_or(0.0);
-
-    // Celkové výdaje
-    let total_expense: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions 
-             WHERE transaction_type = 'expense' AND status = 'completed'
-             AND date >= ?1 AND date < ?2",
-            [&start_date, &end_date],
-            |row| row.get(0),
-        )
-        .unwrap_or(0.0);
-
-    // Rozpad podle kategorií
-    let mut stmt = conn.prepare(
-        "SELECT c.id, c.name, c.color, SUM(t.amount) as total, COUNT(t.id) as count
-         FROM transactions t
-         JOIN categories c ON t.category_id = c.id
-         WHERE t.transaction_type = 'expense' AND t.status = 'completed'
-         AND t.date >= ?1 AND t.date < ?2
-         GROUP BY c.id
-         ORDER BY total DESC",
+    let entries = load_report_entries(
+        &conn,
+        &mut converter,
+        "t.date >= ?1 AND t.date < ?2",
+        &start_date,
+        &end_date,
     )?;
 
-    let categories: Vec<CategoryBreakdown> = stmt
-        .query_map([&start_date, &end_date], |row| {
-            let amount: f64 = row.get(3)?;
-            Ok(CategoryBreakdown {
-                category_id: row.get(0)?,
-                category_name: row.get(1)?,
-                category_color: row.get(2)?,
-                amount,
-                percentage: if total_expense > 0.0 {
-                    (amount / total_expense) * 100.0
-                } else {
-                    0.0
-                },
-                transaction_count: row.get(4)?,
-            })
-        })?
-        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let total_income = total_of(&entries, "income");
+    let total_expense = total_of(&entries, "expense");
 
     Ok(MonthlySummary {
         month: format!("{:04}-{:02}", year, month),
+        currency: converter.target().to_string(),
         total_income,
         total_expense,
         net_change: total_income - total_expense,
-        by_category: categories,
+        by_category: expense_breakdown(&entries, total_expense),
+        warnings: converter.missing_rates(),
     })
 }
 
 #[tauri::command]
-pub fn get_category_breakdown(start_date: String, end_date: String) -> Result<Vec<CategoryBreakdown>> {
+pub fn get_category_breakdown(start_date: String, end_date: String) -> Result<ReportList<CategoryBreakdown>> {
     let conn = get_connection()?;
+    let mut converter = CurrencyConverter::to_default(&conn)?;
 
-    let total_expense: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions 
-             WHERE transaction_type = 'expense' AND status = 'completed'
-             AND date >= ?1 AND date <= ?2",
-            [&start_date, &end_date],
-            |row| row.get(0),
-        )
-        .unwrap_or(0.0);
-
-    let mut stmt = conn.prepare(
-        "SELECT c.id, c.name, c.color, SUM(t.amount) as total, COUNT(t.id) as count
-         FROM transactions t
-         JOIN categories c ON t.category_id = c.id
-         WHERE t.transaction_type = 'expense' AND t.status = 'completed'
-         AND t.date >= ?1 AND t.date <= ?2
-         GROUP BY c.id
-         ORDER BY total DESC",
+    let entries = load_report_entries(
+        &conn,
+        &mut converter,
+        "t.date >= ?1 AND t.date <= ?2",
+        &start_date,
+        &end_date,
     )?;
 
-    let categories = stmt
-        .query_map([&start_date, &end_date], |row| {
-            let amount: f64 = row.get(3)?;
-            Ok(CategoryBreakdown {
-                category_id: row.get(0)?,
-                category_name: row.get(1)?,
-                category_color: row.get(2)?,
-                amount,
-                percentage: if total_expense > 0.0 {
-                    (amount / total_expense) * 100.0
-                } else {
-                    0.0
-                },
-                transaction_count: row.get(4)?,
-            })
-        })?
-        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let total_expense = total_of(&entries, "expense");
+    Ok(ReportList {
+        currency: converter.target().to_string(),
+        items: expense_breakdown(&entries, total_expense),
+        warnings: converter.missing_rates(),
+    })
+}
 
-    Ok(categories)
+#[tauri::command]
+pub fn get_payee_breakdown(start_date: String, end_date: String) -> Result<ReportList<PayeeBreakdown>> {
+    let conn = get_connection()?;
+    let mut converter = CurrencyConverter::to_default(&conn)?;
+
+    let entries = load_report_entries(
+        &conn,
+        &mut converter,
+        "t.date >= ?1 AND t.date <= ?2",
+        &start_date,
+        &end_date,
+    )?;
+
+    let total_expense = total_of(&entries, "expense");
+    Ok(ReportList {
+        currency: converter.target().to_string(),
+        items: payee_breakdown(&entries, total_expense),
+        warnings: converter.missing_rates(),
+    })
 }
 
 #[tauri::command]
-pub fn get_cash_flow_data(start_date: String, end_date: String) -> Result<Vec<CashFlowData>> {
+pub fn get_cash_flow_data(start_date: String, end_date: String) -> Result<ReportList<CashFlowData>> {
     let conn = get_connection()?;
+    let mut converter = CurrencyConverter::to_default(&conn)?;
 
-    let mut stmt = conn.prepare(
-        "SELECT date(date) as day,
-         SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0 END) as income,
-         SUM(CASE WHEN transaction_type = 'expense' THEN amount ELSE 0 END) as expense
-         FROM transactions
-         WHERE status = 'completed' AND date >= ?1 AND date <= ?2
-         GROUP BY day
-         ORDER BY day",
+    let entries = load_report_entries(
+        &conn,
+        &mut converter,
+        "t.date >= ?1 AND t.date <= ?2",
+        &start_date,
+        &end_date,
     )?;
 
-    let mut running_balance = 0.0;
-    let data: Vec<CashFlowData> = stmt
-        .query_map([&start_date, &end_date], |row| {
-            let income: f64 = row.get(1)?;
-            let expense: f64 = row.get(2)?;
-            Ok((row.get::<_, String>(0)?, income, expense))
-        })?
-        .filter_map(|r| r.ok())
-        .map(|(date, income, expense)| {
+    let mut by_day: BTreeMap<String, (Money, Money)> = BTreeMap::new();
+    for entry in entries {
+        let day = by_day.entry(entry.day).or_default();
+        if entry.transaction_type == "income" {
+            day.0 += entry.amount;
+        } else {
+            day.1 += entry.amount;
+        }
+    }
+
+    let mut running_balance = Money::ZERO;
+    let items = by_day
+        .into_iter()
+        .map(|(date, (income, expense))| {
             running_balance += income - expense;
             CashFlowData {
                 date,
@@ -178,6 +375,102 @@ pub fn get_cash_flow_data(start_date: String, end_date: String) -> Result<Vec<Ca
         })
         .collect();
 
-    Ok(data)
+    Ok(ReportList {
+        currency: converter.target().to_string(),
+        items,
+        warnings: converter.missing_rates(),
+    })
 }
 
+/// Kurzové zisky a ztráty z převodů mezi účty v různých měnách.
+///
+/// Obě strany převodu se ocení kurzem z kurzovního lístku ke dni převodu;
+/// rozdíl je zisk (kladný) nebo ztráta oproti tržnímu kurzu. Poplatky se vykazují zvlášť.
+#[tauri::command]
+pub fn get_fx_gain_loss(start_date: String, end_date: String) -> Result<FxGainLossReport> {
+    let conn = get_connection()?;
+    let mut converter = CurrencyConverter::to_default(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, date(date), from_account_id, to_account_id, amount, currency,
+         destination_amount, destination_currency, exchange_rate, COALESCE(fee_amount, 0)
+         FROM transactions
+         WHERE transaction_type = 'transfer' AND status = 'completed'
+         AND destination_amount IS NOT NULL AND destination_currency != currency
+         AND date >= ?1 AND date <= ?2
+         ORDER BY date, id",
+    )?;
+
+    let rows = stmt
+        .query_map([&start_date, &end_date], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, Money>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Money>(6)?,
+                row.get::<_, String>(7)?,
+                row.get::<_, Option<f64>>(8)?,
+                row.get::<_, Money>(9)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut transfers = Vec::new();
+    for row in rows {
+        let (
+            id,
+            date,
+            from_account_id,
+            to_account_id,
+            amount,
+            currency,
+            destination_amount,
+            destination_currency,
+            rate,
+            fee,
+        ) = row;
+        let (Some(source_value), Some(destination_value), Some(fee_value)) = (
+            converter.try_convert(amount, &currency, &date)?,
+            converter.try_convert(destination_amount, &destination_currency, &date)?,
+            converter.try_convert(fee, &currency, &date)?,
+        ) else {
+            continue;
+        };
+        let Some(market_rate) = converter.try_rate(&currency, &destination_currency, &date)? else {
+            continue;
+        };
+
+        transfers.push(FxTransferResult {
+            transaction_id: id,
+            market_rate,
+            applied_rate: rate.unwrap_or_default(),
+            date,
+            from_account_id,
+            to_account_id,
+            source_amount: amount,
+            source_currency: currency,
+            destination_amount,
+            destination_currency,
+            fee_amount: fee,
+            source_value,
+            destination_value,
+            fee_value,
+            gain_loss: destination_value - source_value,
+        });
+    }
+
+    let total_gain_loss: Money = transfers.iter().map(|t| t.gain_loss).sum();
+    let total_fees: Money = transfers.iter().map(|t| t.fee_value).sum();
+
+    Ok(FxGainLossReport {
+        currency: converter.target().to_string(),
+        transfers,
+        total_gain_loss,
+        total_fees,
+        net_result: total_gain_loss - total_fees,
+        warnings: converter.missing_rates(),
+    })
+}
@@ -0,0 +1,566 @@
+use crate::commands::transactions::query_transaction;
+use crate::db::connection::{get_connection, with_transaction};
+use crate::models::{CategorizationRule, RuleRunResult, SaveRuleInput, Transaction};
+use crate::utils::error::{AppError, Result};
+use regex::{Regex, RegexBuilder};
+use rusqlite::{Connection, OptionalExtension};
+
+const RULE_COLUMNS: &str = "id, name, priority, active, stop_processing, match_pattern,
+    match_counterparty, account_id, min_amount, max_amount, transaction_type, set_category_id,
+    set_owner_user_id, set_is_shared, set_flow_group_id, created_at, updated_at";
+
+fn row_to_rule(row: &rusqlite::Row) -> rusqlite::Result<CategorizationRule> {
+    Ok(CategorizationRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        priority: row.get(2)?,
+        active: row.get::<_, i32>(3)? != 0,
+        stop_processing: row.get::<_, i32>(4)? != 0,
+        match_pattern: row.get(5)?,
+        match_counterparty: row.get(6)?,
+        account_id: row.get(7)?,
+        min_amount: row.get(8)?,
+        max_amount: row.get(9)?,
+        transaction_type: row.get(10)?,
+        set_category_id: row.get(11)?,
+        set_tag_ids: Vec::new(),
+        set_owner_user_id: row.get(12)?,
+        set_is_shared: row.get::<_, Option<i32>>(13)?.map(|v| v != 0),
+        set_flow_group_id: row.get(14)?,
+        created_at: row.get(15)?,
+        updated_at: row.get(16)?,
+    })
+}
+
+fn load_rule_tags(conn: &Connection, rule_id: i64) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT tag_id FROM categorization_rule_tags WHERE rule_id = ?1 ORDER BY tag_id",
+    )?;
+    let tags = stmt
+        .query_map([rule_id], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(tags)
+}
+
+fn query_rule(conn: &Connection, id: i64) -> Result<CategorizationRule> {
+    let mut rule = conn
+        .query_row(
+            &format!("SELECT {} FROM categorization_rules WHERE id = ?1", RULE_COLUMNS),
+            [id],
+            row_to_rule,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Pravidlo {} neexistuje", id)))?;
+    rule.set_tag_ids = load_rule_tags(conn, id)?;
+    Ok(rule)
+}
+
+/// Pravidla v pořadí vyhodnocení: vyšší priorita dřív, při shodě starší pravidlo.
+fn query_rules(conn: &Connection, only_active: bool) -> Result<Vec<CategorizationRule>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM categorization_rules WHERE ?1 = 0 OR active = 1
+         ORDER BY priority DESC, id",
+        RULE_COLUMNS
+    ))?;
+    let mut rules = stmt
+        .query_map([only_active as i32], row_to_rule)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for rule in &mut rules {
+        rule.set_tag_ids = load_rule_tags(conn, rule.id)?;
+    }
+    Ok(rules)
+}
+
+fn compile_pattern(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| AppError::InvalidInput(format!("Neplatný regulární výraz: {}", e)))
+}
+
+fn validate_rule(input: &SaveRuleInput) -> Result<()> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Název pravidla je povinný".to_string()));
+    }
+    if let Some(pattern) = &input.match_pattern {
+        compile_pattern(pattern)?;
+    }
+    if let (Some(min), Some(max)) = (input.min_amount, input.max_amount) {
+        if min > max {
+            return Err(AppError::InvalidInput(
+                "Minimální částka je vyšší než maximální".to_string(),
+            ));
+        }
+    }
+    if let Some(transaction_type) = &input.transaction_type {
+        if !matches!(transaction_type.as_str(), "income" | "expense" | "transfer") {
+            return Err(AppError::InvalidInput(format!(
+                "Neznámý typ transakce: {}",
+                transaction_type
+            )));
+        }
+    }
+
+    let has_condition = input.match_pattern.is_some()
+        || input.match_counterparty.is_some()
+        || input.account_id.is_some()
+        || input.min_amount.is_some()
+        || input.max_amount.is_some()
+        || input.transaction_type.is_some();
+    let has_action = input.set_category_id.is_some()
+        || input.set_tag_ids.as_ref().is_some_and(|tags| !tags.is_empty())
+        || input.set_owner_user_id.is_some()
+        || input.set_is_shared.is_some()
+        || input.set_flow_group_id.is_some();
+    if !has_condition || !has_action {
+        return Err(AppError::InvalidInput(
+            "Pravidlo musí mít alespoň jednu podmínku a jednu akci".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn replace_rule_tags(conn: &Connection, rule_id: i64, tag_ids: &[i64]) -> Result<()> {
+    conn.execute("DELETE FROM categorization_rule_tags WHERE rule_id = ?1", [rule_id])?;
+    for tag_id in tag_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO categorization_rule_tags (rule_id, tag_id) VALUES (?1, ?2)",
+            [rule_id, *tag_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Pravidlo s předkompilovaným regulárním výrazem.
+pub(crate) struct CompiledRule {
+    rule: CategorizationRule,
+    pattern: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn matches(&self, transaction: &Transaction) -> bool {
+        let rule = &self.rule;
+
+        if let Some(pattern) = &self.pattern {
+            let text = [transaction.description.as_deref(), transaction.notes.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !pattern.is_match(&text) {
+                return false;
+            }
+        }
+        if let Some(counterparty) = &rule.match_counterparty {
+            let needle = counterparty.trim().to_lowercase();
+            let found = [
+                transaction.counterparty_account.as_deref(),
+                transaction.description.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|value| value.to_lowercase().contains(&needle));
+            if !found {
+                return false;
+            }
+        }
+        if let Some(account_id) = rule.account_id {
+            if transaction.from_account_id != Some(account_id)
+                && transaction.to_account_id != Some(account_id)
+            {
+                return false;
+            }
+        }
+        if matches!(rule.min_amount, Some(min) if transaction.amount < min)
+            || matches!(rule.max_amount, Some(max) if transaction.amount > max)
+        {
+            return false;
+        }
+        if matches!(&rule.transaction_type, Some(t) if *t != transaction.transaction_type) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Načte aktivní pravidla pro opakované vyhodnocení (import, přepočet).
+pub(crate) fn load_rules(conn: &Connection) -> Result<Vec<CompiledRule>> {
+    query_rules(conn, true)?
+        .into_iter()
+        .map(|rule| {
+            let pattern = rule.match_pattern.as_deref().map(compile_pattern).transpose()?;
+            Ok(CompiledRule { rule, pattern })
+        })
+        .collect()
+}
+
+/// Vyhodnotí pravidla nad transakcí. Každý údaj nastaví první vyhovující pravidlo;
+/// bez `overwrite` se vyplněná kategorie, vlastník, tok a zapnuté sdílení nepřepisují.
+/// Vrací, zda se transakce změnila.
+pub(crate) fn apply_rules(
+    conn: &Connection,
+    rules: &[CompiledRule],
+    transaction: &Transaction,
+    overwrite: bool,
+) -> Result<bool> {
+    let mut category_id = (!overwrite).then_some(transaction.category_id).flatten();
+    let mut owner_user_id = (!overwrite).then_some(transaction.owner_user_id).flatten();
+    let mut flow_group_id = (!overwrite).then_some(transaction.flow_group_id).flatten();
+    let mut is_shared = (!overwrite && transaction.is_shared).then_some(true);
+    let mut tag_ids: Vec<i64> = Vec::new();
+    let mut matched = false;
+
+    for compiled in rules.iter().filter(|r| r.matches(transaction)) {
+        let rule = &compiled.rule;
+        matched = true;
+        category_id = category_id.or(rule.set_category_id);
+        owner_user_id = owner_user_id.or(rule.set_owner_user_id);
+        flow_group_id = flow_group_id.or(rule.set_flow_group_id);
+        is_shared = is_shared.or(rule.set_is_shared);
+        tag_ids.extend(&rule.set_tag_ids);
+        if rule.stop_processing {
+            break;
+        }
+    }
+    if !matched {
+        return Ok(false);
+    }
+
+    let category_id = category_id.or(transaction.category_id);
+    let owner_user_id = owner_user_id.or(transaction.owner_user_id);
+    let flow_group_id = flow_group_id.or(transaction.flow_group_id);
+    let is_shared = is_shared.unwrap_or(transaction.is_shared);
+
+    let mut changed = category_id != transaction.category_id
+        || owner_user_id != transaction.owner_user_id
+        || flow_group_id != transaction.flow_group_id
+        || is_shared != transaction.is_shared;
+    if changed {
+        conn.execute(
+            "UPDATE transactions SET category_id = ?1, owner_user_id = ?2, flow_group_id = ?3,
+             is_shared = ?4, updated_at = CURRENT_TIMESTAMP WHERE id = ?5",
+            rusqlite::params![category_id, owner_user_id, flow_group_id, is_shared as i32, transaction.id],
+        )?;
+    }
+    for tag_id in tag_ids {
+        changed |= conn.execute(
+            "INSERT OR IGNORE INTO transaction_tags (transaction_id, tag_id) VALUES (?1, ?2)",
+            [transaction.id, tag_id],
+        )? > 0;
+    }
+    Ok(changed)
+}
+
+#[tauri::command]
+pub fn get_rules() -> Result<Vec<CategorizationRule>> {
+    let conn = get_connection()?;
+    query_rules(&conn, false)
+}
+
+#[tauri::command]
+pub fn create_rule(input: SaveRuleInput) -> Result<CategorizationRule> {
+    validate_rule(&input)?;
+    with_transaction(|conn| {
+        conn.execute(
+            "INSERT INTO categorization_rules (name, priority, active, stop_processing,
+             match_pattern, match_counterparty, account_id, min_amount, max_amount,
+             transaction_type, set_category_id, set_owner_user_id, set_is_shared, set_flow_group_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            rusqlite::params![
+                input.name.trim(),
+                input.priority.unwrap_or(0),
+                input.active.unwrap_or(true) as i32,
+                input.stop_processing.unwrap_or(false) as i32,
+                input.match_pattern,
+                input.match_counterparty,
+                input.account_id,
+                input.min_amount,
+                input.max_amount,
+                input.transaction_type,
+                input.set_category_id,
+                input.set_owner_user_id,
+                input.set_is_shared.map(|v| v as i32),
+                input.set_flow_group_id,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        replace_rule_tags(conn, id, input.set_tag_ids.as_deref().unwrap_or_default())?;
+        query_rule(conn, id)
+    })
+}
+
+#[tauri::command]
+pub fn update_rule(id: i64, input: SaveRuleInput) -> Result<CategorizationRule> {
+    validate_rule(&input)?;
+    with_transaction(|conn| {
+        query_rule(conn, id)?;
+        conn.execute(
+            "UPDATE categorization_rules SET name = ?1, priority = ?2, active = ?3,
+             stop_processing = ?4, match_pattern = ?5, match_counterparty = ?6, account_id = ?7,
+             min_amount = ?8, max_amount = ?9, transaction_type = ?10, set_category_id = ?11,
+             set_owner_user_id = ?12, set_is_shared = ?13, set_flow_group_id = ?14,
+             updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?15",
+            rusqlite::params![
+                input.name.trim(),
+                input.priority.unwrap_or(0),
+                input.active.unwrap_or(true) as i32,
+                input.stop_processing.unwrap_or(false) as i32,
+                input.match_pattern,
+                input.match_counterparty,
+                input.account_id,
+                input.min_amount,
+                input.max_amount,
+                input.transaction_type,
+                input.set_category_id,
+                input.set_owner_user_id,
+                input.set_is_shared.map(|v| v as i32),
+                input.set_flow_group_id,
+                id,
+            ],
+        )?;
+        replace_rule_tags(conn, id, input.set_tag_ids.as_deref().unwrap_or_default())?;
+        query_rule(conn, id)
+    })
+}
+
+#[tauri::command]
+pub fn delete_rule(id: i64) -> Result<()> {
+    let conn = get_connection()?;
+    conn.execute("DELETE FROM categorization_rules WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Znovu vyhodnotí pravidla nad transakcemi v období; `overwrite` přepíše
+/// i ručně zvolenou kategorii, vlastníka a tok.
+#[tauri::command]
+pub fn apply_rules_to_range(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<RuleRunResult> {
+    with_transaction(|conn| {
+        let rules = load_rules(conn)?;
+        let mut stmt = conn.prepare(
+            "SELECT id FROM transactions
+             WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+             ORDER BY date, id",
+        )?;
+        let ids = stmt
+            .query_map(rusqlite::params![start_date, end_date], |row| row.get::<_, i64>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut updated = 0;
+        for id in &ids {
+            let transaction = query_transaction(conn, *id)?;
+            if apply_rules(conn, &rules, &transaction, overwrite.unwrap_or(false))? {
+                updated += 1;
+            }
+        }
+
+        log::info!("apply_rules_to_range: {} of {} transactions updated", updated, ids.len());
+        Ok(RuleRunResult {
+            checked: ids.len(),
+            updated,
+        })
+    })
+}
+
+/// Návrh pravidla podle existující transakce (neukládá se): podmínka na protistranu
+/// nebo popis, účet a typ, akce převezme kategorii, štítky, vlastníka, sdílení a tok.
+#[tauri::command]
+pub fn suggest_rule_from_transaction(transaction_id: i64) -> Result<SaveRuleInput> {
+    let conn = get_connection()?;
+    let transaction = query_transaction(&conn, transaction_id)?;
+
+    let description = transaction
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    let (match_counterparty, match_pattern) = match (&transaction.counterparty_account, description) {
+        (Some(account), _) => (Some(account.clone()), None),
+        (None, Some(description)) => (None, Some(format!("^{}$", regex::escape(description)))),
+        (None, None) => (None, None),
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT tag_id FROM transaction_tags WHERE transaction_id = ?1 ORDER BY tag_id",
+    )?;
+    let tag_ids = stmt
+        .query_map([transaction_id], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<i64>, _>>()?;
+
+    Ok(SaveRuleInput {
+        name: description
+            .or(transaction.counterparty_account.as_deref())
+            .unwrap_or("Nové pravidlo")
+            .to_string(),
+        priority: None,
+        active: Some(true),
+        stop_processing: None,
+        match_pattern,
+        match_counterparty,
+        account_id: transaction.from_account_id.or(transaction.to_account_id),
+        min_amount: None,
+        max_amount: None,
+        transaction_type: Some(transaction.transaction_type),
+        set_category_id: transaction.category_id,
+        set_tag_ids: Some(tag_ids),
+        set_owner_user_id: transaction.owner_user_id,
+        set_is_shared: transaction.is_shared.then_some(true),
+        set_flow_group_id: transaction.flow_group_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    /// Výdaj 250 Kč z účtu 1 (ze dvou) v Albertu s protiúčtem a dvě kategorie pro akce pravidel.
+    fn setup() -> (Connection, i64, i64) {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, name) VALUES (11, 'Petr');
+             INSERT INTO accounts (id, name, account_type)
+             VALUES (1, 'A', 'checking'), (2, 'B', 'savings');
+             INSERT INTO tags (id, name) VALUES (1, 'jídlo'), (2, 'týden');
+             INSERT INTO transactions (id, date, amount, transaction_type, from_account_id,
+                                       description, counterparty_account)
+             VALUES (1, '2024-03-01', 25000, 'expense', 1, 'ALBERT Praha 5', '123/0800');",
+        )
+        .unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id FROM categories ORDER BY id LIMIT 2")
+            .unwrap();
+        let ids: Vec<i64> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        drop(stmt);
+        (conn, ids[0], ids[1])
+    }
+
+    fn add_rule(conn: &Connection, name: &str, columns: &str, values: &str) {
+        conn.execute(
+            &format!(
+                "INSERT INTO categorization_rules (name{}) VALUES ('{}'{})",
+                columns, name, values
+            ),
+            [],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rule_conditions_must_all_hold() {
+        let (conn, _, _) = setup();
+        add_rule(&conn, "vzor", ", match_pattern", ", '^albert'");
+        add_rule(&conn, "jiny vzor", ", match_pattern", ", 'lidl'");
+        add_rule(
+            &conn,
+            "protistrana",
+            ", match_counterparty",
+            ", ' 123/0800 '",
+        );
+        add_rule(&conn, "jiny ucet", ", account_id", ", 2");
+        add_rule(
+            &conn,
+            "rozsah",
+            ", min_amount, max_amount",
+            ", 10000, 25000",
+        );
+        add_rule(&conn, "nad rozsahem", ", min_amount", ", 25001");
+        add_rule(&conn, "prijem", ", transaction_type", ", 'income'");
+        add_rule(
+            &conn,
+            "vzor a ucet",
+            ", match_pattern, account_id",
+            ", 'albert', 2",
+        );
+        add_rule(&conn, "neaktivni", ", active", ", 0");
+
+        let transaction = query_transaction(&conn, 1).unwrap();
+        let mut matching: Vec<&str> = Vec::new();
+        let rules = load_rules(&conn).unwrap();
+        for compiled in &rules {
+            if compiled.matches(&transaction) {
+                matching.push(&compiled.rule.name);
+            }
+        }
+        matching.sort_unstable();
+        assert_eq!(matching, ["protistrana", "rozsah", "vzor"]);
+    }
+
+    #[test]
+    fn first_matching_rule_sets_each_field() {
+        let (conn, first, second) = setup();
+        let actions = ", priority, match_pattern, set_category_id";
+        add_rule(&conn, "A", actions, &format!(", 10, 'albert', {}", first));
+        add_rule(
+            &conn,
+            "B",
+            ", priority, set_category_id, set_owner_user_id, stop_processing",
+            &format!(", 5, {}, 11, 1", second),
+        );
+        add_rule(&conn, "C", ", priority, set_is_shared", ", 1, 1");
+        conn.execute_batch(
+            "INSERT INTO categorization_rule_tags (rule_id, tag_id)
+             SELECT id, 1 FROM categorization_rules WHERE name = 'A';
+             INSERT INTO categorization_rule_tags (rule_id, tag_id)
+             SELECT id, 2 FROM categorization_rules WHERE name = 'B';",
+        )
+        .unwrap();
+
+        let rules = load_rules(&conn).unwrap();
+        let transaction = query_transaction(&conn, 1).unwrap();
+        assert!(apply_rules(&conn, &rules, &transaction, false).unwrap());
+
+        // Pravidlo C se po zastavení na B nevyhodnotí
+        let updated = query_transaction(&conn, 1).unwrap();
+        assert_eq!(updated.category_id, Some(first));
+        assert_eq!(updated.owner_user_id, Some(11));
+        assert!(!updated.is_shared);
+        let tags: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM transaction_tags WHERE transaction_id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tags, 2);
+        assert!(!apply_rules(&conn, &rules, &updated, false).unwrap());
+    }
+
+    #[test]
+    fn filled_fields_change_only_with_overwrite() {
+        let (conn, first, second) = setup();
+        conn.execute(
+            "UPDATE transactions SET category_id = ?1, is_shared = 1 WHERE id = 1",
+            [first],
+        )
+        .unwrap();
+        add_rule(
+            &conn,
+            "A",
+            ", set_category_id, set_is_shared",
+            &format!(", {}, 0", second),
+        );
+        let rules = load_rules(&conn).unwrap();
+
+        let transaction = query_transaction(&conn, 1).unwrap();
+        assert!(!apply_rules(&conn, &rules, &transaction, false).unwrap());
+        let kept = query_transaction(&conn, 1).unwrap();
+        assert_eq!(kept.category_id, Some(first));
+        assert!(kept.is_shared);
+
+        assert!(apply_rules(&conn, &rules, &kept, true).unwrap());
+        let overwritten = query_transaction(&conn, 1).unwrap();
+        assert_eq!(overwritten.category_id, Some(second));
+        assert!(!overwritten.is_shared);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::commands::transactions::{page_order, query_page};
+use crate::db::connection::get_connection;
+use crate::db::settings::fiscal_month_start;
+use crate::models::{PageRequest, SaveViewInput, SavedView, TransactionPage};
+use crate::utils::date_range;
+use crate::utils::error::{AppError, Result};
+use rusqlite::{Connection, OptionalExtension};
+
+const VIEW_COLUMNS: &str =
+    "id, name, filters, date_range, sort_by, sort_direction, created_at, updated_at";
+
+fn row_to_view(row: &rusqlite::Row) -> rusqlite::Result<(SavedView, String)> {
+    Ok((
+        SavedView {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            filters: Default::default(),
+            date_range: row.get(3)?,
+            sort_by: row.get(4)?,
+            sort_direction: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+        },
+        row.get(2)?,
+    ))
+}
+
+fn with_filters((mut view, filters): (SavedView, String)) -> Result<SavedView> {
+    view.filters = serde_json::from_str(&filters)?;
+    Ok(view)
+}
+
+fn query_view(conn: &Connection, id: i64) -> Result<SavedView> {
+    let row = conn
+        .query_row(
+            &format!("SELECT {} FROM saved_views WHERE id = ?1", VIEW_COLUMNS),
+            [id],
+            row_to_view,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Uložený pohled {} neexistuje", id)))?;
+    with_filters(row)
+}
+
+fn validate_view(input: &SaveViewInput) -> Result<()> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Název pohledu je povinný".to_string(),
+        ));
+    }
+    if let Some(expression) = &input.date_range {
+        date_range::resolve(expression, chrono::Local::now().date_naive(), 1)?;
+    }
+    page_order(&PageRequest {
+        sort_by: input.sort_by.clone(),
+        sort_direction: input.sort_direction.clone(),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+fn input_params(input: &SaveViewInput) -> Result<(String, String, Option<String>)> {
+    let filters = serde_json::to_string(&input.filters.clone().unwrap_or_default())
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let date_range = input
+        .date_range
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string);
+    Ok((input.name.trim().to_string(), filters, date_range))
+}
+
+#[tauri::command]
+pub fn get_saved_views() -> Result<Vec<SavedView>> {
+    let conn = get_connection()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM saved_views ORDER BY name",
+        VIEW_COLUMNS
+    ))?;
+    let rows = stmt
+        .query_map([], row_to_view)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    rows.into_iter().map(with_filters).collect()
+}
+
+#[tauri::command]
+pub fn create_saved_view(input: SaveViewInput) -> Result<SavedView> {
+    validate_view(&input)?;
+    let (name, filters, date_range) = input_params(&input)?;
+    let conn = get_connection()?;
+
+    conn.execute(
+        "INSERT INTO saved_views (name, filters, date_range, sort_by, sort_direction)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            name,
+            filters,
+            date_range,
+            input.sort_by,
+            input.sort_direction
+        ],
+    )?;
+    query_view(&conn, conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub fn update_saved_view(id: i64, input: SaveViewInput) -> Result<SavedView> {
+    validate_view(&input)?;
+    let (name, filters, date_range) = input_params(&input)?;
+    let conn = get_connection()?;
+
+    query_view(&conn, id)?;
+    conn.execute(
+        "UPDATE saved_views SET name = ?1, filters = ?2, date_range = ?3, sort_by = ?4,
+         sort_direction = ?5, updated_at = CURRENT_TIMESTAMP WHERE id = ?6",
+        rusqlite::params![
+            name,
+            filters,
+            date_range,
+            input.sort_by,
+            input.sort_direction,
+            id
+        ],
+    )?;
+    query_view(&conn, id)
+}
+
+#[tauri::command]
+pub fn delete_saved_view(id: i64) -> Result<()> {
+    let conn = get_connection()?;
+    conn.execute("DELETE FROM saved_views WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Spustí uložený pohled: relativní období se vyhodnotí k dnešku, řazení ze
+/// vstupní stránky má přednost před uloženým.
+#[tauri::command]
+pub fn run_saved_view(id: i64, page: Option<PageRequest>) -> Result<TransactionPage> {
+    let conn = get_connection()?;
+    let view = query_view(&conn, id)?;
+
+    let mut filters = view.filters;
+    if let Some(expression) = &view.date_range {
+        let today = chrono::Local::now().date_naive();
+        let (start, end) = date_range::resolve(expression, today, fiscal_month_start(&conn)?)?;
+        filters.start_date = Some(start.format("%Y-%m-%d").to_string());
+        filters.end_date = Some(end.format("%Y-%m-%d").to_string());
+    }
+
+    let mut page = page.unwrap_or_default();
+    if page.sort_by.is_none() {
+        page.sort_by = view.sort_by;
+        if page.sort_direction.is_none() {
+            page.sort_direction = view.sort_direction;
+        }
+    }
+    query_page(&conn, &filters, &page)
+}
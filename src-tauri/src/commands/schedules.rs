@@ -0,0 +1,43 @@
+use crate::utils::error::{AppError, Result};
+use crate::utils::schedule::Schedule;
+use chrono::NaiveDate;
+
+/// Nejvyšší počet vrácených termínů náhledu.
+const MAX_PREVIEW: usize = 500;
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidInput(format!("Neplatné datum: {}", value)))
+}
+
+/// Náhled termínů pravidla opakování od `from` (výchozí dnes) do `to`, nejvýše `limit` (výchozí 12).
+#[tauri::command]
+pub fn get_schedule_occurrences(
+    schedule: String,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<String>> {
+    let today = chrono::Local::now().date_naive();
+    let from = from
+        .as_deref()
+        .map(parse_date)
+        .transpose()?
+        .unwrap_or(today);
+    let schedule = Schedule::parse(&schedule, from)?;
+    let limit = limit.map_or(12, |limit| limit as usize).min(MAX_PREVIEW);
+
+    let dates = match to.as_deref().map(parse_date).transpose()? {
+        Some(to) => schedule.between(from, to),
+        None => schedule
+            .occurrences()
+            .skip_while(|date| *date < from)
+            .take(limit)
+            .collect(),
+    };
+    Ok(dates
+        .into_iter()
+        .take(limit)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .collect())
+}
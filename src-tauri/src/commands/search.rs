@@ -0,0 +1,95 @@
+use crate::commands::transactions::{filter_conditions, query_transaction};
+use crate::db::connection::get_connection;
+use crate::models::{TransactionFilters, TransactionSearchHit};
+use crate::utils::error::Result;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+
+fn quoted(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+/// Převede zadání uživatele na dotaz FTS5: slova se hledají všechna (AND),
+/// `"více slov"` jako fráze a `slovo*` jako předpona. Ostatní syntaxe FTS5 se
+/// escapuje, takže dotaz nikdy neskončí chybou. `None`, pokud nic nezbylo.
+pub(crate) fn fts_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+
+    for (index, part) in input.split('"').enumerate() {
+        if index % 2 == 1 {
+            // Uvnitř uvozovek (neuzavřená fráze se bere také jako fráze)
+            let phrase = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if phrase.chars().any(char::is_alphanumeric) {
+                terms.push(quoted(&phrase));
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            let prefix = word.ends_with('*');
+            let word: String = word
+                .chars()
+                .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.' | '@'))
+                .collect();
+            if !word.chars().any(char::is_alphanumeric) {
+                continue;
+            }
+            terms.push(if prefix {
+                format!("{}*", quoted(&word))
+            } else {
+                quoted(&word)
+            });
+        }
+    }
+
+    (!terms.is_empty()).then(|| terms.join(" AND "))
+}
+
+/// Fulltextové hledání v popisu, poznámce, obchodníkovi, štítcích a kategorii,
+/// seřazené podle relevance a zúžené volitelnými filtry.
+#[tauri::command]
+pub fn search_transactions(
+    query: String,
+    filters: Option<TransactionFilters>,
+    limit: Option<i64>,
+) -> Result<Vec<TransactionSearchHit>> {
+    let conn = get_connection()?;
+    let Some(query) = fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+
+    let (conditions, filter_params) = filter_conditions(&filters.unwrap_or_default());
+    let mut params: Vec<&dyn rusqlite::ToSql> = vec![&query];
+    params.extend(filter_params.iter().map(|p| p.as_ref()));
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).max(1);
+    params.push(&limit);
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT rowid, snippet(transactions_fts, -1, '<mark>', '</mark>', '…', 12), rank
+         FROM transactions_fts
+         WHERE transactions_fts MATCH ?
+           AND rowid IN (SELECT t.id FROM transactions t WHERE 1=1{})
+         ORDER BY rank
+         LIMIT ?",
+        conditions
+    ))?;
+    let rows = stmt
+        .query_map(params.as_slice(), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut hits = Vec::new();
+    for (id, snippet, rank) in rows {
+        hits.push(TransactionSearchHit {
+            transaction: query_transaction(&conn, id)?,
+            snippet,
+            // bm25 je záporné, menší = lepší
+            score: -rank,
+        });
+    }
+    Ok(hits)
+}
@@ -0,0 +1,725 @@
+use crate::commands::transactions::{insert_transaction, Categorizer};
+use crate::db::connection::{get_connection, with_transaction};
+use crate::db::currency::{resolve_currency, CurrencyConverter};
+use crate::models::{
+    CreateTransactionInput, MemberBalance, RecordSettlementInput, Repayment, Settlement,
+    SettleUpReport, ShareInput, SharedExpenseSplit,
+};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use crate::utils::schedule::Schedule;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::{BTreeMap, HashMap};
+
+const SHARE_COLUMNS: &str = "id, transaction_id, user_id, amount, percentage, created_at";
+const SETTLEMENT_COLUMNS: &str =
+    "id, from_user_id, to_user_id, amount, currency, date, transaction_id, note, created_at";
+
+fn row_to_share(row: &rusqlite::Row) -> rusqlite::Result<SharedExpenseSplit> {
+    Ok(SharedExpenseSplit {
+        id: row.get(0)?,
+        transaction_id: row.get(1)?,
+        user_id: row.get(2)?,
+        amount: row.get(3)?,
+        percentage: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn row_to_settlement(row: &rusqlite::Row) -> rusqlite::Result<Settlement> {
+    Ok(Settlement {
+        id: row.get(0)?,
+        from_user_id: row.get(1)?,
+        to_user_id: row.get(2)?,
+        amount: row.get(3)?,
+        currency: row.get(4)?,
+        date: row.get(5)?,
+        transaction_id: row.get(6)?,
+        note: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn query_shares(conn: &Connection, transaction_id: i64) -> Result<Vec<SharedExpenseSplit>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM shared_expense_splits WHERE transaction_id = ?1 ORDER BY id",
+        SHARE_COLUMNS
+    ))?;
+    let shares = stmt
+        .query_map([transaction_id], row_to_share)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(shares)
+}
+
+fn ensure_user_exists(conn: &Connection, user_id: i64) -> Result<()> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?1)",
+        [user_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(AppError::InvalidInput(format!("Uživatel {} neexistuje", user_id)));
+    }
+    Ok(())
+}
+
+/// Měsíční příjem uživatele z jeho aktivních příjmů podle jejich opakování.
+fn monthly_income(conn: &Connection, user_id: i64) -> Result<f64> {
+    let mut stmt = conn.prepare(
+        "SELECT amount, schedule, frequency, day_of_month FROM member_incomes
+         WHERE user_id = ?1 AND is_active = 1",
+    )?;
+    let incomes = stmt
+        .query_map([user_id], |row| {
+            Ok((
+                row.get::<_, Money>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i32>>(3)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let today = chrono::Local::now().date_naive();
+    let mut total = 0.0;
+    for (amount, schedule, frequency, day_of_month) in incomes {
+        let schedule =
+            Schedule::stored_or_legacy(schedule.as_deref(), &frequency, 1, day_of_month, today)?;
+        total += amount.to_f64() * schedule.monthly_factor();
+    }
+    Ok(total)
+}
+
+/// Spočítá podíly (částka, procento) pro zvolenou metodu; součet vždy sedí na haléř.
+fn compute_shares(
+    conn: &Connection,
+    total: Money,
+    method: &str,
+    shares: &[ShareInput],
+) -> Result<Vec<(Money, Option<f64>)>> {
+    match method {
+        "amount" => {
+            let amounts = shares
+                .iter()
+                .map(|share| {
+                    share.amount.ok_or_else(|| {
+                        AppError::InvalidInput(format!(
+                            "Chybí částka podílu uživatele {}",
+                            share.user_id
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let sum: Money = amounts.iter().copied().sum();
+            if sum != total {
+                return Err(AppError::InvalidInput(format!(
+                    "Součet podílů ({}) neodpovídá částce transakce ({})",
+                    sum, total
+                )));
+            }
+            Ok(amounts.into_iter().map(|amount| (amount, None)).collect())
+        }
+        "percentage" => {
+            let percentages = shares
+                .iter()
+                .map(|share| {
+                    share.percentage.filter(|p| p.is_finite()).ok_or_else(|| {
+                        AppError::InvalidInput(format!(
+                            "Chybí procento podílu uživatele {}",
+                            share.user_id
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let sum: f64 = percentages.iter().sum();
+            if (sum - 100.0).abs() > 0.001 {
+                return Err(AppError::InvalidInput(format!(
+                    "Součet procent musí být 100 (je {})",
+                    sum
+                )));
+            }
+            let amounts = total.allocate(&percentages);
+            Ok(amounts.into_iter().zip(percentages.into_iter().map(Some)).collect())
+        }
+        "income_ratio" => {
+            let incomes = shares
+                .iter()
+                .map(|share| monthly_income(conn, share.user_id))
+                .collect::<Result<Vec<_>>>()?;
+            let sum: f64 = incomes.iter().sum();
+            if sum <= 0.0 {
+                return Err(AppError::InvalidInput(
+                    "Uživatelé nemají žádné aktivní příjmy pro dělení podle příjmů".to_string(),
+                ));
+            }
+            let amounts = total.allocate(&incomes);
+            Ok(amounts
+                .into_iter()
+                .zip(incomes.iter().map(|income| Some((income / sum * 10000.0).round() / 100.0)))
+                .collect())
+        }
+        other => Err(AppError::InvalidInput(format!(
+            "Neznámá metoda dělení: {}",
+            other
+        ))),
+    }
+}
+
+/// Srovná rozdělení sdíleného výdaje se změněnou transakcí: procentní podíly se rozpočítají
+/// z nové částky, pevné částky musí dál dávat dohromady celou částku.
+pub(crate) fn reallocate_shares(
+    conn: &Connection,
+    transaction_id: i64,
+    transaction_type: &str,
+    total: Money,
+) -> Result<()> {
+    let shares = query_shares(conn, transaction_id)?;
+    if shares.is_empty() {
+        return Ok(());
+    }
+    if transaction_type != "expense" {
+        return Err(AppError::InvalidInput(
+            "Sdílet lze pouze výdaje, nejprve zrušte rozdělení".to_string(),
+        ));
+    }
+
+    let sum: Money = shares.iter().map(|share| share.amount).sum();
+    if sum == total {
+        return Ok(());
+    }
+    let Some(percentages) = shares
+        .iter()
+        .map(|share| share.percentage)
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Err(AppError::InvalidInput(format!(
+            "Součet podílů ({}) neodpovídá částce transakce ({}), upravte celé rozdělení",
+            sum, total
+        )));
+    };
+
+    for (share, amount) in shares.iter().zip(total.allocate(&percentages)) {
+        conn.execute(
+            "UPDATE shared_expense_splits SET amount = ?1 WHERE id = ?2",
+            rusqlite::params![amount, share.id],
+        )?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_shared_expense_splits(transaction_id: i64) -> Result<Vec<SharedExpenseSplit>> {
+    let conn = get_connection()?;
+    query_shares(&conn, transaction_id)
+}
+
+/// Rozdělí výdaj mezi uživatele metodou "amount", "percentage" nebo "income_ratio"
+/// (poměr měsíčních příjmů z `member_incomes`). Předchozí rozdělení se nahradí.
+#[tauri::command]
+pub fn split_shared_expense(
+    transaction_id: i64,
+    method: String,
+    shares: Vec<ShareInput>,
+) -> Result<Vec<SharedExpenseSplit>> {
+    with_transaction(|conn| {
+        let (amount, transaction_type): (Money, String) = conn
+            .query_row(
+                "SELECT amount, transaction_type FROM transactions WHERE id = ?1",
+                [transaction_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Transakce {} neexistuje", transaction_id)))?;
+
+        if transaction_type != "expense" {
+            return Err(AppError::InvalidInput(
+                "Sdílet lze pouze výdaje".to_string(),
+            ));
+        }
+        if shares.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Rozdělení musí obsahovat alespoň jeden podíl".to_string(),
+            ));
+        }
+        for (index, share) in shares.iter().enumerate() {
+            ensure_user_exists(conn, share.user_id)?;
+            if shares[..index].iter().any(|s| s.user_id == share.user_id) {
+                return Err(AppError::InvalidInput(format!(
+                    "Uživatel {} je v rozdělení vícekrát",
+                    share.user_id
+                )));
+            }
+        }
+
+        let computed = compute_shares(conn, amount, &method, &shares)?;
+        if computed.iter().any(|(amount, _)| *amount < Money::ZERO) {
+            return Err(AppError::InvalidInput(
+                "Podíl nesmí být záporný".to_string(),
+            ));
+        }
+
+        conn.execute(
+            "DELETE FROM shared_expense_splits WHERE transaction_id = ?1",
+            [transaction_id],
+        )?;
+        for (share, (amount, percentage)) in shares.iter().zip(computed) {
+            conn.execute(
+                "INSERT INTO shared_expense_splits (transaction_id, user_id, amount, percentage)
+                 VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![transaction_id, share.user_id, amount, percentage],
+            )?;
+        }
+        conn.execute(
+            "UPDATE transactions SET is_shared = 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [transaction_id],
+        )?;
+
+        query_shares(conn, transaction_id)
+    })
+}
+
+#[tauri::command]
+pub fn clear_shared_expense_splits(transaction_id: i64) -> Result<()> {
+    with_transaction(|conn| {
+        conn.execute(
+            "DELETE FROM shared_expense_splits WHERE transaction_id = ?1",
+            [transaction_id],
+        )?;
+        conn.execute(
+            "UPDATE transactions SET is_shared = 0, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [transaction_id],
+        )?;
+        Ok(())
+    })
+}
+
+/// Zaznamená vyrovnání. Jsou-li zadány oba účty, vytvoří se zároveň převod mezi nimi.
+#[tauri::command]
+pub fn record_settlement(input: RecordSettlementInput) -> Result<Settlement> {
+    if input.from_user_id == input.to_user_id {
+        return Err(AppError::InvalidInput(
+            "Vyrovnání musí být mezi dvěma různými uživateli".to_string(),
+        ));
+    }
+    if input.amount <= Money::ZERO {
+        return Err(AppError::InvalidInput(
+            "Částka vyrovnání musí být kladná".to_string(),
+        ));
+    }
+    if input.from_account_id.is_some() != input.to_account_id.is_some() {
+        return Err(AppError::InvalidInput(
+            "Pro převod je potřeba zadat zdrojový i cílový účet".to_string(),
+        ));
+    }
+
+    with_transaction(|conn| {
+        ensure_user_exists(conn, input.from_user_id)?;
+        ensure_user_exists(conn, input.to_user_id)?;
+        let currency = resolve_currency(conn, None, input.from_account_id)?;
+
+        let transaction_id = match (input.from_account_id, input.to_account_id) {
+            (Some(from_account_id), Some(to_account_id)) => {
+                let transfer = insert_transaction(
+                    conn,
+                    &Categorizer::load(conn)?,
+                    &CreateTransactionInput {
+                        date: input.date.clone(),
+                        amount: input.amount,
+                        currency: Some(currency.clone()),
+                        transaction_type: "transfer".to_string(),
+                        from_account_id: Some(from_account_id),
+                        to_account_id: Some(to_account_id),
+                        category_id: None,
+                        description: Some("Vyrovnání sdílených výdajů".to_string()),
+                        owner_user_id: Some(input.from_user_id),
+                        is_shared: None,
+                        status: None,
+                        notes: input.note.clone(),
+                        destination_amount: None,
+                        exchange_rate: None,
+                        fee_amount: None,
+                        external_id: None,
+                        value_date: None,
+                        variable_symbol: None,
+                        constant_symbol: None,
+                        specific_symbol: None,
+                        counterparty_account: None,
+                        payee_id: None,
+                        recurring_payment_id: None,
+                        occurrence_date: None,
+                    },
+                )?;
+                Some(transfer.id)
+            }
+            _ => None,
+        };
+
+        conn.execute(
+            "INSERT INTO settlements (from_user_id, to_user_id, amount, currency, date, transaction_id, note)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                input.from_user_id,
+                input.to_user_id,
+                input.amount,
+                currency,
+                input.date,
+                transaction_id,
+                input.note,
+            ],
+        )?;
+
+        let settlement = conn.query_row(
+            &format!("SELECT {} FROM settlements WHERE id = ?1", SETTLEMENT_COLUMNS),
+            [conn.last_insert_rowid()],
+            row_to_settlement,
+        )?;
+        Ok(settlement)
+    })
+}
+
+#[tauri::command]
+pub fn get_settlements(start_date: Option<String>, end_date: Option<String>) -> Result<Vec<Settlement>> {
+    let conn = get_connection()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM settlements
+         WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+         ORDER BY date DESC, id DESC",
+        SETTLEMENT_COLUMNS
+    ))?;
+    let settlements = stmt
+        .query_map(rusqlite::params![start_date, end_date], row_to_settlement)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(settlements)
+}
+
+/// Smaže záznam o vyrovnání; případný převod zůstává jako běžná transakce.
+#[tauri::command]
+pub fn delete_settlement(id: i64) -> Result<()> {
+    let conn = get_connection()?;
+    conn.execute("DELETE FROM settlements WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct BalanceTally {
+    paid_for_others: Money,
+    owed_to_others: Money,
+    settled_paid: Money,
+    settled_received: Money,
+}
+
+impl BalanceTally {
+    fn net(&self) -> Money {
+        self.paid_for_others - self.owed_to_others + self.settled_paid - self.settled_received
+    }
+}
+
+/// Hladové párování splátek: největší dlužník platí největšímu věřiteli, dokud se salda
+/// nevyrovnají. Pro n sald s nulovým součtem dá nejvýše n − 1 plateb.
+fn greedy_repayments(balances: &[(i64, Money)]) -> Vec<(i64, i64, Money)> {
+    let mut creditors: Vec<(i64, Money)> = balances
+        .iter()
+        .filter(|(_, net)| *net > Money::ZERO)
+        .copied()
+        .collect();
+    let mut debtors: Vec<(i64, Money)> = balances
+        .iter()
+        .filter(|(_, net)| *net < Money::ZERO)
+        .map(|(user_id, net)| (*user_id, -*net))
+        .collect();
+    creditors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    debtors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut repayments = Vec::new();
+    let (mut c, mut d) = (0, 0);
+    while c < creditors.len() && d < debtors.len() {
+        let amount = creditors[c].1.min(debtors[d].1);
+        repayments.push((debtors[d].0, creditors[c].0, amount));
+        creditors[c].1 -= amount;
+        debtors[d].1 -= amount;
+        if creditors[c].1.is_zero() {
+            c += 1;
+        }
+        if debtors[d].1.is_zero() {
+            d += 1;
+        }
+    }
+    repayments
+}
+
+/// Nad tolik nenulových sald se podskupiny neprohledávají (2^n stavů), stačí hladové párování.
+const MAX_EXACT_BALANCES: usize = 16;
+
+/// Nejmenší počet splátek: salda se rozdělí do co nejvíce podskupin s nulovým součtem
+/// (úplné prohledání podmnožin) a každá se vyrovná zvlášť, celkem n − počet skupin plateb.
+fn minimal_repayments(balances: &[(i64, Money)]) -> Vec<(i64, i64, Money)> {
+    let nets: Vec<(i64, Money)> = balances
+        .iter()
+        .filter(|(_, net)| !net.is_zero())
+        .copied()
+        .collect();
+    if nets.len() > MAX_EXACT_BALANCES {
+        return greedy_repayments(&nets);
+    }
+
+    // groups[mask] = nejvíce nulových podskupin, na které jde rozdělit salda z `mask`
+    let full = (1usize << nets.len()) - 1;
+    let mut sums = vec![Money::ZERO; full + 1];
+    let mut groups = vec![0usize; full + 1];
+    for mask in 1..=full {
+        let lowest = mask.trailing_zeros() as usize;
+        sums[mask] = sums[mask & (mask - 1)] + nets[lowest].1;
+        let best = (0..nets.len())
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| groups[mask ^ (1 << i)])
+            .max()
+            .unwrap_or(0);
+        groups[mask] = best + usize::from(sums[mask].is_zero());
+    }
+
+    // Zpětný průchod: odebírat salda po nejlepší cestě, nulový součet uzavře skupinu
+    let mut repayments = Vec::new();
+    let mut group = Vec::new();
+    let mut mask = full;
+    while mask != 0 {
+        let target = groups[mask] - usize::from(sums[mask].is_zero());
+        let index = (0..nets.len())
+            .find(|i| mask & (1 << i) != 0 && groups[mask ^ (1 << i)] == target)
+            .unwrap_or_else(|| mask.trailing_zeros() as usize);
+        group.push(nets[index]);
+        mask ^= 1 << index;
+        if sums[mask].is_zero() {
+            repayments.extend(greedy_repayments(&group));
+            group.clear();
+        }
+    }
+    repayments
+}
+
+/// Kdo komu dluží za období: plátce sdíleného výdaje (vlastník transakce, jinak vlastník
+/// zdrojového účtu) má pohledávku vůči ostatním ve výši jejich podílů. Platby ze
+/// společného účtu a podíly společného uživatele se nepočítají. Zaznamenaná vyrovnání
+/// dluh snižují. Vše ve výchozí měně.
+#[tauri::command]
+pub fn get_settle_up(start_date: Option<String>, end_date: Option<String>) -> Result<SettleUpReport> {
+    let conn = get_connection()?;
+    let mut converter = CurrencyConverter::to_default(&conn)?;
+
+    let mut users: HashMap<i64, (String, bool)> = HashMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT id, name, is_shared_user FROM users")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i32>(2)? == 1))
+        })?;
+        for row in rows {
+            let (id, name, shared) = row?;
+            users.insert(id, (name, shared));
+        }
+    }
+    let is_shared_user = |id: i64| users.get(&id).map(|(_, shared)| *shared).unwrap_or(true);
+
+    let mut tallies: BTreeMap<i64, BalanceTally> = BTreeMap::new();
+
+    let mut stmt = conn.prepare(
+        "SELECT s.user_id, s.amount, COALESCE(t.owner_user_id, a.owner_user_id), t.currency, t.date
+         FROM shared_expense_splits s
+         JOIN transactions t ON s.transaction_id = t.id
+         LEFT JOIN accounts a ON t.from_account_id = a.id
+         WHERE t.status = 'completed'
+         AND (?1 IS NULL OR date(t.date) >= ?1)
+         AND (?2 IS NULL OR date(t.date) <= ?2)",
+    )?;
+    let shares = stmt
+        .query_map(rusqlite::params![start_date, end_date], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Money>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for (user_id, amount, payer, currency, date) in shares {
+        let Some(payer) = payer else { continue };
+        if payer == user_id || is_shared_user(payer) || is_shared_user(user_id) {
+            continue;
+        }
+        let amount = converter.convert(amount, &currency, &date)?;
+        tallies.entry(payer).or_default().paid_for_others += amount;
+        tallies.entry(user_id).or_default().owed_to_others += amount;
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT from_user_id, to_user_id, amount, currency, date FROM settlements
+         WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)",
+    )?;
+    let settlements = stmt
+        .query_map(rusqlite::params![start_date, end_date], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Money>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for (from_user_id, to_user_id, amount, currency, date) in settlements {
+        let amount = converter.convert(amount, &currency, &date)?;
+        tallies.entry(from_user_id).or_default().settled_paid += amount;
+        tallies.entry(to_user_id).or_default().settled_received += amount;
+    }
+
+    let name_of = |id: i64| users.get(&id).map(|(name, _)| name.clone()).unwrap_or_default();
+
+    let balances: Vec<MemberBalance> = tallies
+        .iter()
+        .map(|(user_id, tally)| MemberBalance {
+            user_id: *user_id,
+            user_name: name_of(*user_id),
+            paid_for_others: tally.paid_for_others,
+            owed_to_others: tally.owed_to_others,
+            settled_paid: tally.settled_paid,
+            settled_received: tally.settled_received,
+            net: tally.net(),
+        })
+        .collect();
+
+    let nets: Vec<(i64, Money)> = balances.iter().map(|b| (b.user_id, b.net)).collect();
+    let repayments = minimal_repayments(&nets)
+        .into_iter()
+        .map(|(from_user_id, to_user_id, amount)| Repayment {
+            from_user_id,
+            from_user_name: name_of(from_user_id),
+            to_user_id,
+            to_user_name: name_of(to_user_id),
+            amount,
+        })
+        .collect();
+
+    Ok(SettleUpReport {
+        currency: converter.target().to_string(),
+        balances,
+        repayments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn czk(text: &str) -> Money {
+        text.parse().unwrap()
+    }
+
+    /// Výdaj 100 Kč rozdělený 70/30, podíly s procenty nebo jen s pevnými částkami.
+    fn setup(percentages: bool) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, name) VALUES (11, 'Petr'), (12, 'Jana');
+             INSERT INTO transactions (id, date, amount, transaction_type)
+             VALUES (1, '2024-03-01', 10000, 'expense');
+             INSERT INTO shared_expense_splits (transaction_id, user_id, amount, percentage)
+             VALUES (1, 11, 7000, 70), (1, 12, 3000, 30);",
+        )
+        .unwrap();
+        if !percentages {
+            conn.execute("UPDATE shared_expense_splits SET percentage = NULL", [])
+                .unwrap();
+        }
+        conn
+    }
+
+    fn share_amounts(conn: &Connection) -> Vec<i64> {
+        query_shares(conn, 1)
+            .unwrap()
+            .iter()
+            .map(|share| share.amount.minor())
+            .collect()
+    }
+
+    #[test]
+    fn percentage_shares_follow_new_amount() {
+        let conn = setup(true);
+        reallocate_shares(&conn, 1, "expense", czk("40.01")).unwrap();
+        assert_eq!(share_amounts(&conn), [2801, 1200]);
+    }
+
+    #[test]
+    fn fixed_shares_must_be_edited_with_the_amount() {
+        let conn = setup(false);
+        reallocate_shares(&conn, 1, "expense", czk("100")).unwrap();
+        assert!(matches!(
+            reallocate_shares(&conn, 1, "expense", czk("40")),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert_eq!(share_amounts(&conn), [7000, 3000]);
+    }
+
+    #[test]
+    fn only_expenses_keep_shares() {
+        let conn = setup(true);
+        assert!(matches!(
+            reallocate_shares(&conn, 1, "income", czk("100")),
+            Err(AppError::InvalidInput(_))
+        ));
+        conn.execute("DELETE FROM shared_expense_splits", [])
+            .unwrap();
+        reallocate_shares(&conn, 1, "income", czk("100")).unwrap();
+    }
+
+    /// Zůstatky po provedení splátek.
+    fn settled(balances: &[(i64, Money)], repayments: &[(i64, i64, Money)]) -> Vec<Money> {
+        balances
+            .iter()
+            .map(|(user_id, net)| {
+                repayments.iter().fold(*net, |net, (from, to, amount)| {
+                    match (from == user_id, to == user_id) {
+                        (true, _) => net + *amount,
+                        (_, true) => net - *amount,
+                        _ => net,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn repayments_use_zero_sum_groups() {
+        // {10, −7, −3} a {4, −4} se vyrovnají třemi platbami, hladové párování jich potřebuje čtyři
+        let balances = [
+            (1, czk("10")),
+            (2, czk("4")),
+            (3, czk("-7")),
+            (4, czk("-4")),
+            (5, czk("-3")),
+            (6, Money::ZERO),
+        ];
+        assert_eq!(greedy_repayments(&balances).len(), 4);
+
+        let repayments = minimal_repayments(&balances);
+        assert_eq!(repayments.len(), 3);
+        assert!(repayments.contains(&(4, 2, czk("4"))));
+        assert!(settled(&balances, &repayments)
+            .iter()
+            .all(|net| net.is_zero()));
+    }
+
+    #[test]
+    fn repayments_without_zero_sum_subgroups() {
+        let balances = [(1, czk("6")), (2, czk("4")), (3, czk("-5")), (4, czk("-5"))];
+        let repayments = minimal_repayments(&balances);
+        assert_eq!(repayments.len(), 3);
+        assert!(settled(&balances, &repayments)
+            .iter()
+            .all(|net| net.is_zero()));
+
+        assert!(minimal_repayments(&[(1, Money::ZERO)]).is_empty());
+        assert!(minimal_repayments(&[]).is_empty());
+    }
+}
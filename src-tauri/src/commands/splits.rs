@@ -0,0 +1,244 @@
+use crate::db::connection::{get_connection, with_transaction};
+use crate::models::{CreateSplitInput, SplitLineInput, TransactionSplit, UpdateSplitInput};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use rusqlite::{Connection, OptionalExtension};
+
+const SPLIT_COLUMNS: &str = "id, transaction_id, amount, category_id, note, created_at, updated_at";
+
+fn row_to_split(row: &rusqlite::Row) -> rusqlite::Result<TransactionSplit> {
+    Ok(TransactionSplit {
+        id: row.get(0)?,
+        transaction_id: row.get(1)?,
+        amount: row.get(2)?,
+        category_id: row.get(3)?,
+        note: row.get(4)?,
+        tag_ids: Vec::new(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn load_split_tags(conn: &Connection, split_id: i64) -> Result<Vec<i64>> {
+    let mut stmt =
+        conn.prepare("SELECT tag_id FROM transaction_split_tags WHERE split_id = ?1 ORDER BY tag_id")?;
+    let tags = stmt
+        .query_map([split_id], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(tags)
+}
+
+fn query_split(conn: &Connection, id: i64) -> Result<TransactionSplit> {
+    let mut split = conn.query_row(
+        &format!("SELECT {} FROM transaction_splits WHERE id = ?1", SPLIT_COLUMNS),
+        [id],
+        row_to_split,
+    )?;
+    split.tag_ids = load_split_tags(conn, id)?;
+    Ok(split)
+}
+
+fn query_splits(conn: &Connection, transaction_id: i64) -> Result<Vec<TransactionSplit>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM transaction_splits WHERE transaction_id = ?1 ORDER BY id",
+        SPLIT_COLUMNS
+    ))?;
+    let mut splits = stmt
+        .query_map([transaction_id], row_to_split)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for split in &mut splits {
+        split.tag_ids = load_split_tags(conn, split.id)?;
+    }
+    Ok(splits)
+}
+
+/// Součet rozpadu transakce, případně bez jednoho řádku (při jeho úpravě).
+pub(crate) fn splits_total(conn: &Connection, transaction_id: i64, except_split: Option<i64>) -> Result<Money> {
+    let total = conn.query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM transaction_splits
+         WHERE transaction_id = ?1 AND (?2 IS NULL OR id != ?2)",
+        rusqlite::params![transaction_id, except_split],
+        |row| row.get(0),
+    )?;
+    Ok(total)
+}
+
+/// Rozpad musí dát dohromady přesně částku transakce (nulový součet = bez rozpadu).
+pub(crate) fn ensure_splits_match(total: Money, amount: Money) -> Result<()> {
+    if !total.is_zero() && total != amount {
+        return Err(AppError::InvalidInput(format!(
+            "Součet rozpadu ({}) neodpovídá částce transakce ({}), upravte celý rozpad",
+            total, amount
+        )));
+    }
+    Ok(())
+}
+
+fn transaction_amount(conn: &Connection, transaction_id: i64) -> Result<Money> {
+    conn.query_row(
+        "SELECT amount FROM transactions WHERE id = ?1",
+        [transaction_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| AppError::NotFound(format!("Transakce {} neexistuje", transaction_id)))
+}
+
+fn validate_line(conn: &Connection, amount: Money, category_id: Option<i64>, tag_ids: &[i64]) -> Result<()> {
+    if amount <= Money::ZERO {
+        return Err(AppError::InvalidInput(
+            "Částka řádku rozpadu musí být kladná".to_string(),
+        ));
+    }
+
+    if let Some(category_id) = category_id {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM categories WHERE id = ?1)",
+            [category_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(AppError::InvalidInput(format!(
+                "Kategorie {} neexistuje",
+                category_id
+            )));
+        }
+    }
+
+    for tag_id in tag_ids {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM tags WHERE id = ?1)",
+            [tag_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(AppError::InvalidInput(format!("Štítek {} neexistuje", tag_id)));
+        }
+    }
+
+    Ok(())
+}
+
+fn replace_split_tags(conn: &Connection, split_id: i64, tag_ids: &[i64]) -> Result<()> {
+    conn.execute("DELETE FROM transaction_split_tags WHERE split_id = ?1", [split_id])?;
+    for tag_id in tag_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO transaction_split_tags (split_id, tag_id) VALUES (?1, ?2)",
+            [split_id, *tag_id],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_split(
+    conn: &Connection,
+    transaction_id: i64,
+    amount: Money,
+    category_id: Option<i64>,
+    note: &Option<String>,
+    tag_ids: &[i64],
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO transaction_splits (transaction_id, amount, category_id, note)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![transaction_id, amount, category_id, note],
+    )?;
+    let id = conn.last_insert_rowid();
+    replace_split_tags(conn, id, tag_ids)?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn get_transaction_splits(transaction_id: i64) -> Result<Vec<TransactionSplit>> {
+    let conn = get_connection()?;
+    query_splits(&conn, transaction_id)
+}
+
+/// Nahradí celý rozpad transakce. Řádky musí dát dohromady přesně částku transakce;
+/// prázdný seznam rozpad zruší.
+#[tauri::command]
+pub fn set_transaction_splits(transaction_id: i64, splits: Vec<SplitLineInput>) -> Result<Vec<TransactionSplit>> {
+    with_transaction(|conn| {
+        let amount = transaction_amount(conn, transaction_id)?;
+
+        for line in &splits {
+            validate_line(conn, line.amount, line.category_id, line.tag_ids.as_deref().unwrap_or_default())?;
+        }
+
+        ensure_splits_match(splits.iter().map(|line| line.amount).sum(), amount)?;
+
+        conn.execute(
+            "DELETE FROM transaction_splits WHERE transaction_id = ?1",
+            [transaction_id],
+        )?;
+        for line in &splits {
+            insert_split(
+                conn,
+                transaction_id,
+                line.amount,
+                line.category_id,
+                &line.note,
+                line.tag_ids.as_deref().unwrap_or_default(),
+            )?;
+        }
+
+        query_splits(conn, transaction_id)
+    })
+}
+
+/// Přidá řádek rozpadu; výsledný rozpad musí dát přesně částku transakce
+/// (rozdělení na více řádků najednou umí `set_transaction_splits`).
+#[tauri::command]
+pub fn create_transaction_split(input: CreateSplitInput) -> Result<TransactionSplit> {
+    with_transaction(|conn| {
+        let amount = transaction_amount(conn, input.transaction_id)?;
+        let tag_ids = input.tag_ids.unwrap_or_default();
+        validate_line(conn, input.amount, input.category_id, &tag_ids)?;
+        ensure_splits_match(splits_total(conn, input.transaction_id, None)? + input.amount, amount)?;
+
+        let id = insert_split(
+            conn,
+            input.transaction_id,
+            input.amount,
+            input.category_id,
+            &input.note,
+            &tag_ids,
+        )?;
+        query_split(conn, id)
+    })
+}
+
+/// Upraví řádek rozpadu; změna částky musí zachovat součet rozpadu.
+#[tauri::command]
+pub fn update_transaction_split(id: i64, input: UpdateSplitInput) -> Result<TransactionSplit> {
+    with_transaction(|conn| {
+        let split = query_split(conn, id)?;
+        let amount = transaction_amount(conn, split.transaction_id)?;
+        let tag_ids = input.tag_ids.unwrap_or(split.tag_ids);
+        validate_line(conn, input.amount, input.category_id, &tag_ids)?;
+        ensure_splits_match(splits_total(conn, split.transaction_id, Some(id))? + input.amount, amount)?;
+
+        conn.execute(
+            "UPDATE transaction_splits SET amount = ?1, category_id = ?2, note = ?3,
+             updated_at = CURRENT_TIMESTAMP WHERE id = ?4",
+            rusqlite::params![input.amount, input.category_id, input.note, id],
+        )?;
+        replace_split_tags(conn, id, &tag_ids)?;
+
+        query_split(conn, id)
+    })
+}
+
+/// Smaže řádek rozpadu; zbylé řádky musí stále dát částku transakce (poslední řádek lze smazat vždy).
+#[tauri::command]
+pub fn delete_transaction_split(id: i64) -> Result<()> {
+    with_transaction(|conn| {
+        let split = query_split(conn, id)?;
+        let amount = transaction_amount(conn, split.transaction_id)?;
+        ensure_splits_match(splits_total(conn, split.transaction_id, Some(id))?, amount)?;
+
+        conn.execute("DELETE FROM transaction_splits WHERE id = ?1", [id])?;
+        Ok(())
+    })
+}
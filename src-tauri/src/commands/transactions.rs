@@ -1,23 +1,223 @@
-use crate::commands::accounts::update_account_balance;
-use crate::db::connection::get_connection;
+use crate::db::connection::{get_connection, with_transaction};
+use crate::db::currency::{find_rate, resolve_currency, CurrencyConverter};
+use crate::commands::duplicates::refresh_fingerprint;
+use crate::commands::payees::{assign_payee, load_payee_matchers, PayeeMatchers};
+use crate::commands::recurring::match_recurring_payment;
+use crate::commands::rules::{apply_rules, load_rules, CompiledRule};
+use crate::commands::search::fts_query;
+use crate::commands::shared_expenses::reallocate_shares;
+use crate::commands::splits::{ensure_splits_match, splits_total};
+use crate::db::ledger::{apply_entry, reverse_entry, LedgerEntry};
 use crate::models::{
-    CreateTagInput, CreateTransactionInput, Tag, Transaction, TransactionFilters,
-    UpdateTransactionInput,
+    CreateTagInput, CreateTransactionInput, PageRequest, Tag, Transaction, TransactionFilters,
+    TransactionPage, UpdateTransactionInput,
 };
-use crate::utils::error::Result;
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use crate::utils::payment::{normalize_account, PaymentDetails};
+use rusqlite::Connection;
+
+pub(crate) const TRANSACTION_COLUMNS: &str = "id, date, amount, currency, transaction_type,
+    from_account_id, to_account_id, category_id, description, owner_user_id, is_shared, status,
+    recurring_payment_id, flow_group_id, notes, created_at, updated_at,
+    destination_amount, destination_currency, exchange_rate, fee_amount, external_id,
+    value_date, variable_symbol, constant_symbol, specific_symbol, counterparty_account, fingerprint,
+    payee_id, reconciliation_status, reconciliation_id, occurrence_date";
+
+pub(crate) fn row_to_transaction(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
+    Ok(Transaction {
+        id: row.get(0)?,
+        date: row.get(1)?,
+        amount: row.get(2)?,
+        currency: row.get(3)?,
+        transaction_type: row.get(4)?,
+        from_account_id: row.get(5)?,
+        to_account_id: row.get(6)?,
+        category_id: row.get(7)?,
+        description: row.get(8)?,
+        owner_user_id: row.get(9)?,
+        is_shared: row.get::<_, i32>(10)? != 0,
+        status: row.get(11)?,
+        destination_amount: row.get(17)?,
+        destination_currency: row.get(18)?,
+        exchange_rate: row.get(19)?,
+        fee_amount: row.get(20)?,
+        external_id: row.get(21)?,
+        value_date: row.get(22)?,
+        variable_symbol: row.get(23)?,
+        constant_symbol: row.get(24)?,
+        specific_symbol: row.get(25)?,
+        counterparty_account: row.get(26)?,
+        fingerprint: row.get(27)?,
+        payee_id: row.get(28)?,
+        reconciliation_status: row.get(29)?,
+        reconciliation_id: row.get(30)?,
+        recurring_payment_id: row.get(12)?,
+        occurrence_date: row.get(31)?,
+        flow_group_id: row.get(13)?,
+        notes: row.get(14)?,
+        created_at: row.get(15)?,
+        updated_at: row.get(16)?,
+    })
+}
 
-#[tauri::command]
-pub fn create_transaction(input: CreateTransactionInput) -> Result<Transaction> {
-    let conn = get_connection()?;
+/// Odsouhlasená transakce je uzamčená proti úpravám a smazání.
+pub(crate) fn ensure_unlocked(transaction: &Transaction) -> Result<()> {
+    if transaction.reconciliation_status == "reconciled" {
+        return Err(AppError::InvalidInput(format!(
+            "Transakce {} je odsouhlasená s výpisem, nejprve ji odemkněte",
+            transaction.id
+        )));
+    }
+    Ok(())
+}
+
+/// Načte transakci přes již držené připojení (např. uvnitř `with_transaction`).
+pub(crate) fn query_transaction(conn: &Connection, id: i64) -> Result<Transaction> {
+    conn.query_row(
+        &format!("SELECT {} FROM transactions WHERE id = ?1", TRANSACTION_COLUMNS),
+        [id],
+        row_to_transaction,
+    )
+    .map_err(Into::into)
+}
+
+/// Údaje potřebné k doplnění cílové strany převodu.
+struct TransferRequest<'a> {
+    transaction_type: &'a str,
+    date: &'a str,
+    amount: Money,
+    currency: &'a str,
+    to_account_id: Option<i64>,
+    destination_amount: Option<Money>,
+    exchange_rate: Option<f64>,
+    fee_amount: Option<Money>,
+}
+
+#[derive(Default)]
+struct TransferLegs {
+    destination_amount: Option<Money>,
+    destination_currency: Option<String>,
+    exchange_rate: Option<f64>,
+    fee_amount: Option<Money>,
+}
+
+/// Doplní cílovou stranu převodu v měně cílového účtu.
+///
+/// Mezi účty v různých měnách se cílová částka vezme ze vstupu, jinak se dopočítá
+/// ze zadaného kurzu nebo z kurzovního lístku k datu převodu. Uložený kurz je vždy
+/// ten skutečně použitý (cílová částka / zdrojová částka).
+fn resolve_transfer_legs(conn: &Connection, req: &TransferRequest) -> Result<TransferLegs> {
+    if req.transaction_type != "transfer" {
+        if req.destination_amount.is_some() || req.exchange_rate.is_some() || req.fee_amount.is_some() {
+            return Err(AppError::InvalidInput(
+                "Cílovou částku, kurz a poplatek lze zadat jen u převodu".to_string(),
+            ));
+        }
+        return Ok(TransferLegs::default());
+    }
+
+    if matches!(req.fee_amount, Some(fee) if fee < Money::ZERO) {
+        return Err(AppError::InvalidInput("Poplatek nesmí být záporný".to_string()));
+    }
+    if let Some(rate) = req.exchange_rate {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(AppError::InvalidInput(format!("Neplatný kurz: {}", rate)));
+        }
+    }
+
+    let to_account_id = match req.to_account_id {
+        Some(id) => id,
+        None => {
+            return Ok(TransferLegs {
+                fee_amount: req.fee_amount,
+                ..TransferLegs::default()
+            })
+        }
+    };
+    let destination_currency = resolve_currency(conn, None, Some(to_account_id))?;
+
+    let destination_amount = if destination_currency == req.currency {
+        req.destination_amount
+    } else {
+        Some(match (req.destination_amount, req.exchange_rate) {
+            (Some(amount), _) => amount,
+            (None, Some(rate)) => req.amount.mul_f64(rate),
+            (None, None) => {
+                let day = req.date.get(..10).unwrap_or(req.date);
+                req.amount.mul_f64(find_rate(conn, req.currency, &destination_currency, day)?)
+            }
+        })
+    };
+
+    let exchange_rate = match destination_amount {
+        Some(destination) if !req.amount.is_zero() => {
+            Some(destination.minor() as f64 / req.amount.minor() as f64)
+        }
+        _ => None,
+    };
+
+    Ok(TransferLegs {
+        destination_amount,
+        destination_currency: Some(destination_currency),
+        exchange_rate,
+        fee_amount: req.fee_amount,
+    })
+}
+
+/// Pravidla kategorizace a obchodníci zkompilovaní jednou pro celou dávku vkládaných transakcí.
+pub(crate) struct Categorizer {
+    rules: Vec<CompiledRule>,
+    payees: PayeeMatchers,
+}
+
+impl Categorizer {
+    pub(crate) fn load(conn: &Connection) -> Result<Self> {
+        Ok(Categorizer {
+            rules: load_rules(conn)?,
+            payees: load_payee_matchers(conn)?,
+        })
+    }
+}
+
+/// Vloží transakci a promítne ji do zůstatků účtů přes již držené připojení.
+pub(crate) fn insert_transaction(
+    conn: &Connection,
+    categorizer: &Categorizer,
+    input: &CreateTransactionInput,
+) -> Result<Transaction> {
+    let status = input.status.clone().unwrap_or_else(|| "completed".to_string());
+    let currency = resolve_currency(
+        conn,
+        input.currency.clone(),
+        input.from_account_id.or(input.to_account_id),
+    )?;
+    let legs = resolve_transfer_legs(
+        conn,
+        &TransferRequest {
+            transaction_type: &input.transaction_type,
+            date: &input.date,
+            amount: input.amount,
+            currency: &currency,
+            to_account_id: input.to_account_id,
+            destination_amount: input.destination_amount,
+            exchange_rate: input.exchange_rate,
+            fee_amount: input.fee_amount,
+        },
+    )?;
 
     conn.execute(
         "INSERT INTO transactions (date, amount, currency, transaction_type, from_account_id,
-         to_account_id, category_id, description, owner_user_id, is_shared, status, notes)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+         to_account_id, category_id, description, owner_user_id, is_shared, status, notes,
+         destination_amount, destination_currency, exchange_rate, fee_amount, external_id,
+         value_date, variable_symbol, constant_symbol, specific_symbol, counterparty_account, payee_id,
+         recurring_payment_id, occurrence_date, recurring_generated)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+         ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?25 IS NOT NULL)",
         rusqlite::params![
             input.date,
             input.amount,
-            input.currency.unwrap_or_else(|| "CZK".to_string()),
+            currency,
             input.transaction_type,
             input.from_account_id,
             input.to_account_id,
@@ -25,246 +225,428 @@ pub fn create_transaction(input: CreateTransactionInput) -> Result<Transaction>
             input.description,
             input.owner_user_id,
             input.is_shared.unwrap_or(false) as i32,
-            input.status.clone().unwrap_or_else(|| "completed".to_string()),
+            status,
             input.notes,
+            legs.destination_amount,
+            legs.destination_currency,
+            legs.exchange_rate,
+            legs.fee_amount,
+            input.external_id,
+            input.value_date,
+            input.variable_symbol,
+            input.constant_symbol,
+            input.specific_symbol,
+            input.counterparty_account.as_deref().map(normalize_account),
+            input.payee_id,
+            input.recurring_payment_id,
+            input.occurrence_date,
         ],
     )?;
 
     let id = conn.last_insert_rowid();
-
-    // Aktualizace zůstatků účtů
-    if input.status.as_deref() != Some("planned") {
-        match input.transaction_type.as_str() {
-            "expense" => {
-                if let Some(from_id) = input.from_account_id {
-                    drop(conn);
-                    update_account_balance(from_id, -input.amount)?;
-                }
-            }
-            "income" => {
-                if let Some(to_id) = input.to_account_id {
-                    drop(conn);
-                    update_account_balance(to_id, input.amount)?;
-                }
-            }
-            "transfer" => {
-                if let Some(from_id) = input.from_account_id {
-                    drop(conn);
-                    update_account_balance(from_id, -input.amount)?;
-                    if let Some(to_id) = input.to_account_id {
-                        update_account_balance(to_id, input.amount)?;
-                    }
-                }
-            }
-            _ => {}
-        }
+    refresh_fingerprint(conn, id)?;
+
+    // Aktualizace zůstatků účtů ve stejné SQL transakci
+    let transaction = query_transaction(conn, id)?;
+    apply_entry(conn, &LedgerEntry::from(&transaction))?;
+
+    // Pravidla kategorizace a výchozí údaje obchodníka doplní jen to, co vstup nechal prázdné
+    let ruled = apply_rules(conn, &categorizer.rules, &transaction, false)?;
+    let transaction = if ruled { query_transaction(conn, id)? } else { transaction };
+    let assigned = assign_payee(conn, &categorizer.payees, &transaction, false)?;
+    if match_recurring_payment(conn, &transaction)? || assigned {
+        return query_transaction(conn, id);
     }
+    Ok(transaction)
+}
 
-    get_transaction_by_id(id)
+/// Ruční zadání: platební údaje se ověří (import je přebírá tak, jak jsou ve výpisu).
+#[tauri::command]
+pub fn create_transaction(input: CreateTransactionInput) -> Result<Transaction> {
+    let details = PaymentDetails::validated(
+        input.variable_symbol.as_deref(),
+        input.constant_symbol.as_deref(),
+        input.specific_symbol.as_deref(),
+        input.counterparty_account.as_deref(),
+    )?;
+    let input = CreateTransactionInput {
+        variable_symbol: details.variable_symbol,
+        constant_symbol: details.constant_symbol,
+        specific_symbol: details.specific_symbol,
+        counterparty_account: details.counterparty_account,
+        ..input
+    };
+    with_transaction(|conn| insert_transaction(conn, &Categorizer::load(conn)?, &input))
 }
 
 #[tauri::command]
 pub fn get_transaction_by_id(id: i64) -> Result<Transaction> {
     let conn = get_connection()?;
-
-    conn.query_row("SELECT * FROM transactions WHERE id = ?1", [id], |row| {
-        Ok(Transaction {
-            id: row.get(0)?,
-            date: row.get(1)?,
-            amount: row.get(2)?,
-            currency: row.get(3)?,
-            transaction_type: row.get(4)?,
-            from_account_id: row.get(5)?,
-            to_account_id: row.get(6)?,
-            category_id: row.get(7)?,
-            description: row.get(8)?,
-            owner_user_id: row.get(9)?,
-            is_shared: row.get::<_, i32>(10)? != 0,
-            status: row.get(11)?,
-            recurring_payment_id: row.get(12)?,
-            flow_group_id: row.get(13)?,
-            notes: row.get(14)?,
-            created_at: row.get(15)?,
-            updated_at: row.get(16)?,
-        })
-    })
-    .map_err(Into::into)
+    query_transaction(&conn, id)
 }
 
 #[tauri::command]
 pub fn get_transactions() -> Result<Vec<Transaction>> {
     let conn = get_connection()?;
 
-    let mut stmt = conn.prepare("SELECT * FROM transactions ORDER BY date DESC, id DESC")?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM transactions ORDER BY date DESC, id DESC",
+        TRANSACTION_COLUMNS
+    ))?;
     let transactions = stmt
-        .query_map([], |row| {
-            Ok(Transaction {
-                id: row.get(0)?,
-                date: row.get(1)?,
-                amount: row.get(2)?,
-                currency: row.get(3)?,
-                transaction_type: row.get(4)?,
-                from_account_id: row.get(5)?,
-                to_account_id: row.get(6)?,
-                category_id: row.get(7)?,
-                description: row.get(8)?,
-                owner_user_id: row.get(9)?,
-                is_shared: row.get::<_, i32>(10)? != 0,
-                status: row.get(11)?,
-                recurring_payment_id: row.get(12)?,
-                flow_group_id: row.get(13)?,
-                notes: row.get(14)?,
-                created_at: row.get(15)?,
-                updated_at: row.get(16)?,
-            })
-        })?
+        .query_map([], row_to_transaction)?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
     Ok(transactions)
 }
 
-#[tauri::command]
-pub fn get_transactions_filtered(filters: TransactionFilters) -> Result<Vec<Transaction>> {
-    let conn = get_connection()?;
+/// `?, ?, ?` pro `count` parametrů
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
 
-    let mut query = String::from("SELECT * FROM transactions WHERE 1=1");
+/// Podmínky `AND ...` nad transakcemi s aliasem `t` a jejich parametry;
+/// prázdný seznam nic neomezuje.
+pub(crate) fn filter_conditions(
+    filters: &TransactionFilters,
+) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut sql = String::new();
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     if let Some(start) = &filters.start_date {
-        query.push_str(" AND date >= ?");
+        sql.push_str(" AND t.date >= ?");
         params.push(Box::new(start.clone()));
     }
 
     if let Some(end) = &filters.end_date {
-        query.push_str(" AND date <= ?");
+        sql.push_str(" AND t.date <= ?");
         params.push(Box::new(end.clone()));
     }
 
+    // Vlastník transakce, u nepřiřazených vlastník účtu
+    if let Some(ids) = filters.user_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        sql.push_str(&format!(
+            " AND COALESCE(t.owner_user_id, (SELECT a.owner_user_id FROM accounts a
+                 WHERE a.id = COALESCE(t.from_account_id, t.to_account_id))) IN ({})",
+            placeholders(ids.len())
+        ));
+        params.extend(ids.iter().map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>));
+    }
+
+    if let Some(ids) = filters.account_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        let list = placeholders(ids.len());
+        sql.push_str(&format!(
+            " AND (t.from_account_id IN ({}) OR t.to_account_id IN ({}))",
+            list, list
+        ));
+        for _ in 0..2 {
+            params.extend(ids.iter().map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>));
+        }
+    }
+
+    if let Some(ids) = filters.bank_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        sql.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM accounts a
+                 WHERE a.id IN (t.from_account_id, t.to_account_id) AND a.bank_id IN ({}))",
+            placeholders(ids.len())
+        ));
+        params.extend(ids.iter().map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>));
+    }
+
+    // Kategorie transakce nebo některé položky jejího rozpadu
+    if let Some(ids) = filters.category_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        let categories = if filters.include_subcategories.unwrap_or(false) {
+            format!(
+                "WITH RECURSIVE tree(id) AS (
+                     SELECT id FROM categories WHERE id IN ({})
+                     UNION SELECT c.id FROM categories c JOIN tree ON c.parent_category_id = tree.id
+                 ) SELECT id FROM tree",
+                placeholders(ids.len())
+            )
+        } else {
+            placeholders(ids.len())
+        };
+        sql.push_str(&format!(
+            " AND (t.category_id IN ({}) OR EXISTS (SELECT 1 FROM transaction_splits s
+                 WHERE s.transaction_id = t.id AND s.category_id IN ({})))",
+            categories, categories
+        ));
+        for _ in 0..2 {
+            params.extend(ids.iter().map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>));
+        }
+    }
+
+    if let Some(ids) = filters.tag_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        sql.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM transaction_tags tt
+                 WHERE tt.transaction_id = t.id AND tt.tag_id IN ({}))",
+            placeholders(ids.len())
+        ));
+        params.extend(ids.iter().map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>));
+    }
+
+    if filters.untagged.unwrap_or(false) {
+        sql.push_str(" AND NOT EXISTS (SELECT 1 FROM transaction_tags tt WHERE tt.transaction_id = t.id)");
+    }
+
+    if let Some(is_shared) = filters.is_shared {
+        sql.push_str(" AND COALESCE(t.is_shared, 0) = ?");
+        params.push(Box::new(is_shared as i32));
+    }
+
+    let symbols = [
+        ("variable_symbol", &filters.variable_symbol),
+        ("constant_symbol", &filters.constant_symbol),
+        ("specific_symbol", &filters.specific_symbol),
+    ];
+    for (column, value) in symbols {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            sql.push_str(&format!(" AND LTRIM(t.{}, '0') = LTRIM(?, '0')", column));
+            params.push(Box::new(value.to_string()));
+        }
+    }
+
+    if let Some(account) = filters.counterparty_account.as_deref().filter(|a| !a.trim().is_empty()) {
+        sql.push_str(" AND t.counterparty_account = ?");
+        params.push(Box::new(normalize_account(account)));
+    }
+
+    if let Some(types) = filters.types.as_ref().filter(|types| !types.is_empty()) {
+        sql.push_str(&format!(" AND t.transaction_type IN ({})", placeholders(types.len())));
+        params.extend(types.iter().map(|v| Box::new(v.clone()) as Box<dyn rusqlite::ToSql>));
+    }
+
+    if let Some(statuses) = filters.statuses.as_ref().filter(|statuses| !statuses.is_empty()) {
+        sql.push_str(&format!(" AND t.status IN ({})", placeholders(statuses.len())));
+        params.extend(statuses.iter().map(|v| Box::new(v.clone()) as Box<dyn rusqlite::ToSql>));
+    }
+
     if let Some(min) = filters.min_amount {
-        query.push_str(" AND amount >= ?");
+        sql.push_str(" AND t.amount >= ?");
         params.push(Box::new(min));
     }
 
     if let Some(max) = filters.max_amount {
-        query.push_str(" AND amount <= ?");
+        sql.push_str(" AND t.amount <= ?");
         params.push(Box::new(max));
     }
 
-    if let Some(search) = &filters.search_query {
-        query.push_str(" AND (description LIKE ? OR notes LIKE ?)");
-        let pattern = format!("%{}%", search);
-        params.push(Box::new(pattern.clone()));
-        params.push(Box::new(pattern));
+    if let Some(search) = filters.search_query.as_deref().and_then(fts_query) {
+        sql.push_str(
+            " AND t.id IN (SELECT rowid FROM transactions_fts WHERE transactions_fts MATCH ?)",
+        );
+        params.push(Box::new(search));
     }
 
-    query.push_str(" ORDER BY date DESC, id DESC");
+    (sql, params)
+}
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 1000;
+
+/// Výraz `ORDER BY` pro stránku; `t.id` drží pořadí stabilní mezi stránkami.
+pub(crate) fn page_order(page: &PageRequest) -> Result<String> {
+    let column = match page.sort_by.as_deref().unwrap_or("date") {
+        "date" => "t.date",
+        "amount" => "t.amount",
+        "description" => "t.description COLLATE NOCASE",
+        "category" => "(SELECT c.name FROM categories c WHERE c.id = t.category_id) COLLATE NOCASE",
+        "payee" => "(SELECT p.name FROM payees p WHERE p.id = t.payee_id) COLLATE NOCASE",
+        "created_at" => "t.created_at",
+        other => {
+            return Err(AppError::InvalidInput(format!("Nelze řadit podle '{}'", other)));
+        }
+    };
+    let direction = match page.sort_direction.as_deref().unwrap_or("desc") {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        other => {
+            return Err(AppError::InvalidInput(format!("Neplatný směr řazení '{}'", other)));
+        }
+    };
+    Ok(format!("{} {}, t.id {}", column, direction, direction))
+}
+
+/// Vyfiltrované transakce po stránkách. Souhrn (počet, příjmy, výdaje, čistý
+/// výsledek) se počítá z celého výběru, ne jen z vrácené stránky.
+#[tauri::command]
+pub fn get_transactions_filtered(
+    filters: TransactionFilters,
+    page: Option<PageRequest>,
+) -> Result<TransactionPage> {
+    let conn = get_connection()?;
+    query_page(&conn, &filters, &page.unwrap_or_default())
+}
 
-    let mut stmt = conn.prepare(&query)?;
+pub(crate) fn query_page(
+    conn: &Connection,
+    filters: &TransactionFilters,
+    page: &PageRequest,
+) -> Result<TransactionPage> {
+    let order = page_order(page)?;
+    let offset = page.offset.unwrap_or(0).max(0);
+    let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
 
+    let (conditions, params) = filter_conditions(filters);
     let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
-    let transactions = stmt
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM transactions t WHERE 1=1{} ORDER BY {} LIMIT {} OFFSET {}",
+        TRANSACTION_COLUMNS, conditions, order, limit, offset
+    ))?;
+    let items = stmt
+        .query_map(param_refs.as_slice(), row_to_transaction)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    // Součty po měně a dni, aby se cizí měny přepočetly kurzem ke dni transakce
+    let mut stmt = conn.prepare(&format!(
+        "SELECT t.transaction_type, t.currency, date(t.date), SUM(t.amount), COUNT(*)
+         FROM transactions t WHERE 1=1{}
+         GROUP BY t.transaction_type, t.currency, date(t.date)",
+        conditions
+    ))?;
+    let groups = stmt
         .query_map(param_refs.as_slice(), |row| {
-            Ok(Transaction {
-                id: row.get(0)?,
-                date: row.get(1)?,
-                amount: row.get(2)?,
-                currency: row.get(3)?,
-                transaction_type: row.get(4)?,
-                from_account_id: row.get(5)?,
-                to_account_id: row.get(6)?,
-                category_id: row.get(7)?,
-                description: row.get(8)?,
-                owner_user_id: row.get(9)?,
-                is_shared: row.get::<_, i32>(10)? != 0,
-                status: row.get(11)?,
-                recurring_payment_id: row.get(12)?,
-                flow_group_id: row.get(13)?,
-                notes: row.get(14)?,
-                created_at: row.get(15)?,
-                updated_at: row.get(16)?,
-            })
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Money>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
         })?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
-    Ok(transactions)
+    let mut converter = CurrencyConverter::to_default(conn)?;
+    let mut total_count = 0;
+    let mut total_income = Money::ZERO;
+    let mut total_expense = Money::ZERO;
+    for (transaction_type, currency, day, amount, count) in groups {
+        total_count += count;
+        // Bez kurzu se skupina jen vynechá ze součtů, stránka se načte vždy
+        let converted = match transaction_type.as_str() {
+            "income" | "expense" => converter.try_convert(amount, &currency, &day)?,
+            _ => None,
+        };
+        match (transaction_type.as_str(), converted) {
+            ("income", Some(amount)) => total_income += amount,
+            ("expense", Some(amount)) => total_expense += amount,
+            _ => {}
+        }
+    }
+
+    Ok(TransactionPage {
+        items,
+        offset,
+        limit,
+        total_count,
+        currency: converter.target().to_string(),
+        total_income,
+        total_expense,
+        net: total_income - total_expense,
+        warnings: converter.missing_rates(),
+    })
 }
 
 #[tauri::command]
 pub fn update_transaction(id: i64, input: UpdateTransactionInput) -> Result<Transaction> {
-    let conn = get_connection()?;
-
-    conn.execute(
-        "UPDATE transactions SET date = ?1, amount = ?2, currency = ?3, transaction_type = ?4,
-         from_account_id = ?5, to_account_id = ?6, category_id = ?7, description = ?8,
-         owner_user_id = ?9, is_shared = ?10, status = ?11, notes = ?12
-         WHERE id = ?13",
-        rusqlite::params![
-            input.date,
-            input.amount,
-            input.currency,
-            input.transaction_type,
-            input.from_account_id,
-            input.to_account_id,
-            input.category_id,
-            input.description,
-            input.owner_user_id,
-            input.is_shared as i32,
-            input.status,
-            input.notes,
-            id
-        ],
-    )?;
-
-    get_transaction_by_id(id)
+    with_transaction(|conn| {
+        // Vrátit dopad původní podoby transakce (typ, stav, účty i částka se mohly změnit)
+        let previous = query_transaction(conn, id)?;
+        ensure_unlocked(&previous)?;
+
+        // Platební údaje a obchodník se mění, jen když je vstup uvádí
+        let details = PaymentDetails::updated(
+            PaymentDetails {
+                variable_symbol: previous.variable_symbol.clone(),
+                constant_symbol: previous.constant_symbol.clone(),
+                specific_symbol: previous.specific_symbol.clone(),
+                counterparty_account: previous.counterparty_account.clone(),
+            },
+            input.variable_symbol.as_ref().map(Option::as_deref),
+            input.constant_symbol.as_ref().map(Option::as_deref),
+            input.specific_symbol.as_ref().map(Option::as_deref),
+            input.counterparty_account.as_ref().map(Option::as_deref),
+        )?;
+        let payee_id = input.payee_id.unwrap_or(previous.payee_id);
+        reverse_entry(conn, &LedgerEntry::from(&previous))?;
+
+        // Částku rozdělené transakce nelze změnit bez úpravy rozpadu
+        ensure_splits_match(splits_total(conn, id, None)?, input.amount)?;
+        // Sdílené podíly se přepočítají podle uložených procent
+        reallocate_shares(conn, id, &input.transaction_type, input.amount)?;
+
+        let legs = resolve_transfer_legs(
+            conn,
+            &TransferRequest {
+                transaction_type: &input.transaction_type,
+                date: &input.date,
+                amount: input.amount,
+                currency: &input.currency,
+                to_account_id: input.to_account_id,
+                destination_amount: input.destination_amount,
+                exchange_rate: input.exchange_rate,
+                fee_amount: input.fee_amount,
+            },
+        )?;
+
+        conn.execute(
+            "UPDATE transactions SET date = ?1, amount = ?2, currency = ?3, transaction_type = ?4,
+             from_account_id = ?5, to_account_id = ?6, category_id = ?7, description = ?8,
+             owner_user_id = ?9, is_shared = ?10, status = ?11, notes = ?12,
+             destination_amount = ?13, destination_currency = ?14, exchange_rate = ?15,
+             fee_amount = ?16, payee_id = ?17, variable_symbol = ?18, constant_symbol = ?19,
+             specific_symbol = ?20, counterparty_account = ?21, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?22",
+            rusqlite::params![
+                input.date,
+                input.amount,
+                input.currency,
+                input.transaction_type,
+                input.from_account_id,
+                input.to_account_id,
+                input.category_id,
+                input.description,
+                input.owner_user_id,
+                input.is_shared as i32,
+                input.status,
+                input.notes,
+                legs.destination_amount,
+                legs.destination_currency,
+                legs.exchange_rate,
+                legs.fee_amount,
+                payee_id,
+                details.variable_symbol,
+                details.constant_symbol,
+                details.specific_symbol,
+                details.counterparty_account,
+                id
+            ],
+        )?;
+
+        refresh_fingerprint(conn, id)?;
+
+        // Promítnout novou podobu
+        let updated = query_transaction(conn, id)?;
+        apply_entry(conn, &LedgerEntry::from(&updated))?;
+
+        Ok(updated)
+    })
 }
 
 #[tauri::command]
 pub fn delete_transaction(id: i64) -> Result<()> {
-    let conn = get_connection()?;
+    with_transaction(|conn| {
+        // Získat transakci před smazáním pro úpravu zůstatku
+        let tx = query_transaction(conn, id)?;
+        ensure_unlocked(&tx)?;
 
-    // Získat transakci před smazáním pro úpravu zůstatku
-    let tx = get_transaction_by_id(id)?;
-
-    // Vrátit zůstatek
-    if tx.status != "planned" {
-        match tx.transaction_type.as_str() {
-            "expense" => {
-                if let Some(from_id) = tx.from_account_id {
-                    drop(conn);
-                    update_account_balance(from_id, tx.amount)?;
-                    let conn = get_connection()?;
-                    conn.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
-                    return Ok(());
-                }
-            }
-            "income" => {
-                if let Some(to_id) = tx.to_account_id {
-                    drop(conn);
-                    update_account_balance(to_id, -tx.amount)?;
-                    let conn = get_connection()?;
-                    conn.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
-                    return Ok(());
-                }
-            }
-            "transfer" => {
-                if let Some(from_id) = tx.from_account_id {
-                    drop(conn);
-                    update_account_balance(from_id, tx.amount)?;
-                    if let Some(to_id) = tx.to_account_id {
-                        update_account_balance(to_id, -tx.amount)?;
-                    }
-                    let conn = get_connection()?;
-                    conn.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
-                    return Ok(());
-                }
-            }
-            _ => {}
-        }
-    }
+        // Vrátit zůstatek a smazat v jedné SQL transakci
+        reverse_entry(conn, &LedgerEntry::from(&tx))?;
+        conn.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
 
-    conn.execute("DELETE FROM transactions WHERE id = ?1", [id])?;
-    Ok(())
+        Ok(())
+    })
 }
 
 // ============================================
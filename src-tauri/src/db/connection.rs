@@ -1,7 +1,7 @@
-use crate::db::schema::SCHEMA;
+use crate::db::migrations::run_migrations;
 use crate::utils::error::{AppError, Result};
 use once_cell::sync::OnceCell;
-use rusqlite::Connection;
+use rusqlite::{Connection, Transaction};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
@@ -14,13 +14,13 @@ pub fn init_database(path: &str) -> Result<()> {
         .set(path_buf.clone())
         .map_err(|_| AppError::Internal("Databáze již byla inicializována".to_string()))?;
 
-    let conn = Connection::open(&path_buf)?;
+    let mut conn = Connection::open(&path_buf)?;
 
     // Enable foreign keys
     conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
-    // Execute schema
-    conn.execute_batch(SCHEMA)?;
+    // Aplikovat chybějící migrace schématu
+    run_migrations(&mut conn)?;
 
     CONNECTION
         .set(Mutex::new(conn))
@@ -38,6 +38,21 @@ pub fn get_connection() -> Result<std::sync::MutexGuard<'static, Connection>> {
         .map_err(|e| AppError::Internal(format!("Chyba zámku databáze: {}", e)))
 }
 
+/// Spustí `f` jako jednu jednotku práce v SQL transakci nad sdíleným připojením.
+///
+/// Pokud `f` vrátí chybu, všechny změny se vrátí zpět. Uvnitř `f` se nesmí volat
+/// `get_connection` (zámek je již držen) - vše musí jít přes předanou transakci.
+pub fn with_transaction<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce(&Transaction) -> Result<T>,
+{
+    let mut conn = get_connection()?;
+    let tx = conn.transaction()?;
+    let value = f(&tx)?;
+    tx.commit()?;
+    Ok(value)
+}
+
 pub fn get_db_path() -> Result<PathBuf> {
     DB_PATH
         .get()
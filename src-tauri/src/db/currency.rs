@@ -0,0 +1,167 @@
+use crate::db::settings::default_currency;
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::{BTreeSet, HashMap};
+
+/// Měna nové položky: explicitně zadaná, jinak měna účtu, jinak výchozí měna.
+pub fn resolve_currency(
+    conn: &Connection,
+    explicit: Option<String>,
+    account_id: Option<i64>,
+) -> Result<String> {
+    if let Some(currency) = explicit.filter(|c| !c.trim().is_empty()) {
+        return Ok(currency.trim().to_uppercase());
+    }
+
+    if let Some(account_id) = account_id {
+        let currency: Option<String> = conn
+            .query_row(
+                "SELECT currency FROM accounts WHERE id = ?1",
+                [account_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(currency) = currency {
+            return Ok(currency);
+        }
+    }
+
+    default_currency(conn)
+}
+
+/// Poslední známý kurz páru k datu (včetně), pouze přímý směr.
+fn stored_rate(conn: &Connection, from: &str, to: &str, date: &str) -> Result<Option<f64>> {
+    let rate = conn
+        .query_row(
+            "SELECT rate FROM exchange_rates
+             WHERE from_currency = ?1 AND to_currency = ?2 AND date <= ?3
+             ORDER BY date DESC LIMIT 1",
+            [from, to, date],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(rate)
+}
+
+/// Kurz přímo nebo z opačného páru.
+fn pair_rate(conn: &Connection, from: &str, to: &str, date: &str) -> Result<Option<f64>> {
+    if let Some(rate) = stored_rate(conn, from, to, date)? {
+        return Ok(Some(rate));
+    }
+    Ok(stored_rate(conn, to, from, date)?.map(|rate| 1.0 / rate))
+}
+
+/// Kurz `from` → `to` platný k datu: 1 jednotka `from` = kurz jednotek `to`.
+///
+/// Použije poslední kurz se stejným nebo starším datem. Není-li pár zadán přímo ani
+/// obráceně, zkusí křížový kurz přes měnu, ke které mají kurz obě strany (typicky CZK
+/// z kurzovního lístku ČNB).
+pub fn find_rate(conn: &Connection, from: &str, to: &str, date: &str) -> Result<f64> {
+    if from == to {
+        return Ok(1.0);
+    }
+
+    if let Some(rate) = pair_rate(conn, from, to, date)? {
+        return Ok(rate);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT to_currency FROM exchange_rates WHERE from_currency = ?1
+         UNION
+         SELECT from_currency FROM exchange_rates WHERE to_currency = ?1",
+    )?;
+    let pivots = stmt
+        .query_map([from], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for pivot in pivots {
+        if let (Some(first), Some(second)) = (
+            pair_rate(conn, from, &pivot, date)?,
+            pair_rate(conn, &pivot, to, date)?,
+        ) {
+            return Ok(first * second);
+        }
+    }
+
+    Err(AppError::NotFound(format!(
+        "Chybí kurz {}/{} k datu {}",
+        from, to, date
+    )))
+}
+
+/// Přepočet částek do jedné měny s mezipamětí kurzů pro opakované dotazy v reportech.
+pub struct CurrencyConverter<'a> {
+    conn: &'a Connection,
+    target: String,
+    rates: HashMap<(String, String), f64>,
+    missing: BTreeSet<String>,
+}
+
+impl<'a> CurrencyConverter<'a> {
+    pub fn new(conn: &'a Connection, target: String) -> Self {
+        CurrencyConverter {
+            conn,
+            target,
+            rates: HashMap::new(),
+            missing: BTreeSet::new(),
+        }
+    }
+
+    /// Převodník do výchozí měny ze `settings`.
+    pub fn to_default(conn: &'a Connection) -> Result<Self> {
+        Ok(Self::new(conn, default_currency(conn)?))
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Přepočte částku kurzem platným k datu transakce (`YYYY-MM-DD…`).
+    pub fn convert(&mut self, amount: Money, currency: &str, date: &str) -> Result<Money> {
+        if currency == self.target {
+            return Ok(amount);
+        }
+
+        let day = date.get(..10).unwrap_or(date).to_string();
+        let key = (currency.to_string(), day);
+        let rate = match self.rates.get(&key) {
+            Some(rate) => *rate,
+            None => {
+                let rate = find_rate(self.conn, currency, &self.target, &key.1)?;
+                self.rates.insert(key, rate);
+                rate
+            }
+        };
+
+        Ok(amount.mul_f64(rate))
+    }
+
+    /// Jako `convert`, ale chybějící kurz jen zaznamená (viz `missing_rates`) a vrátí `None`.
+    pub fn try_convert(&mut self, amount: Money, currency: &str, date: &str) -> Result<Option<Money>> {
+        let converted = self.convert(amount, currency, date);
+        self.note_missing(converted)
+    }
+
+    /// Kurz dvojice měn k datu; chybějící zaznamená stejně jako `try_convert`.
+    pub fn try_rate(&mut self, from: &str, to: &str, date: &str) -> Result<Option<f64>> {
+        let rate = find_rate(self.conn, from, to, date);
+        self.note_missing(rate)
+    }
+
+    fn note_missing<T>(&mut self, result: Result<T>) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(AppError::NotFound(message)) => {
+                self.missing.insert(message);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Chybějící kurzy zjištěné při přepočtu, jako upozornění k reportu.
+    pub fn missing_rates(&self) -> Vec<String> {
+        self.missing.iter().cloned().collect()
+    }
+}
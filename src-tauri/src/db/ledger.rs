@@ -0,0 +1,357 @@
+use crate::models::{AccountBalanceCheck, OrphanedReference, Transaction};
+use crate::utils::error::Result;
+use crate::utils::money::Money;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Údaje transakce, které ovlivňují zůstatky účtů.
+#[derive(Debug, Clone, Copy)]
+pub struct LedgerEntry<'a> {
+    pub transaction_type: &'a str,
+    pub status: &'a str,
+    pub from_account_id: Option<i64>,
+    pub to_account_id: Option<i64>,
+    pub amount: Money,
+    pub destination_amount: Option<Money>,
+    pub fee_amount: Option<Money>,
+}
+
+impl<'a> From<&'a Transaction> for LedgerEntry<'a> {
+    fn from(tx: &'a Transaction) -> Self {
+        LedgerEntry {
+            transaction_type: &tx.transaction_type,
+            status: &tx.status,
+            from_account_id: tx.from_account_id,
+            to_account_id: tx.to_account_id,
+            amount: tx.amount,
+            destination_amount: tx.destination_amount,
+            fee_amount: tx.fee_amount,
+        }
+    }
+}
+
+/// Vrátí změny zůstatků (účet, částka), které transakce způsobí.
+/// Plánované transakce zůstatky neovlivňují.
+pub fn balance_changes(entry: &LedgerEntry) -> Vec<(i64, Money)> {
+    if entry.status == "planned" {
+        return Vec::new();
+    }
+
+    let mut changes = Vec::new();
+    match entry.transaction_type {
+        "expense" => {
+            if let Some(from_id) = entry.from_account_id {
+                changes.push((from_id, -entry.amount));
+            }
+        }
+        "income" => {
+            if let Some(to_id) = entry.to_account_id {
+                changes.push((to_id, entry.amount));
+            }
+        }
+        // Každá strana převodu v měně svého účtu, poplatek jde za zdrojovým účtem
+        "transfer" => {
+            if let Some(from_id) = entry.from_account_id {
+                changes.push((from_id, -(entry.amount + entry.fee_amount.unwrap_or_default())));
+            }
+            if let Some(to_id) = entry.to_account_id {
+                changes.push((to_id, entry.destination_amount.unwrap_or(entry.amount)));
+            }
+        }
+        // Vyrovnání zůstatku - částka je se znaménkem
+        "adjustment" => {
+            if let Some(to_id) = entry.to_account_id {
+                changes.push((to_id, entry.amount));
+            }
+        }
+        _ => {}
+    }
+    changes
+}
+
+pub fn adjust_account_balance(conn: &Connection, account_id: i64, amount_change: Money) -> Result<()> {
+    conn.execute(
+        "UPDATE accounts SET current_balance = current_balance + ?1 WHERE id = ?2",
+        rusqlite::params![amount_change, account_id],
+    )?;
+
+    Ok(())
+}
+
+/// Promítne transakci do zůstatků účtů.
+pub fn apply_entry(conn: &Connection, entry: &LedgerEntry) -> Result<()> {
+    for (account_id, change) in balance_changes(entry) {
+        adjust_account_balance(conn, account_id, change)?;
+    }
+    Ok(())
+}
+
+/// Vrátí zpět dopad transakce na zůstatky účtů.
+pub fn reverse_entry(conn: &Connection, entry: &LedgerEntry) -> Result<()> {
+    for (account_id, change) in balance_changes(entry) {
+        adjust_account_balance(conn, account_id, -change)?;
+    }
+    Ok(())
+}
+
+/// Součet dopadů všech transakcí na zůstatky, po účtech; `cleared_only` bere
+/// jen transakce odškrtnuté proti výpisu.
+fn ledger_totals(
+    conn: &Connection,
+    account_id: Option<i64>,
+    cleared_only: bool,
+) -> Result<HashMap<i64, Money>> {
+    let mut stmt = conn.prepare(
+        "SELECT transaction_type, status, from_account_id, to_account_id, amount,
+         destination_amount, fee_amount
+         FROM transactions
+         WHERE status != 'planned'
+         AND (?1 IS NULL OR from_account_id = ?1 OR to_account_id = ?1)
+         AND (?2 = 0 OR reconciliation_status != 'uncleared')",
+    )?;
+
+    let mut totals: HashMap<i64, Money> = HashMap::new();
+    let mut rows = stmt.query(rusqlite::params![account_id, cleared_only])?;
+    while let Some(row) = rows.next()? {
+        let transaction_type: String = row.get(0)?;
+        let status: String = row.get(1)?;
+        let entry = LedgerEntry {
+            transaction_type: &transaction_type,
+            status: &status,
+            from_account_id: row.get(2)?,
+            to_account_id: row.get(3)?,
+            amount: row.get(4)?,
+            destination_amount: row.get(5)?,
+            fee_amount: row.get(6)?,
+        };
+        for (id, change) in balance_changes(&entry) {
+            *totals.entry(id).or_default() += change;
+        }
+    }
+
+    Ok(totals)
+}
+
+/// Přepočítá zůstatky účtů z `initial_balance` a transakcí a porovná je s uloženými.
+pub fn check_balances(conn: &Connection) -> Result<Vec<AccountBalanceCheck>> {
+    let totals = ledger_totals(conn, None, false)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, currency, initial_balance, current_balance FROM accounts ORDER BY name",
+    )?;
+    let checks = stmt
+        .query_map([], |row| {
+            let account_id: i64 = row.get(0)?;
+            let initial_balance: Money = row.get(3)?;
+            let stored_balance: Money = row.get(4)?;
+            let computed_balance = initial_balance + totals.get(&account_id).copied().unwrap_or_default();
+            Ok(AccountBalanceCheck {
+                account_id,
+                account_name: row.get(1)?,
+                currency: row.get(2)?,
+                initial_balance,
+                stored_balance,
+                computed_balance,
+                difference: stored_balance - computed_balance,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(checks)
+}
+
+/// Najde řádky, jejichž cizí klíč ukazuje na neexistující záznam.
+pub fn find_orphaned_references(conn: &Connection) -> Result<Vec<OrphanedReference>> {
+    let mut stmt = conn.prepare(
+        "SELECT k.\"table\", k.rowid, l.\"from\", k.parent
+         FROM pragma_foreign_key_check AS k
+         JOIN pragma_foreign_key_list(k.\"table\") AS l ON l.id = k.fkid AND l.seq = 0
+         ORDER BY k.\"table\", k.rowid",
+    )?;
+    let orphans = stmt
+        .query_map([], |row| {
+            Ok(OrphanedReference {
+                table_name: row.get(0)?,
+                row_id: row.get(1)?,
+                column_name: row.get(2)?,
+                referenced_table: row.get(3)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(orphans)
+}
+
+/// Nastaví zůstatek účtu tak, aby zůstal v souladu s transakcemi:
+/// `initial_balance` se dopočítá jako zůstatek před všemi transakcemi.
+pub fn set_balance(conn: &Connection, account_id: i64, balance: Money) -> Result<()> {
+    let net_change = ledger_totals(conn, Some(account_id), false)?
+        .get(&account_id)
+        .copied()
+        .unwrap_or_default();
+
+    conn.execute(
+        "UPDATE accounts SET current_balance = ?1, initial_balance = ?2 WHERE id = ?3",
+        rusqlite::params![balance, balance - net_change, account_id],
+    )?;
+
+    Ok(())
+}
+
+/// Zůstatek účtu z `initial_balance` a transakcí odškrtnutých proti výpisu.
+pub fn cleared_balance(conn: &Connection, account_id: i64) -> Result<Money> {
+    let initial_balance: Money = conn.query_row(
+        "SELECT initial_balance FROM accounts WHERE id = ?1",
+        [account_id],
+        |row| row.get(0),
+    )?;
+    let cleared = ledger_totals(conn, Some(account_id), true)?
+        .get(&account_id)
+        .copied()
+        .unwrap_or_default();
+    Ok(initial_balance + cleared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations::run_migrations;
+
+    fn czk(value: &str) -> Money {
+        value.parse().unwrap()
+    }
+
+    fn entry<'a>(
+        transaction_type: &'a str,
+        status: &'a str,
+        from_account_id: Option<i64>,
+        to_account_id: Option<i64>,
+        amount: &str,
+    ) -> LedgerEntry<'a> {
+        LedgerEntry {
+            transaction_type,
+            status,
+            from_account_id,
+            to_account_id,
+            amount: czk(amount),
+            destination_amount: None,
+            fee_amount: None,
+        }
+    }
+
+    #[test]
+    fn balance_changes_per_type() {
+        assert_eq!(
+            balance_changes(&entry("expense", "completed", Some(1), Some(2), "100")),
+            vec![(1, czk("-100"))]
+        );
+        assert_eq!(
+            balance_changes(&entry("income", "completed", Some(1), Some(2), "100")),
+            vec![(2, czk("100"))]
+        );
+        assert_eq!(
+            balance_changes(&entry("adjustment", "completed", None, Some(2), "-30.5")),
+            vec![(2, czk("-30.5"))]
+        );
+        assert_eq!(
+            balance_changes(&entry("transfer", "completed", Some(1), Some(2), "100")),
+            vec![(1, czk("-100")), (2, czk("100"))]
+        );
+
+        let fx = LedgerEntry {
+            destination_amount: Some(czk("4")),
+            fee_amount: Some(czk("2.5")),
+            ..entry("transfer", "completed", Some(1), Some(2), "100")
+        };
+        assert_eq!(
+            balance_changes(&fx),
+            vec![(1, czk("-102.5")), (2, czk("4"))]
+        );
+
+        for transaction_type in ["expense", "income", "transfer", "adjustment"] {
+            let planned = entry(transaction_type, "planned", Some(1), Some(2), "100");
+            assert!(balance_changes(&planned).is_empty(), "{}", transaction_type);
+        }
+        assert!(balance_changes(&entry("expense", "completed", None, Some(2), "1")).is_empty());
+        assert!(balance_changes(&entry("income", "completed", Some(1), None, "1")).is_empty());
+        assert!(balance_changes(&entry("unknown", "completed", Some(1), Some(2), "1")).is_empty());
+    }
+
+    fn balances(conn: &Connection) -> Vec<Money> {
+        let checks = check_balances(conn).unwrap();
+        for check in &checks {
+            assert!(check.difference.is_zero(), "{:?}", check);
+        }
+        checks.iter().map(|check| check.stored_balance).collect()
+    }
+
+    /// Stejný postup jako `update_transaction`: vrátit původní dopad, přepsat, promítnout nový.
+    fn update(conn: &Connection, previous: &LedgerEntry, next: &LedgerEntry) {
+        reverse_entry(conn, previous).unwrap();
+        conn.execute(
+            "UPDATE transactions SET transaction_type = ?1, status = ?2, from_account_id = ?3,
+             to_account_id = ?4, amount = ?5, destination_amount = ?6, fee_amount = ?7
+             WHERE id = 1",
+            rusqlite::params![
+                next.transaction_type,
+                next.status,
+                next.from_account_id,
+                next.to_account_id,
+                next.amount,
+                next.destination_amount,
+                next.fee_amount
+            ],
+        )
+        .unwrap();
+        apply_entry(conn, next).unwrap();
+    }
+
+    #[test]
+    fn updates_keep_balances_in_sync() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO accounts (id, name, account_type, initial_balance, current_balance)
+             VALUES (1, 'A', 'checking', 100000, 100000), (2, 'B', 'checking', 100000, 100000),
+                    (3, 'C', 'savings', 100000, 100000);
+             INSERT INTO transactions (id, date, amount, transaction_type, status, from_account_id)
+             VALUES (1, '2024-03-01', 5000, 'expense', 'planned', 1);",
+        )
+        .unwrap();
+
+        // Přesun výdaje na jiný účet a přechod plánovaná → provedená
+        let planned = entry("expense", "planned", Some(1), None, "50");
+        let moved = entry("expense", "completed", Some(2), None, "50");
+        update(&conn, &planned, &moved);
+        assert_eq!(balances(&conn), vec![czk("1000"), czk("950"), czk("1000")]);
+
+        let mut variants = Vec::new();
+        for status in ["planned", "completed"] {
+            for (from, to) in [(1, 2), (3, 1)] {
+                variants.push(entry("expense", status, Some(from), None, "50"));
+                variants.push(entry("income", status, None, Some(to), "75.25"));
+                variants.push(LedgerEntry {
+                    fee_amount: Some(czk("1.5")),
+                    destination_amount: Some(czk("20")),
+                    ..entry("transfer", status, Some(from), Some(to), "30")
+                });
+            }
+        }
+
+        // Každá dvojice (původní, nová) včetně změny typu, stavu i účtů
+        let mut current = moved;
+        for previous in &variants {
+            for next in &variants {
+                update(&conn, &current, previous);
+                balances(&conn);
+                update(&conn, previous, next);
+                let mut expected = vec![czk("1000"); 3];
+                for (account_id, change) in balance_changes(next) {
+                    expected[account_id as usize - 1] += change;
+                }
+                assert_eq!(balances(&conn), expected, "{:?} -> {:?}", previous, next);
+                current = *next;
+            }
+        }
+    }
+}
@@ -0,0 +1,1247 @@
+use crate::db::schema::SCHEMA;
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use rusqlite::{Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+
+/// Jeden krok migrace schématu. Verze tvoří souvislou rostoucí řadu
+/// a každý aplikovaný krok se zapíše do tabulky `migrations`.
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub up: fn(&Transaction) -> Result<()>,
+}
+
+/// Všechny známé migrace v pořadí, v jakém se aplikují.
+/// Nové změny schématu se přidávají výhradně na konec seznamu.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Výchozí schéma",
+        up: migrate_v1_baseline,
+    },
+    Migration {
+        version: 2,
+        description: "Kreditní limit, účet u stálých výdajů, fondy a měsíční plány",
+        up: migrate_v2_goals_and_columns,
+    },
+    Migration {
+        version: 3,
+        description: "Peněžní částky jako celé číslo v setinách",
+        up: migrate_v3_money_as_integer,
+    },
+    Migration {
+        version: 4,
+        description: "Kurzovní lístek",
+        up: migrate_v4_exchange_rates,
+    },
+    Migration {
+        version: 5,
+        description: "Převody mezi měnami: cílová částka, kurz a poplatek",
+        up: migrate_v5_transfer_legs,
+    },
+    Migration {
+        version: 6,
+        description: "Rozpad transakcí do více kategorií",
+        up: migrate_v6_transaction_splits,
+    },
+    Migration {
+        version: 7,
+        description: "Sdílené výdaje: vazba členů na uživatele a vyrovnání",
+        up: migrate_v7_settlements,
+    },
+    Migration {
+        version: 8,
+        description: "Sloučení členů domácnosti s uživateli",
+        up: migrate_v8_unify_people,
+    },
+    Migration {
+        version: 9,
+        description: "Profily importu bankovních výpisů z CSV",
+        up: migrate_v9_import_profiles,
+    },
+    Migration {
+        version: 10,
+        description: "Externí ID importovaných transakcí",
+        up: migrate_v10_external_ids,
+    },
+    Migration {
+        version: 11,
+        description: "Platební symboly, protiúčet a datum valuty u transakcí",
+        up: migrate_v11_payment_details,
+    },
+    Migration {
+        version: 12,
+        description: "Otisk zdroje transakcí pro hledání duplicit",
+        up: migrate_v12_fingerprints,
+    },
+    Migration {
+        version: 13,
+        description: "Pravidla automatické kategorizace",
+        up: migrate_v13_categorization_rules,
+    },
+    Migration {
+        version: 14,
+        description: "Obchodníci (payees) s aliasy a vazba na transakce",
+        up: migrate_v14_payees,
+    },
+    Migration {
+        version: 15,
+        description: "Fulltextové hledání v transakcích (FTS5)",
+        up: migrate_v15_transactions_fts,
+    },
+    Migration {
+        version: 16,
+        description: "Uložené pohledy (filtry transakcí)",
+        up: migrate_v16_saved_views,
+    },
+    Migration {
+        version: 17,
+        description: "Odsouhlasení účtů s výpisem a stav odsouhlasení transakcí",
+        up: migrate_v17_reconciliations,
+    },
+    Migration {
+        version: 18,
+        description: "Platební symboly a protiúčet u pravidelných plateb a převodů",
+        up: migrate_v18_payment_identifiers,
+    },
+    Migration {
+        version: 19,
+        description: "Jednotné opakování (RRULE) u plateb, příjmů, výdajů, převodů a cílů",
+        up: migrate_v19_schedules,
+    },
+    Migration {
+        version: 20,
+        description: "Termín výskytu u transakcí z pravidelných plateb (dohánění zmeškaných)",
+        up: migrate_v20_recurring_occurrences,
+    },
+    Migration {
+        version: 21,
+        description: "Příznak transakcí vygenerovaných z pravidelných plateb",
+        up: migrate_v21_recurring_generated,
+    },
+];
+
+#[derive(Debug, Serialize)]
+pub struct SchemaVersion {
+    pub current_version: i64,
+    pub latest_version: i64,
+}
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Vrátí nejvyšší aplikovanou verzi; databáze bez tabulky `migrations` má verzi 0.
+pub fn current_version(conn: &Connection) -> Result<i64> {
+    let has_table: Option<String> = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'migrations'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if has_table.is_none() {
+        return Ok(0);
+    }
+
+    let version: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM migrations",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(version)
+}
+
+/// Odmítne databázi zapsanou novější verzí aplikace.
+pub fn ensure_supported(version: i64) -> Result<()> {
+    let latest = latest_version();
+    if version > latest {
+        return Err(AppError::Internal(format!(
+            "Databáze má verzi schématu {}, tato verze aplikace podporuje nejvýše verzi {}. Aktualizujte aplikaci.",
+            version, latest
+        )));
+    }
+    Ok(())
+}
+
+/// Aplikuje všechny chybějící migrace v jedné SQL transakci.
+///
+/// Cizí klíče jsou během migrace vypnuté, aby bylo možné přestavovat tabulky
+/// (SQLite neumí měnit typ sloupce); `PRAGMA foreign_keys` nelze měnit uvnitř transakce.
+pub fn run_migrations(conn: &mut Connection) -> Result<()> {
+    let current = current_version(conn)?;
+    ensure_supported(current)?;
+
+    if current == latest_version() {
+        return Ok(());
+    }
+
+    conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+    let result = apply_pending(conn, current);
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    result
+}
+
+fn apply_pending(conn: &mut Connection, current: i64) -> Result<()> {
+    let tx = conn.transaction()?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        log::info!(
+            "Aplikuji migraci {}: {}",
+            migration.version,
+            migration.description
+        );
+        (migration.up)(&tx)?;
+        tx.execute(
+            "INSERT INTO migrations (version) VALUES (?1)",
+            [migration.version],
+        )?;
+    }
+
+    let violations: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM pragma_foreign_key_check",
+        [],
+        |row| row.get(0),
+    )?;
+    if violations > 0 {
+        log::warn!(
+            "Po migraci databáze obsahuje {} záznamů s neplatným cizím klíčem",
+            violations
+        );
+    }
+
+    tx.commit()?;
+    log::info!("Schéma databáze aktualizováno na verzi {}", latest_version());
+    Ok(())
+}
+
+fn column_exists(tx: &Transaction, table: &str, column: &str) -> Result<bool> {
+    let count: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        [table, column],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Starší databáze mohou sloupec již mít (vznikly z novější podoby `SCHEMA`).
+fn add_column_if_missing(tx: &Transaction, table: &str, column: &str, definition: &str) -> Result<()> {
+    if !column_exists(tx, table, column)? {
+        tx.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {};",
+            table, column, definition
+        ))?;
+    }
+    Ok(())
+}
+
+// ============================================
+// MIGRACE
+// ============================================
+
+fn migrate_v1_baseline(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(SCHEMA)?;
+    Ok(())
+}
+
+const V2_GOALS: &str = r#"
+-- ============================================
+-- FINANCIAL_GOALS (Fondy a cíle)
+-- ============================================
+CREATE TABLE IF NOT EXISTS financial_goals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    goal_type TEXT NOT NULL,
+    icon TEXT,
+    color TEXT,
+    weekly_amount REAL,
+    day_of_week INTEGER,
+    monthly_contribution REAL,
+    current_balance REAL DEFAULT 0.0,
+    yearly_amount REAL,
+    target_month INTEGER,
+    current_saved REAL DEFAULT 0.0,
+    account_id INTEGER,
+    notes TEXT,
+    is_active INTEGER DEFAULT 1,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_financial_goals_active ON financial_goals(is_active);
+
+-- ============================================
+-- FUND_WITHDRAWALS (Čerpání z fondů)
+-- ============================================
+CREATE TABLE IF NOT EXISTS fund_withdrawals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goal_id INTEGER NOT NULL,
+    amount REAL NOT NULL,
+    description TEXT,
+    date TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goal_id) REFERENCES financial_goals(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_withdrawals_goal ON fund_withdrawals(goal_id);
+
+-- ============================================
+-- MONTHLY_PLANS (Měsíční plány fondů)
+-- ============================================
+CREATE TABLE IF NOT EXISTS monthly_plans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goal_id INTEGER NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL,
+    planned_count INTEGER NOT NULL DEFAULT 0,
+    realized_count INTEGER NOT NULL DEFAULT 0,
+    planned_amount REAL NOT NULL DEFAULT 0.0,
+    realized_amount REAL NOT NULL DEFAULT 0.0,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (goal_id, year, month),
+    FOREIGN KEY (goal_id) REFERENCES financial_goals(id) ON DELETE CASCADE
+);
+"#;
+
+fn migrate_v2_goals_and_columns(tx: &Transaction) -> Result<()> {
+    add_column_if_missing(tx, "accounts", "credit_limit", "REAL")?;
+    add_column_if_missing(
+        tx,
+        "fixed_expenses",
+        "account_id",
+        "INTEGER REFERENCES accounts(id) ON DELETE SET NULL",
+    )?;
+
+    tx.execute_batch(V2_GOALS)?;
+
+    // Tabulka mohla vzniknout ze starší podoby bez naspořené částky
+    add_column_if_missing(tx, "financial_goals", "current_saved", "REAL DEFAULT 0.0")?;
+
+    Ok(())
+}
+
+/// Peněžní sloupce, které se ukládají jako `INTEGER` v setinách měnové jednotky.
+const V3_MONEY_COLUMNS: &[(&str, &[&str])] = &[
+    (
+        "accounts",
+        &["initial_balance", "current_balance", "premium_min_flow", "credit_limit"],
+    ),
+    ("transactions", &["amount"]),
+    ("shared_expense_splits", &["amount"]),
+    ("recurring_payments", &["amount"]),
+    (
+        "savings_goals",
+        &["target_amount", "current_amount", "auto_deposit_amount"],
+    ),
+    ("member_incomes", &["amount"]),
+    ("scheduled_transfers", &["amount"]),
+    ("fixed_expenses", &["amount"]),
+    ("budget_categories", &["monthly_limit"]),
+    (
+        "financial_goals",
+        &[
+            "weekly_amount",
+            "monthly_contribution",
+            "current_balance",
+            "yearly_amount",
+            "current_saved",
+        ],
+    ),
+    ("fund_withdrawals", &["amount"]),
+    ("monthly_plans", &["planned_amount", "realized_amount"]),
+];
+
+fn migrate_v3_money_as_integer(tx: &Transaction) -> Result<()> {
+    for (table, columns) in V3_MONEY_COLUMNS {
+        rebuild_with_integer_columns(tx, table, columns)?;
+        convert_to_minor_units(tx, table, columns)?;
+    }
+    Ok(())
+}
+
+/// Přestaví tabulku tak, aby vybrané sloupce měly typ `INTEGER`.
+/// Vychází z uloženého `CREATE TABLE`, takže zachová i sloupce přidané přes `ALTER TABLE`.
+fn rebuild_with_integer_columns(tx: &Transaction, table: &str, columns: &[&str]) -> Result<()> {
+    let sql: String = tx.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+
+    let body_start = sql
+        .find('(')
+        .ok_or_else(|| AppError::Internal(format!("Neočekávaná definice tabulky {}", table)))?;
+    let body_end = sql
+        .rfind(')')
+        .ok_or_else(|| AppError::Internal(format!("Neočekávaná definice tabulky {}", table)))?;
+
+    let definitions: Vec<String> = split_definitions(&sql[body_start + 1..body_end])
+        .into_iter()
+        .map(|definition| retype_definition(definition, columns))
+        .collect();
+
+    let dependent_sql: Vec<String> = {
+        let mut stmt = tx.prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE tbl_name = ?1 AND type IN ('index', 'trigger') AND sql IS NOT NULL",
+        )?;
+        let rows = stmt
+            .query_map([table], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        rows
+    };
+
+    let column_list: Vec<String> = {
+        let mut stmt = tx.prepare("SELECT name FROM pragma_table_info(?1) ORDER BY cid")?;
+        let rows = stmt
+            .query_map([table], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        rows
+    };
+    let column_list = column_list.join(", ");
+
+    tx.execute_batch(&format!(
+        "CREATE TABLE {table}_new ({body});
+         INSERT INTO {table}_new ({cols}) SELECT {cols} FROM {table};
+         DROP TABLE {table};
+         ALTER TABLE {table}_new RENAME TO {table};",
+        table = table,
+        body = definitions.join(","),
+        cols = column_list,
+    ))?;
+
+    for sql in dependent_sql {
+        tx.execute_batch(&sql)?;
+    }
+
+    Ok(())
+}
+
+/// Rozdělí tělo `CREATE TABLE` na definice sloupců a omezení (čárky mimo závorky a řetězce).
+fn split_definitions(body: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut start = 0;
+
+    for (i, c) in body.char_indices() {
+        match c {
+            '\'' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                parts.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&body[start..]);
+    parts
+}
+
+fn retype_definition(definition: &str, columns: &[&str]) -> String {
+    let name = definition
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .trim_matches(|c| c == '"' || c == '`' || c == '[' || c == ']');
+
+    if !columns.contains(&name) {
+        return definition.to_string();
+    }
+
+    definition
+        .replacen(" REAL", " INTEGER", 1)
+        .replace("DEFAULT 0.0", "DEFAULT 0")
+}
+
+/// Převede uložené hodnoty na setiny stejným zaokrouhlením jako `Money::from_f64`
+/// (SQL `ROUND(1.005 * 100)` by kvůli binární reprezentaci dalo 100).
+fn convert_to_minor_units(tx: &Transaction, table: &str, columns: &[&str]) -> Result<()> {
+    for column in columns {
+        let values: Vec<(i64, f64)> = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT rowid, {} FROM {} WHERE {} IS NOT NULL",
+                column, table, column
+            ))?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let mut update = tx.prepare(&format!(
+            "UPDATE {} SET {} = ?1 WHERE rowid = ?2",
+            table, column
+        ))?;
+        for (rowid, value) in values {
+            let amount = Money::from_f64(value).map_err(|e| {
+                AppError::Internal(format!("{}.{} (rowid {}): {}", table, column, rowid, e))
+            })?;
+            update.execute(rusqlite::params![amount, rowid])?;
+        }
+    }
+    Ok(())
+}
+
+const V4_EXCHANGE_RATES: &str = r#"
+-- ============================================
+-- EXCHANGE_RATES (Kurzy měn)
+-- 1 jednotka from_currency = rate jednotek to_currency
+-- ============================================
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL,
+    from_currency TEXT NOT NULL,
+    to_currency TEXT NOT NULL,
+    rate REAL NOT NULL CHECK (rate > 0),
+    source TEXT NOT NULL DEFAULT 'manual',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (date, from_currency, to_currency)
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_pair ON exchange_rates(from_currency, to_currency, date);
+"#;
+
+fn migrate_v4_exchange_rates(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V4_EXCHANGE_RATES)?;
+    Ok(())
+}
+
+fn migrate_v5_transfer_legs(tx: &Transaction) -> Result<()> {
+    add_column_if_missing(tx, "transactions", "destination_amount", "INTEGER")?;
+    add_column_if_missing(tx, "transactions", "destination_currency", "TEXT")?;
+    add_column_if_missing(tx, "transactions", "exchange_rate", "REAL")?;
+    add_column_if_missing(tx, "transactions", "fee_amount", "INTEGER")?;
+    Ok(())
+}
+
+const V6_TRANSACTION_SPLITS: &str = r#"
+-- ============================================
+-- TRANSACTION_SPLITS (Rozpad transakce do kategorií)
+-- ============================================
+CREATE TABLE IF NOT EXISTS transaction_splits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    category_id INTEGER,
+    note TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_splits_transaction ON transaction_splits(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_transaction_splits_category ON transaction_splits(category_id);
+
+-- ============================================
+-- TRANSACTION_SPLIT_TAGS
+-- ============================================
+CREATE TABLE IF NOT EXISTS transaction_split_tags (
+    split_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (split_id, tag_id),
+    FOREIGN KEY (split_id) REFERENCES transaction_splits(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_split_tags_tag ON transaction_split_tags(tag_id);
+"#;
+
+fn migrate_v6_transaction_splits(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V6_TRANSACTION_SPLITS)?;
+    Ok(())
+}
+
+const V7_SETTLEMENTS: &str = r#"
+-- ============================================
+-- SETTLEMENTS (Vyrovnání dluhů mezi uživateli)
+-- ============================================
+CREATE TABLE IF NOT EXISTS settlements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    from_user_id INTEGER NOT NULL,
+    to_user_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    currency TEXT NOT NULL DEFAULT 'CZK',
+    date TEXT NOT NULL,
+    transaction_id INTEGER,
+    note TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (from_user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (to_user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlements_date ON settlements(date);
+"#;
+
+fn migrate_v7_settlements(tx: &Transaction) -> Result<()> {
+    // Člen domácnosti (příjmy) ↔ uživatel (transakce) pro dělení podle příjmů
+    add_column_if_missing(
+        tx,
+        "household_members",
+        "user_id",
+        "INTEGER REFERENCES users(id) ON DELETE SET NULL",
+    )?;
+    tx.execute_batch(V7_SETTLEMENTS)?;
+    Ok(())
+}
+
+const V8_MEMBER_INCOMES: &str = r#"
+-- ============================================
+-- MEMBER_INCOMES (Příjmy osob) - vazba na users místo household_members
+-- ============================================
+CREATE TABLE member_incomes_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    frequency TEXT NOT NULL DEFAULT 'monthly',
+    day_of_month INTEGER,
+    account_id INTEGER,
+    is_active INTEGER DEFAULT 1,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE SET NULL
+);
+
+INSERT INTO member_incomes_new (id, user_id, name, amount, frequency, day_of_month,
+    account_id, is_active, created_at, updated_at)
+SELECT mi.id, map.user_id, mi.name, mi.amount, mi.frequency, mi.day_of_month,
+    mi.account_id, mi.is_active, mi.created_at, mi.updated_at
+FROM member_incomes mi
+JOIN temp.member_person_map map ON map.member_id = mi.member_id;
+
+DROP TABLE member_incomes;
+ALTER TABLE member_incomes_new RENAME TO member_incomes;
+
+CREATE INDEX IF NOT EXISTS idx_incomes_user ON member_incomes(user_id);
+CREATE INDEX IF NOT EXISTS idx_incomes_account ON member_incomes(account_id);
+
+DROP TABLE household_members;
+DROP TABLE temp.member_person_map;
+"#;
+
+/// Člen domácnosti před sloučením: id, jméno, barva, avatar, navázaný uživatel.
+type LegacyMember = (i64, String, String, Option<String>, Option<i64>);
+
+/// Osobou je nadále jen `users`. Člen domácnosti se přiřadí k navázanému uživateli,
+/// jinak k uživateli se stejným jménem, jinak se pro něj uživatel založí.
+fn migrate_v8_unify_people(tx: &Transaction) -> Result<()> {
+    let members: Vec<LegacyMember> = {
+        let mut stmt = tx.prepare(
+            "SELECT hm.id, hm.name, hm.color, hm.avatar, u.id
+             FROM household_members hm
+             LEFT JOIN users u ON u.id = hm.user_id
+             ORDER BY hm.id",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    tx.execute_batch(
+        "CREATE TEMP TABLE member_person_map (member_id INTEGER PRIMARY KEY, user_id INTEGER NOT NULL);",
+    )?;
+
+    for (member_id, name, color, avatar, linked_user) in members {
+        let by_name: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM users
+                 WHERE is_shared_user = 0 AND lower(trim(name)) = lower(trim(?1))
+                 ORDER BY active DESC, id LIMIT 1",
+                [&name],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let user_id = match linked_user.or(by_name) {
+            Some(user_id) => user_id,
+            None => {
+                tx.execute(
+                    "INSERT INTO users (name, color, avatar, role, is_shared_user, active)
+                     VALUES (?1, ?2, ?3, 'member', 0, 1)",
+                    rusqlite::params![name, color, avatar],
+                )?;
+                tx.last_insert_rowid()
+            }
+        };
+
+        tx.execute(
+            "INSERT INTO temp.member_person_map (member_id, user_id) VALUES (?1, ?2)",
+            [member_id, user_id],
+        )?;
+    }
+
+    tx.execute_batch(V8_MEMBER_INCOMES)?;
+    Ok(())
+}
+
+const V9_IMPORT_PROFILES: &str = r#"
+-- ============================================
+-- IMPORT_PROFILES (Mapování sloupců CSV výpisů)
+-- ============================================
+CREATE TABLE IF NOT EXISTS import_profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    bank_code TEXT,
+    delimiter TEXT NOT NULL DEFAULT ';',
+    encoding TEXT NOT NULL DEFAULT 'utf-8',
+    date_format TEXT NOT NULL DEFAULT '%d.%m.%Y',
+    decimal_comma INTEGER NOT NULL DEFAULT 1,
+    date_column TEXT NOT NULL,
+    amount_column TEXT NOT NULL,
+    debit_column TEXT,
+    direction_column TEXT,
+    debit_value TEXT,
+    currency_column TEXT,
+    description_column TEXT,
+    counterparty_column TEXT,
+    note_column TEXT,
+    is_builtin INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Vestavěné profily českých bank
+INSERT OR IGNORE INTO import_profiles (name, bank_code, encoding, date_format, date_column, amount_column,
+    currency_column, description_column, counterparty_column, note_column, is_builtin) VALUES
+    ('Fio banka', '2010', 'utf-8', '%d.%m.%Y', 'Datum', 'Objem',
+        'Měna', 'Zpráva pro příjemce', 'Název protiúčtu', 'Poznámka', 1),
+    ('ČSOB', '0300', 'windows-1250', '%d.%m.%Y', 'datum zaúčtování', 'částka',
+        'měna', 'označení operace', 'název účtu protiúčtu', 'poznámka', 1),
+    ('Komerční banka', '0100', 'windows-1250', '%d.%m.%Y', 'Datum splatnosti', 'Částka',
+        NULL, 'Popis pro příjemce', 'Název protistrany', 'Systémový popis', 1),
+    ('Air Bank', '3030', 'utf-8', '%d.%m.%Y', 'Datum provedení', 'Částka v měně účtu',
+        'Měna účtu', 'Zpráva pro příjemce', 'Název protistrany', 'Poznámka pro mě', 1),
+    ('Raiffeisenbank', '5500', 'windows-1250', '%d.%m.%Y', 'Datum provedení', 'Zaúčtovaná částka',
+        'Měna účtu', 'Zpráva', 'Název protiúčtu', 'Poznámka', 1),
+    ('mBank', '6210', 'windows-1250', '%d-%m-%Y', '#Datum uskutečnění transakce', '#Částka transakce',
+        NULL, '#Zpráva pro příjemce', '#Plátce/Příjemce', '#Popis transakce', 1);
+"#;
+
+fn migrate_v9_import_profiles(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V9_IMPORT_PROFILES)?;
+    Ok(())
+}
+
+fn migrate_v10_external_ids(tx: &Transaction) -> Result<()> {
+    // FITID z OFX, číslo šeku z QIF apod. - brání opakovanému importu stejného pohybu
+    add_column_if_missing(tx, "transactions", "external_id", "TEXT")?;
+    tx.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_transactions_external_id ON transactions(external_id);",
+    )?;
+    Ok(())
+}
+
+fn migrate_v11_payment_details(tx: &Transaction) -> Result<()> {
+    // Strukturované údaje z camt.053 / MT940; `date` zůstává datem zaúčtování
+    add_column_if_missing(tx, "transactions", "value_date", "TEXT")?;
+    add_column_if_missing(tx, "transactions", "variable_symbol", "TEXT")?;
+    add_column_if_missing(tx, "transactions", "constant_symbol", "TEXT")?;
+    add_column_if_missing(tx, "transactions", "specific_symbol", "TEXT")?;
+    add_column_if_missing(tx, "transactions", "counterparty_account", "TEXT")?;
+    tx.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_transactions_variable_symbol ON transactions(variable_symbol);",
+    )?;
+    Ok(())
+}
+
+/// Otisk v podobě z verze 12 (pozdější změny `duplicates::FINGERPRINT_SQL` migraci nemění).
+const V12_FINGERPRINT_SQL: &str = "LOWER(
+    COALESCE((SELECT CAST(bank_id AS TEXT) FROM accounts
+              WHERE accounts.id = COALESCE(transactions.from_account_id, transactions.to_account_id)), '')
+    || '|' || SUBSTR(transactions.date, 1, 10)
+    || '|' || transactions.transaction_type || ':' || transactions.amount
+    || '|' || TRIM(COALESCE(transactions.counterparty_account, transactions.description, ''))
+    || '|' || COALESCE(transactions.external_id, transactions.variable_symbol, ''))";
+
+fn migrate_v12_fingerprints(tx: &Transaction) -> Result<()> {
+    add_column_if_missing(tx, "transactions", "fingerprint", "TEXT")?;
+    tx.execute_batch(&format!(
+        "UPDATE transactions SET fingerprint = {};
+         CREATE INDEX IF NOT EXISTS idx_transactions_fingerprint ON transactions(fingerprint);",
+        V12_FINGERPRINT_SQL
+    ))?;
+    Ok(())
+}
+
+const V13_CATEGORIZATION_RULES: &str = r#"
+-- ============================================
+-- CATEGORIZATION_RULES (Pravidla automatické kategorizace)
+-- ============================================
+CREATE TABLE IF NOT EXISTS categorization_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    active INTEGER NOT NULL DEFAULT 1,
+    stop_processing INTEGER NOT NULL DEFAULT 0,
+    match_pattern TEXT,
+    match_counterparty TEXT,
+    account_id INTEGER,
+    min_amount INTEGER,
+    max_amount INTEGER,
+    transaction_type TEXT,
+    set_category_id INTEGER,
+    set_owner_user_id INTEGER,
+    set_is_shared INTEGER,
+    set_flow_group_id INTEGER,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (set_category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    FOREIGN KEY (set_owner_user_id) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (set_flow_group_id) REFERENCES flow_groups(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS categorization_rule_tags (
+    rule_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (rule_id, tag_id),
+    FOREIGN KEY (rule_id) REFERENCES categorization_rules(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_categorization_rules_active ON categorization_rules(active, priority);
+"#;
+
+fn migrate_v13_categorization_rules(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V13_CATEGORIZATION_RULES)?;
+    Ok(())
+}
+
+const V14_PAYEES: &str = r#"
+-- ============================================
+-- PAYEES (Obchodníci a protistrany)
+-- ============================================
+CREATE TABLE IF NOT EXISTS payees (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    default_category_id INTEGER,
+    default_owner_user_id INTEGER,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (default_category_id) REFERENCES categories(id) ON DELETE SET NULL,
+    FOREIGN KEY (default_owner_user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Alias je text hledaný v popisu nebo protiúčtu, případně regulární výraz
+CREATE TABLE IF NOT EXISTS payee_aliases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    payee_id INTEGER NOT NULL,
+    pattern TEXT NOT NULL,
+    is_regex INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (payee_id) REFERENCES payees(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_payee_aliases_payee ON payee_aliases(payee_id);
+"#;
+
+fn migrate_v14_payees(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V14_PAYEES)?;
+    add_column_if_missing(
+        tx,
+        "transactions",
+        "payee_id",
+        "INTEGER REFERENCES payees(id) ON DELETE SET NULL",
+    )?;
+    tx.execute_batch("CREATE INDEX IF NOT EXISTS idx_transactions_payee ON transactions(payee_id);")?;
+    Ok(())
+}
+
+// Bez diakritiky a velikosti písmen: "cistirna" najde "Čistírna"
+const V15_TRANSACTIONS_FTS: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS transactions_fts USING fts5(
+    description, notes, payee, tags, category,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+"#;
+
+/// Dokument transakce pro `transactions_fts` (rowid = id transakce).
+const FTS_DOCUMENT: &str = "INSERT INTO transactions_fts (rowid, description, notes, payee, tags, category)
+    SELECT t.id, COALESCE(t.description, ''), COALESCE(t.notes, ''), COALESCE(p.name, ''),
+        COALESCE((SELECT group_concat(g.name, ' ') FROM transaction_tags tt
+                  JOIN tags g ON g.id = tt.tag_id WHERE tt.transaction_id = t.id), ''),
+        COALESCE(c.name, '')
+    FROM transactions t
+    LEFT JOIN payees p ON p.id = t.payee_id
+    LEFT JOIN categories c ON c.id = t.category_id";
+
+/// Trigger, který přeindexuje transakce splňující `condition` (nad aliasem `t`).
+fn fts_trigger(name: &str, event: &str, condition: &str) -> String {
+    format!(
+        "CREATE TRIGGER IF NOT EXISTS {name} AFTER {event} BEGIN
+            DELETE FROM transactions_fts WHERE rowid IN (SELECT t.id FROM transactions t WHERE {condition});
+            {document} WHERE {condition};
+        END;",
+        name = name,
+        event = event,
+        condition = condition,
+        document = FTS_DOCUMENT,
+    )
+}
+
+fn migrate_v15_transactions_fts(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V15_TRANSACTIONS_FTS)?;
+    tx.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS transactions_fts_delete AFTER DELETE ON transactions BEGIN
+            DELETE FROM transactions_fts WHERE rowid = OLD.id;
+        END;",
+    )?;
+
+    let triggers = [
+        ("transactions_fts_insert", "INSERT ON transactions", "t.id = NEW.id"),
+        (
+            "transactions_fts_update",
+            "UPDATE OF description, notes, payee_id, category_id ON transactions",
+            "t.id = NEW.id",
+        ),
+        ("transactions_fts_tag_insert", "INSERT ON transaction_tags", "t.id = NEW.transaction_id"),
+        ("transactions_fts_tag_delete", "DELETE ON transaction_tags", "t.id = OLD.transaction_id"),
+        (
+            "transactions_fts_tag_rename",
+            "UPDATE OF name ON tags",
+            "t.id IN (SELECT transaction_id FROM transaction_tags WHERE tag_id = NEW.id)",
+        ),
+        ("transactions_fts_payee_rename", "UPDATE OF name ON payees", "t.payee_id = NEW.id"),
+        ("transactions_fts_category_rename", "UPDATE OF name ON categories", "t.category_id = NEW.id"),
+        ("transactions_fts_category_delete", "DELETE ON categories", "t.category_id = OLD.id"),
+    ];
+    for (name, event, condition) in triggers {
+        tx.execute_batch(&fts_trigger(name, event, condition))?;
+    }
+
+    tx.execute("DELETE FROM transactions_fts", [])?;
+    tx.execute(FTS_DOCUMENT, [])?;
+    Ok(())
+}
+
+const V16_SAVED_VIEWS: &str = r#"
+-- ============================================
+-- SAVED_VIEWS (Uložené filtry transakcí)
+-- ============================================
+-- filters je serializovaný TransactionFilters, date_range relativní období ("last_3_months")
+CREATE TABLE IF NOT EXISTS saved_views (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    filters TEXT NOT NULL DEFAULT '{}',
+    date_range TEXT,
+    sort_by TEXT,
+    sort_direction TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+"#;
+
+fn migrate_v16_saved_views(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V16_SAVED_VIEWS)?;
+    Ok(())
+}
+
+const V17_RECONCILIATIONS: &str = r#"
+-- ============================================
+-- RECONCILIATIONS (Odsouhlasení účtu s výpisem)
+-- ============================================
+CREATE TABLE IF NOT EXISTS reconciliations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id INTEGER NOT NULL,
+    statement_date TEXT NOT NULL,
+    statement_balance INTEGER NOT NULL,
+    cleared_balance INTEGER,
+    status TEXT NOT NULL DEFAULT 'open',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_reconciliations_account ON reconciliations(account_id, statement_date);
+"#;
+
+fn migrate_v17_reconciliations(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(V17_RECONCILIATIONS)?;
+    // uncleared | cleared (odškrtnuto proti výpisu) | reconciled (uzamčeno)
+    add_column_if_missing(
+        tx,
+        "transactions",
+        "reconciliation_status",
+        "TEXT NOT NULL DEFAULT 'uncleared'",
+    )?;
+    add_column_if_missing(
+        tx,
+        "transactions",
+        "reconciliation_id",
+        "INTEGER REFERENCES reconciliations(id) ON DELETE SET NULL",
+    )?;
+    Ok(())
+}
+
+fn migrate_v18_payment_identifiers(tx: &Transaction) -> Result<()> {
+    for table in ["recurring_payments", "scheduled_transfers"] {
+        for column in ["variable_symbol", "constant_symbol", "specific_symbol", "counterparty_account"] {
+            add_column_if_missing(tx, table, column, "TEXT")?;
+        }
+    }
+    tx.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_transactions_counterparty ON transactions(counterparty_account);",
+    )?;
+
+    // Protiúčty z dřívějších importů do jednotného tvaru (český IBAN -> tuzemský zápis)
+    let accounts = {
+        let mut stmt = tx.prepare(
+            "SELECT DISTINCT counterparty_account FROM transactions WHERE counterparty_account IS NOT NULL",
+        )?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        rows
+    };
+    for account in accounts {
+        let normalized = crate::utils::payment::normalize_account(&account);
+        if normalized != account {
+            tx.execute(
+                "UPDATE transactions SET counterparty_account = ?1 WHERE counterparty_account = ?2",
+                [&normalized, &account],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn migrate_v19_schedules(tx: &Transaction) -> Result<()> {
+    use crate::utils::schedule::Schedule;
+    use chrono::NaiveDate;
+
+    // Pravidlo ze starších polí: (id, frekvence, počet period, den v měsíci/týdnu, začátek)
+    let sources = [
+        (
+            "recurring_payments",
+            "SELECT id, frequency, frequency_value, day_of_period,
+             COALESCE(next_execution_date, date(created_at), date('now')) FROM recurring_payments",
+        ),
+        (
+            "member_incomes",
+            "SELECT id, frequency, 1, day_of_month, COALESCE(date(created_at), date('now'))
+             FROM member_incomes",
+        ),
+        (
+            "fixed_expenses",
+            "SELECT id, frequency, 1, day_of_month, COALESCE(date(created_at), date('now'))
+             FROM fixed_expenses",
+        ),
+        (
+            "scheduled_transfers",
+            "SELECT id, 'monthly', 1, day_of_month, COALESCE(date(created_at), date('now'))
+             FROM scheduled_transfers",
+        ),
+        (
+            "financial_goals",
+            "SELECT id, 'weekly', 1, day_of_week, COALESCE(date(created_at), date('now'))
+             FROM financial_goals WHERE goal_type = 'weekly_variable' AND day_of_week IS NOT NULL",
+        ),
+    ];
+
+    for (table, query) in sources {
+        add_column_if_missing(tx, table, "schedule", "TEXT")?;
+        let rows = {
+            let mut stmt = tx.prepare(query)?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<i32>>(2)?,
+                        row.get::<_, Option<i32>>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+        for (id, frequency, interval, day, start) in rows {
+            let start = NaiveDate::parse_from_str(&start, "%Y-%m-%d")
+                .unwrap_or_else(|_| chrono::Local::now().date_naive());
+            let schedule = if table == "financial_goals" {
+                Schedule::weekly_on(day.unwrap_or(0), start)
+            } else {
+                Schedule::legacy(
+                    frequency.as_deref().unwrap_or("monthly"),
+                    interval.unwrap_or(1),
+                    day,
+                    start,
+                )
+            };
+            tx.execute(
+                &format!("UPDATE {} SET schedule = ?1 WHERE id = ?2", table),
+                rusqlite::params![schedule.to_string(), id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn migrate_v20_recurring_occurrences(tx: &Transaction) -> Result<()> {
+    add_column_if_missing(tx, "transactions", "occurrence_date", "TEXT")?;
+    // Každý termín pravidelné platby smí vytvořit nejvýše jednu transakci
+    tx.execute_batch(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_transactions_recurring_occurrence
+         ON transactions(recurring_payment_id, occurrence_date)
+         WHERE occurrence_date IS NOT NULL;",
+    )?;
+    Ok(())
+}
+
+fn migrate_v21_recurring_generated(tx: &Transaction) -> Result<()> {
+    add_column_if_missing(tx, "transactions", "recurring_generated", "INTEGER NOT NULL DEFAULT 0")?;
+    // Termín měly dosud jen transakce založené zpracováním pravidelných plateb
+    tx.execute(
+        "UPDATE transactions SET recurring_generated = 1 WHERE occurrence_date IS NOT NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Databáze založená aplikací před zavedením migrací: jen `SCHEMA`, bez záznamu verze.
+    fn baseline_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, name, color, is_shared_user) VALUES (2, 'Jana', '#EC4899', 0);
+             INSERT INTO accounts (id, name, account_type, initial_balance, current_balance)
+             VALUES (1, 'Běžný', 'checking', 1234.56, 1133.555);
+             INSERT INTO tags (id, name) VALUES (1, 'dovolená');
+             INSERT INTO transactions (id, date, amount, transaction_type, from_account_id,
+                 category_id, description, notes)
+             VALUES (1, '2023-07-14', 101.005, 'expense', 1, 2, 'Benzín OMV', 'cesta k moři');
+             INSERT INTO transaction_tags (transaction_id, tag_id) VALUES (1, 1);
+             INSERT INTO household_members (id, name, color) VALUES
+                 (1, ' jana ', '#000000'), (2, 'Petr', '#10B981');
+             INSERT INTO member_incomes (id, member_id, name, amount, account_id) VALUES
+                 (1, 1, 'Mzda', 42000.5, 1), (2, 2, 'Brigáda', 0.1, NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn column_type(conn: &Connection, table: &str, column: &str) -> String {
+        conn.query_row(
+            "SELECT type FROM pragma_table_info(?1) WHERE name = ?2",
+            [table, column],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn upgrades_baseline_database() {
+        let mut conn = baseline_database();
+        assert_eq!(current_version(&conn).unwrap(), 0);
+
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+        // Peníze v setinách jako INTEGER
+        for (table, columns) in V3_MONEY_COLUMNS {
+            for column in *columns {
+                assert_eq!(
+                    column_type(&conn, table, column),
+                    "INTEGER",
+                    "{}.{}",
+                    table,
+                    column
+                );
+            }
+        }
+        let balances: (i64, i64) = conn
+            .query_row(
+                "SELECT initial_balance, current_balance FROM accounts WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(balances, (123456, 113356));
+        let amount: i64 = conn
+            .query_row("SELECT amount FROM transactions WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(amount, 10101);
+
+        // Členové domácnosti jsou uživatelé: Jana podle jména, Petr nově založený
+        let incomes: Vec<(String, String, i64)> = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT mi.name, u.name, mi.amount FROM member_incomes mi
+                     JOIN users u ON u.id = mi.user_id ORDER BY mi.id",
+                )
+                .unwrap();
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .collect::<std::result::Result<_, _>>()
+                .unwrap();
+            rows
+        };
+        assert_eq!(
+            incomes,
+            vec![
+                ("Mzda".to_string(), "Jana".to_string(), 4200050),
+                ("Brigáda".to_string(), "Petr".to_string(), 10),
+            ]
+        );
+        let users: i64 = conn
+            .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(users, 3);
+        let legacy: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'household_members'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(legacy, 0);
+
+        // Fulltext obsahuje i transakce z doby před migrací
+        for query in ["benzin", "mori", "dovolena", "vydaj"] {
+            let found: Vec<i64> = {
+                let mut stmt = conn
+                    .prepare("SELECT rowid FROM transactions_fts WHERE transactions_fts MATCH ?1")
+                    .unwrap();
+                let rows = stmt
+                    .query_map([query], |row| row.get(0))
+                    .unwrap()
+                    .collect::<std::result::Result<_, _>>()
+                    .unwrap();
+                rows
+            };
+            assert_eq!(found, vec![1], "{}", query);
+        }
+
+        let violations: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(violations, 0);
+
+        // Opakované spuštění nic nemění
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+    }
+}
@@ -1,3 +1,6 @@
 pub mod connection;
+pub mod currency;
+pub mod ledger;
+pub mod migrations;
 pub mod schema;
-
+pub mod settings;
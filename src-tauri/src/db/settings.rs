@@ -0,0 +1,29 @@
+use crate::utils::error::Result;
+use rusqlite::{Connection, OptionalExtension};
+
+/// Měna použitá, když ji nelze odvodit ze vstupu ani z účtu.
+pub const FALLBACK_CURRENCY: &str = "CZK";
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
+    let value = conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    Ok(value)
+}
+
+/// Měna, do které se přepočítávají reporty (`settings.default_currency`).
+pub fn default_currency(conn: &Connection) -> Result<String> {
+    Ok(get_setting(conn, "default_currency")?
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or_else(|| FALLBACK_CURRENCY.to_string()))
+}
+
+/// První měsíc fiskálního roku (`settings.fiscal_month_start`, 1–12).
+pub fn fiscal_month_start(conn: &Connection) -> Result<u32> {
+    Ok(get_setting(conn, "fiscal_month_start")?
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|month| (1..=12).contains(month))
+        .unwrap_or(1))
+}
@@ -0,0 +1,273 @@
+//! ISO 20022 camt.053 (výpis), camt.052 a camt.054 (avíza) bez ohledu na verzi schématu.
+
+use super::{find_symbol, non_empty, ParsedStatement, StatementBalance, StatementRow};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use chrono::NaiveDate;
+use roxmltree::{Document, Node};
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+/// Text elementu na cestě `path` (názvy bez jmenného prostoru).
+fn text_at(node: Node, path: &[&str]) -> Option<String> {
+    let mut current = node;
+    for name in path {
+        current = child(current, name)?;
+    }
+    current.text().and_then(non_empty)
+}
+
+/// `<Dt>2024-03-05</Dt>` i `<DtTm>2024-03-05T10:00:00+01:00</DtTm>`
+fn date_at(node: Node, name: &str) -> Option<String> {
+    let date = child(node, name)?;
+    let text = text_at(date, &["Dt"]).or_else(|| text_at(date, &["DtTm"]))?;
+    NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// Částka s ohledem na `CdtDbtInd` (DBIT = odepsání).
+fn signed_amount(node: Node) -> Option<(Money, Option<String>)> {
+    let amount = child(node, "Amt")?;
+    let value: Money = amount.text()?.trim().parse().ok()?;
+    let currency = amount.attribute("Ccy").map(str::to_uppercase);
+    match text_at(node, &["CdtDbtInd"]).as_deref() {
+        Some("DBIT") => Some((-value, currency)),
+        _ => Some((value, currency)),
+    }
+}
+
+/// Číslo účtu: IBAN, nebo jiný identifikátor (český formát "123456789/0800").
+fn account_id(account: Node) -> Option<String> {
+    text_at(account, &["Id", "IBAN"]).or_else(|| text_at(account, &["Id", "Othr", "Id"]))
+}
+
+fn balance_type(balance: Node) -> Option<String> {
+    text_at(balance, &["Tp", "CdOrPrtry", "Cd"])
+        .or_else(|| text_at(balance, &["Tp", "CdOrPrtry", "Prtry"]))
+}
+
+/// Pohyb a jeho (první) detail: symboly, protistrana, zpráva.
+fn parse_entry(entry: Node, line: usize, parsed: &mut ParsedStatement) {
+    // Jen zaúčtované pohyby; camt.053 v8+ má stav zanořený v <Sts><Cd>
+    let status = text_at(entry, &["Sts"]).or_else(|| text_at(entry, &["Sts", "Cd"]));
+    if status.as_deref().is_some_and(|s| s != "BOOK") {
+        return;
+    }
+
+    let Some(date) = date_at(entry, "BookgDt").or_else(|| date_at(entry, "ValDt")) else {
+        parsed.error(line, "Chybí nebo je neplatné datum zaúčtování (BookgDt)");
+        return;
+    };
+    let Some((amount, currency)) = signed_amount(entry) else {
+        parsed.error(line, "Chybí nebo je neplatná částka (Amt)");
+        return;
+    };
+
+    let details = child(entry, "NtryDtls").and_then(|d| child(d, "TxDtls"));
+    let is_debit = amount < Money::ZERO;
+    let (party, party_account) = if is_debit {
+        ("Cdtr", "CdtrAcct")
+    } else {
+        ("Dbtr", "DbtrAcct")
+    };
+
+    let mut row = StatementRow {
+        line,
+        date,
+        amount,
+        currency,
+        value_date: date_at(entry, "ValDt"),
+        external_id: text_at(entry, &["AcctSvcrRef"])
+            .or_else(|| text_at(entry, &["NtryRef"]))
+            .or_else(|| details.and_then(|d| text_at(d, &["Refs", "AcctSvcrRef"]))),
+        note: text_at(entry, &["AddtlNtryInf"]),
+        ..Default::default()
+    };
+
+    if let Some(details) = details {
+        if let Some(parties) = child(details, "RltdPties") {
+            // camt.053 v8+ obaluje stranu do <Pty>
+            row.counterparty = child(parties, party)
+                .and_then(|p| text_at(p, &["Nm"]).or_else(|| text_at(p, &["Pty", "Nm"])));
+            row.counterparty_account = child(parties, party_account).and_then(account_id);
+        }
+        if let Some(remittance) = child(details, "RmtInf") {
+            let unstructured: Vec<String> = children(remittance, "Ustrd")
+                .filter_map(|n| n.text().and_then(non_empty))
+                .collect();
+            row.description = non_empty(&unstructured.join(" "));
+        }
+
+        // České banky uvádějí symboly v referencích ("VS:123", "/VS123/SS456/KS0308")
+        let references: Vec<String> = [
+            text_at(details, &["Refs", "EndToEndId"]),
+            text_at(details, &["Refs", "InstrId"]),
+            text_at(details, &["RmtInf", "Strd", "CdtrRefInf", "Ref"]),
+            text_at(details, &["Purp", "Prtry"]),
+            row.description.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let references = references.join(" ");
+        row.variable_symbol = find_symbol(&references, "VS");
+        row.constant_symbol = find_symbol(&references, "KS");
+        row.specific_symbol = find_symbol(&references, "SS");
+    }
+
+    parsed.rows.push(row);
+}
+
+/// Rozparsuje camt výpis s jedním účtem; počáteční a konečný zůstatek z `<Bal>`.
+pub(crate) fn parse(text: &str) -> Result<ParsedStatement> {
+    let document = Document::parse(text)
+        .map_err(|e| AppError::InvalidInput(format!("Neplatný XML soubor: {}", e)))?;
+    let root = document.root_element();
+
+    let Some(message) = root.children().find(|n| {
+        matches!(
+            n.tag_name().name(),
+            "BkToCstmrStmt" | "BkToCstmrAcctRpt" | "BkToCstmrDbtCdtNtfctn"
+        )
+    }) else {
+        return Err(AppError::InvalidInput(
+            "Soubor není výpis ani avízo ve formátu camt".to_string(),
+        ));
+    };
+
+    let statements: Vec<Node> = message
+        .children()
+        .filter(|n| matches!(n.tag_name().name(), "Stmt" | "Rpt" | "Ntfctn"))
+        .collect();
+    let accounts: std::collections::HashSet<Option<String>> = statements
+        .iter()
+        .map(|s| child(*s, "Acct").and_then(account_id))
+        .collect();
+    if accounts.len() > 1 {
+        return Err(AppError::InvalidInput(
+            "Soubor obsahuje výpisy více účtů, importujte je jednotlivě".to_string(),
+        ));
+    }
+
+    let mut parsed = ParsedStatement::default();
+    for statement in statements {
+        let currency = text_at(statement, &["Acct", "Ccy"]).map(|c| c.to_uppercase());
+
+        for balance in children(statement, "Bal") {
+            let Some((amount, _)) = signed_amount(balance) else {
+                continue;
+            };
+            let value = StatementBalance {
+                amount,
+                date: date_at(balance, "Dt"),
+            };
+            match balance_type(balance).as_deref() {
+                // Výpisy za více dní: první počáteční a poslední konečný zůstatek
+                Some("OPBD") | Some("PRCD") if parsed.opening_balance.is_none() => {
+                    parsed.opening_balance = Some(value)
+                }
+                Some("CLBD") => parsed.closing_balance = Some(value),
+                _ => {}
+            }
+        }
+
+        for entry in children(statement, "Ntry") {
+            let line = document.text_pos_at(entry.range().start).row as usize;
+            let before = parsed.rows.len();
+            parse_entry(entry, line, &mut parsed);
+            if let Some(row) = parsed.rows.get_mut(before) {
+                if row.currency.is_none() {
+                    row.currency = currency.clone();
+                }
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_booked_entries_with_details() {
+        let parsed = parse(include_str!("fixtures/camt053.xml")).unwrap();
+
+        assert_eq!(parsed.rows.len(), 2);
+        let first = &parsed.rows[0];
+        assert_eq!((first.line, first.date.as_str()), (20, "2024-03-05"));
+        assert_eq!(first.amount.minor(), -125050);
+        assert_eq!(first.currency.as_deref(), Some("CZK"));
+        assert_eq!(first.value_date.as_deref(), Some("2024-03-04"));
+        assert_eq!(first.external_id.as_deref(), Some("BANK-1"));
+        assert_eq!(first.counterparty.as_deref(), Some("Elektrárna s.r.o."));
+        assert_eq!(first.counterparty_account.as_deref(), Some("123457/0710"));
+        assert_eq!(first.description.as_deref(), Some("Záloha březen"));
+        assert_eq!(first.variable_symbol.as_deref(), Some("1234567890"));
+        assert_eq!(first.specific_symbol.as_deref(), Some("77"));
+        assert_eq!(first.constant_symbol.as_deref(), Some("0308"));
+
+        let salary = &parsed.rows[1];
+        assert_eq!((salary.line, salary.amount.minor()), (40, 4500000));
+        assert_eq!(salary.currency.as_deref(), Some("EUR"));
+        assert_eq!(salary.external_id.as_deref(), Some("BANK-2"));
+        assert_eq!(salary.note.as_deref(), Some("Mzda 03/2024"));
+        assert_eq!(salary.counterparty.as_deref(), Some("Zaměstnavatel a.s."));
+        assert_eq!(
+            salary.counterparty_account.as_deref(),
+            Some("CZ7908000000002000145399")
+        );
+        assert_eq!(salary.variable_symbol.as_deref(), Some("202403"));
+
+        let errors: Vec<usize> = parsed.errors.iter().map(|e| e.line).collect();
+        assert_eq!(errors, [63]);
+        let opening = parsed.opening_balance.unwrap();
+        assert_eq!(opening.amount.minor(), 100000);
+        assert_eq!(opening.date.as_deref(), Some("2024-02-29"));
+        assert_eq!(parsed.closing_balance.unwrap().amount.minor(), 4474950);
+
+        let other = include_str!("fixtures/camt053.xml").replacen(
+            "</Stmt>",
+            "</Stmt><Stmt><Acct><Id><IBAN>CZ7908000000002000145399</IBAN></Id></Acct></Stmt>",
+            1,
+        );
+        assert!(matches!(parse(&other), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn newer_schema_nests_status_and_party() {
+        let parsed = parse(include_str!("fixtures/camt053_v8.xml")).unwrap();
+
+        assert_eq!(parsed.rows.len(), 1);
+        let row = &parsed.rows[0];
+        assert_eq!(
+            (row.date.as_str(), row.amount.minor()),
+            ("2024-03-07", -50000)
+        );
+        assert_eq!(row.counterparty.as_deref(), Some("Nájem s.r.o."));
+        assert_eq!(row.currency.as_deref(), Some("CZK"));
+        assert!(parsed.closing_balance.is_none());
+    }
+
+    #[test]
+    fn xml_without_statement_is_rejected() {
+        assert!(matches!(parse("<a>"), Err(AppError::InvalidInput(_))));
+        assert!(matches!(
+            parse("<Document><Other/></Document>"),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,247 @@
+use super::{non_empty, parse_amount, ParsedStatement, StatementRow};
+use crate::models::ImportProfile;
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use chrono::NaiveDate;
+
+/// Kolik řádků na začátku souboru se prohledá kvůli záhlaví (banky před něj dávají
+/// údaje o účtu a období).
+const HEADER_SEARCH_LINES: usize = 30;
+
+struct Columns {
+    date: usize,
+    amount: usize,
+    debit: Option<usize>,
+    direction: Option<usize>,
+    currency: Option<usize>,
+    description: Option<usize>,
+    counterparty: Option<usize>,
+    note: Option<usize>,
+}
+
+fn find_column(header: &[String], name: &str) -> Option<usize> {
+    let name = name.trim().to_lowercase();
+    header.iter().position(|cell| cell.trim().to_lowercase() == name)
+}
+
+fn resolve_columns(header: &[String], profile: &ImportProfile) -> Result<Columns> {
+    let required = |name: &str| {
+        find_column(header, name).ok_or_else(|| {
+            AppError::InvalidInput(format!("Ve výpisu chybí sloupec '{}'", name))
+        })
+    };
+    let optional = |name: &Option<String>| -> Result<Option<usize>> {
+        match name {
+            Some(name) => required(name).map(Some),
+            None => Ok(None),
+        }
+    };
+
+    Ok(Columns {
+        date: required(&profile.date_column)?,
+        amount: required(&profile.amount_column)?,
+        debit: optional(&profile.debit_column)?,
+        direction: optional(&profile.direction_column)?,
+        currency: optional(&profile.currency_column)?,
+        description: optional(&profile.description_column)?,
+        counterparty: optional(&profile.counterparty_column)?,
+        note: optional(&profile.note_column)?,
+    })
+}
+
+fn delimiter_byte(profile: &ImportProfile) -> Result<u8> {
+    match profile.delimiter.as_str() {
+        "\\t" | "\t" => Ok(b'\t'),
+        d if d.len() == 1 => Ok(d.as_bytes()[0]),
+        d => Err(AppError::InvalidInput(format!("Neplatný oddělovač: '{}'", d))),
+    }
+}
+
+/// Rozparsuje CSV výpis podle profilu. Řádky bez data (součty, patičky) se přeskočí,
+/// chybné řádky se vrátí jako chyby s číslem řádku v souboru.
+pub(crate) fn parse(text: &str, profile: &ImportProfile) -> Result<ParsedStatement> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter_byte(profile)?)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let mut columns = None;
+    let mut parsed = ParsedStatement::default();
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line() as usize).unwrap_or(0);
+        let cells: Vec<String> = record.iter().map(|c| c.trim().to_string()).collect();
+
+        let Some(columns) = &columns else {
+            if find_column(&cells, &profile.date_column).is_some() {
+                columns = Some(resolve_columns(&cells, profile)?);
+            } else if line > HEADER_SEARCH_LINES {
+                break;
+            }
+            continue;
+        };
+
+        let cell = |index: usize| cells.get(index).map(String::as_str).unwrap_or("");
+        let optional = |index: Option<usize>| index.and_then(|i| non_empty(cell(i)));
+
+        let date_text = cell(columns.date);
+        if date_text.is_empty() {
+            continue;
+        }
+        let date = match NaiveDate::parse_from_str(date_text, &profile.date_format) {
+            Ok(date) => date.format("%Y-%m-%d").to_string(),
+            Err(_) => {
+                parsed.error(line, format!("Neplatné datum '{}'", date_text));
+                continue;
+            }
+        };
+
+        let mut amount = match (non_empty(cell(columns.amount)), columns.debit) {
+            (Some(text), _) => parse_amount(&text, profile.decimal_comma),
+            // Odepsaná částka v samostatném sloupci, vždy jako výdaj
+            (None, Some(debit)) => non_empty(cell(debit))
+                .and_then(|text| parse_amount(&text, profile.decimal_comma))
+                .map(|amount| if amount > Money::ZERO { -amount } else { amount }),
+            (None, None) => None,
+        };
+        if let (Some(direction), Some(debit_value), Some(value)) =
+            (columns.direction, &profile.debit_value, amount)
+        {
+            if cell(direction).eq_ignore_ascii_case(debit_value.trim()) && value > Money::ZERO {
+                amount = Some(-value);
+            }
+        }
+
+        let Some(amount) = amount else {
+            parsed.error(line, "Chybí nebo je neplatná částka");
+            continue;
+        };
+
+        parsed.rows.push(StatementRow {
+            line,
+            date,
+            amount,
+            currency: optional(columns.currency).map(|c| c.to_uppercase()),
+            description: optional(columns.description),
+            counterparty: optional(columns.counterparty),
+            note: optional(columns.note),
+            ..Default::default()
+        });
+    }
+
+    if columns.is_none() {
+        return Err(AppError::InvalidInput(format!(
+            "Ve výpisu nebylo nalezeno záhlaví se sloupcem '{}'",
+            profile.date_column
+        )));
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(date_column: &str, amount_column: &str) -> ImportProfile {
+        ImportProfile {
+            id: 0,
+            name: "Test".to_string(),
+            bank_code: None,
+            delimiter: ";".to_string(),
+            encoding: "utf-8".to_string(),
+            date_format: "%d.%m.%Y".to_string(),
+            decimal_comma: true,
+            date_column: date_column.to_string(),
+            amount_column: amount_column.to_string(),
+            debit_column: None,
+            direction_column: None,
+            debit_value: None,
+            currency_column: None,
+            description_column: None,
+            counterparty_column: None,
+            note_column: None,
+            is_builtin: false,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn fio() -> ImportProfile {
+        ImportProfile {
+            currency_column: Some("Měna".to_string()),
+            description_column: Some("Zpráva pro příjemce".to_string()),
+            counterparty_column: Some("Název protiúčtu".to_string()),
+            note_column: Some("poznámka".to_string()),
+            ..profile("Datum", "Objem")
+        }
+    }
+
+    #[test]
+    fn parses_export_with_preamble_and_footer() {
+        let parsed = parse(include_str!("fixtures/fio.csv"), &fio()).unwrap();
+
+        assert_eq!(parsed.rows.len(), 2);
+        let first = &parsed.rows[0];
+        assert_eq!((first.line, first.date.as_str()), (6, "2024-03-05"));
+        assert_eq!(first.amount.minor(), -125050);
+        assert_eq!(first.currency.as_deref(), Some("CZK"));
+        assert_eq!(first.description.as_deref(), Some("Záloha březen"));
+        assert_eq!(first.counterparty.as_deref(), Some("Elektrárna s.r.o."));
+        assert_eq!(first.note.as_deref(), Some("elektřina"));
+
+        let second = &parsed.rows[1];
+        assert_eq!(second.amount.minor(), 4500000);
+        assert_eq!(second.currency.as_deref(), Some("CZK"));
+        assert_eq!(second.description, None);
+
+        let errors: Vec<usize> = parsed.errors.iter().map(|e| e.line).collect();
+        assert_eq!(errors, [8, 9]);
+    }
+
+    #[test]
+    fn debit_column_and_direction_make_expenses() {
+        let profile = ImportProfile {
+            delimiter: "\\t".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            decimal_comma: false,
+            debit_column: Some("Debit".to_string()),
+            direction_column: Some("Direction".to_string()),
+            debit_value: Some("DBIT".to_string()),
+            currency_column: Some("Currency".to_string()),
+            description_column: Some("Description".to_string()),
+            ..profile("Date", "Credit")
+        };
+        let parsed = parse(include_str!("fixtures/debit_columns.tsv"), &profile).unwrap();
+
+        let amounts: Vec<i64> = parsed.rows.iter().map(|r| r.amount.minor()).collect();
+        assert_eq!(amounts, [10000, -2550, -4000]);
+        assert_eq!(parsed.rows[1].currency.as_deref(), Some("EUR"));
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line, 5);
+    }
+
+    #[test]
+    fn missing_header_or_column_is_an_error() {
+        let text = include_str!("fixtures/fio.csv");
+        let missing = ImportProfile {
+            description_column: Some("Popis".to_string()),
+            ..fio()
+        };
+        assert!(matches!(
+            parse(text, &missing),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            parse(text, &profile("Datum zaúčtování", "Objem")),
+            Err(AppError::InvalidInput(_))
+        ));
+        let comma = ImportProfile {
+            delimiter: ",;".to_string(),
+            ..fio()
+        };
+        assert!(parse(text, &comma).is_err());
+    }
+}
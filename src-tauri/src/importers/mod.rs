@@ -0,0 +1,224 @@
+//! Parsery bankovních výpisů. Každý formát vrací řádky `StatementRow`,
+//! které do transakcí převádí `commands::imports`.
+
+pub mod camt;
+pub mod csv_statement;
+pub mod mt940;
+pub mod ofx;
+pub mod qif;
+
+use crate::models::ImportRowError;
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+
+/// Jeden pohyb z výpisu; kladná částka je připsání na účet, záporná odepsání.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct StatementRow {
+    pub line: usize,
+    pub date: String,
+    pub amount: Money,
+    pub currency: Option<String>,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+    pub note: Option<String>,
+    pub external_id: Option<String>,
+    pub value_date: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+}
+
+/// Počáteční nebo konečný zůstatek uvedený ve výpisu.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StatementBalance {
+    pub amount: Money,
+    pub date: Option<String>,
+}
+
+/// Výsledek parsování: platné řádky a chyby jednotlivých řádků.
+#[derive(Debug, Default)]
+pub(crate) struct ParsedStatement {
+    pub rows: Vec<StatementRow>,
+    pub errors: Vec<ImportRowError>,
+    pub opening_balance: Option<StatementBalance>,
+    pub closing_balance: Option<StatementBalance>,
+}
+
+impl ParsedStatement {
+    pub fn error(&mut self, line: usize, message: impl Into<String>) {
+        self.errors.push(ImportRowError {
+            line,
+            message: message.into(),
+        });
+    }
+}
+
+/// Dekóduje soubor v zadaném kódování (např. `windows-1250`) a odstraní BOM.
+pub(crate) fn decode(bytes: &[u8], encoding: &str) -> Result<String> {
+    let encoding = encoding_rs::Encoding::for_label(encoding.trim().as_bytes())
+        .ok_or_else(|| AppError::InvalidInput(format!("Neznámé kódování: {}", encoding)))?;
+    let (text, _, _) = encoding.decode(bytes);
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// Dekóduje soubor bez udaného kódování: UTF-8, a není-li platné, Windows-1250.
+pub(crate) fn decode_auto(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.trim_start_matches('\u{feff}').to_string(),
+        Err(_) => encoding_rs::WINDOWS_1250.decode(bytes).0.into_owned(),
+    }
+}
+
+/// Částka z výpisu: "-1 234,56", "1.234,56 CZK", "+250.00". Mezery a měna se ignorují.
+pub(crate) fn parse_amount(text: &str, decimal_comma: bool) -> Option<Money> {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '-' | ',' | '.'))
+        .collect();
+    let normalized = if decimal_comma {
+        cleaned.replace('.', "").replace(',', ".")
+    } else {
+        cleaned.replace(',', "")
+    };
+    normalized.parse().ok()
+}
+
+/// Desetinná čárka, pokud je čárka posledním oddělovačem a nejde o oddělovač tisíců
+/// ("12,50" ano, "1,234" a "1,234.50" ne).
+pub(crate) fn guess_decimal_comma(text: &str) -> bool {
+    match (text.rfind(','), text.rfind('.')) {
+        (Some(comma), dot) if !matches!(dot, Some(dot) if dot > comma) => {
+            text[comma + 1..].chars().filter(char::is_ascii_digit).count() != 3
+        }
+        _ => false,
+    }
+}
+
+/// Platební symbol z volného textu: "VS:123", "/VS123", "VS 123", "VS=123".
+pub(crate) fn find_symbol(text: &str, key: &str) -> Option<String> {
+    let upper = text.to_uppercase();
+    let mut from = 0;
+
+    while let Some(pos) = upper[from..].find(key) {
+        let start = from + pos;
+        from = start + key.len();
+        if upper[..start].chars().next_back().is_some_and(|c| c.is_alphanumeric()) {
+            continue;
+        }
+
+        let digits: String = upper[from..]
+            .trim_start_matches([':', '/', '=', ' ', '.'])
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        if !digits.is_empty() && digits.len() <= 10 {
+            return Some(digits);
+        }
+    }
+    None
+}
+
+/// Prázdné buňky jako `None`.
+pub(crate) fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_in_bank_formats() {
+        let amount = |text, decimal_comma| parse_amount(text, decimal_comma).map(|m| m.minor());
+        assert_eq!(amount("-1 234,56", true), Some(-123456));
+        assert_eq!(amount("1.234,56 CZK", true), Some(123456));
+        assert_eq!(amount("+250.00", false), Some(25000));
+        assert_eq!(amount("1,234.50", false), Some(123450));
+        assert_eq!(amount("12", true), Some(1200));
+        assert_eq!(amount("", true), None);
+        assert_eq!(amount("CZK", false), None);
+    }
+
+    #[test]
+    fn decode_strips_bom_and_converts_windows_1250() {
+        assert_eq!(decode(b"\xef\xbb\xbfDatum", "utf-8").unwrap(), "Datum");
+        assert_eq!(
+            decode(b"Platba \x9a\xe8", "windows-1250").unwrap(),
+            "Platba šč"
+        );
+        assert_eq!(decode(b"Platba \x9a\xe8", " cp1250 ").unwrap(), "Platba šč");
+        assert!(matches!(
+            decode(b"x", "klingon"),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn decimal_comma_guess() {
+        assert!(guess_decimal_comma("12,50"));
+        assert!(guess_decimal_comma("1.234,5"));
+        assert!(!guess_decimal_comma("1,234"));
+        assert!(!guess_decimal_comma("1,234.50"));
+        assert!(!guess_decimal_comma("100"));
+    }
+
+    #[test]
+    fn decode_auto_falls_back_to_windows_1250() {
+        assert_eq!(decode_auto("\u{feff}Účet".as_bytes()), "Účet");
+        assert_eq!(decode_auto(b"\x8a\x9dastn\xfd"), "Šťastný");
+    }
+
+    #[test]
+    fn symbols_in_free_text() {
+        assert_eq!(
+            find_symbol("Platba VS:123 dekujeme", "VS").as_deref(),
+            Some("123")
+        );
+        assert_eq!(find_symbol("/VS0308/SS42", "SS").as_deref(), Some("42"));
+        assert_eq!(find_symbol("ks 0308", "KS").as_deref(), Some("0308"));
+        assert_eq!(find_symbol("VS=77, VS 88", "VS").as_deref(), Some("77"));
+        assert_eq!(find_symbol("AVS 12", "VS"), None);
+        assert_eq!(find_symbol("VSX12", "VS"), None);
+        assert_eq!(find_symbol("VS 12345678901", "VS"), None);
+    }
+
+    /// Každý parser odmítne soubor jiného formátu místo toho, aby z něj vyrobil prázdný výpis.
+    #[test]
+    fn parsers_reject_other_formats() {
+        let files: [(&str, &[u8]); 6] = [
+            ("csv", &include_bytes!("fixtures/fio.csv")[..]),
+            ("ofx", &include_bytes!("fixtures/statement_1250.ofx")[..]),
+            ("ofx", &include_bytes!("fixtures/creditcard.ofx")[..]),
+            ("qif", &include_bytes!("fixtures/bank.qif")[..]),
+            ("camt", &include_bytes!("fixtures/camt053.xml")[..]),
+            ("mt940", &include_bytes!("fixtures/statement.sta")[..]),
+        ];
+        type Parser = fn(&str) -> Result<ParsedStatement>;
+        let parsers: [(&str, Parser); 4] = [
+            ("ofx", ofx::parse),
+            ("qif", |text| qif::parse(text, None)),
+            ("camt", camt::parse),
+            ("mt940", mt940::parse),
+        ];
+
+        for (format, bytes) in files {
+            let text = decode_auto(bytes);
+            for (parser, parse) in parsers {
+                if parser != format {
+                    assert!(
+                        matches!(parse(&text), Err(AppError::InvalidInput(_))),
+                        "{} -> {}",
+                        format,
+                        parser
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! SWIFT MT940. Pole `:86:` bývá volný text ("VS 123 KS 0308") nebo podpole `?20`–`?33`.
+
+use super::{find_symbol, non_empty, ParsedStatement, StatementBalance, StatementRow};
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use chrono::{Datelike, NaiveDate};
+
+struct Field {
+    tag: String,
+    line: usize,
+    content: String,
+}
+
+/// Rozdělí zprávu na pole `:TAG:obsah`; pokračovací řádky patří k předchozímu poli.
+fn fields(text: &str) -> Vec<Field> {
+    let mut result: Vec<Field> = Vec::new();
+    let mut open = false;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        let tag = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(tag, _)| {
+                (2..=3).contains(&tag.len())
+                    && tag
+                        .get(..2)
+                        .is_some_and(|code| code.chars().all(|c| c.is_ascii_digit()))
+            });
+
+        if let Some((tag, content)) = tag {
+            result.push(Field {
+                tag: tag.to_string(),
+                line: index + 1,
+                content: content.to_string(),
+            });
+            open = true;
+        } else if line.starts_with('{') || line == "-" || line.starts_with("-}") {
+            // Obálka SWIFT zprávy ({1:...}{4:) a její konec (-, -} nebo -}{5:...});
+            // pokračovací řádek textu může začínat pomlčkou, např. "-sleva".
+            open = false;
+        } else if open {
+            if let Some(field) = result.last_mut() {
+                field.content.push('\n');
+                field.content.push_str(line);
+            }
+        }
+    }
+    result
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..6)?, "%y%m%d").ok()
+}
+
+fn parse_amount(text: &str) -> Option<Money> {
+    text.replace(',', ".").parse().ok()
+}
+
+/// `:60F:C240301CZK1000,00`
+fn parse_balance(content: &str) -> Option<(StatementBalance, String)> {
+    let sign = content.get(..1)?;
+    let date = parse_date(content.get(1..7)?)?;
+    let currency = content.get(7..10)?.to_uppercase();
+    let amount = parse_amount(content.get(10..)?.trim())?;
+    let amount = if sign == "D" { -amount } else { amount };
+    Some((
+        StatementBalance {
+            amount,
+            date: Some(date.format("%Y-%m-%d").to_string()),
+        },
+        currency,
+    ))
+}
+
+/// Řádek `:61:` – valuta RRMMDD, [zaúčtování MMDD], C/D/RC/RD, [kód], částka, typ, reference.
+struct Entry {
+    value_date: NaiveDate,
+    booking_date: NaiveDate,
+    amount: Money,
+    bank_reference: Option<String>,
+    details: Option<String>,
+}
+
+fn parse_entry(content: &str) -> Option<Entry> {
+    let (first, details) = content.split_once('\n').unwrap_or((content, ""));
+    let value_date = parse_date(first)?;
+    let mut rest = first.get(6..)?;
+
+    let mut booking_date = value_date;
+    if let Some(digits) = rest
+        .get(..4)
+        .filter(|d| d.chars().all(|c| c.is_ascii_digit()))
+    {
+        let month: u32 = digits[..2].parse().ok()?;
+        let day: u32 = digits[2..].parse().ok()?;
+        // Zaúčtování může spadnout do jiného roku než valuta
+        let year = match (month, value_date.month()) {
+            (12, 1) => value_date.year() - 1,
+            (1, 12) => value_date.year() + 1,
+            _ => value_date.year(),
+        };
+        booking_date = NaiveDate::from_ymd_opt(year, month, day)?;
+        rest = &rest[4..];
+    }
+
+    let (debit, mark_len) = if rest.starts_with("RC") {
+        (true, 2)
+    } else if rest.starts_with("RD") {
+        (false, 2)
+    } else if rest.starts_with('D') {
+        (true, 1)
+    } else if rest.starts_with('C') {
+        (false, 1)
+    } else {
+        return None;
+    };
+    rest = &rest[mark_len..];
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+
+    let amount_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ','))
+        .unwrap_or(rest.len());
+    let amount = parse_amount(&rest[..amount_len])?;
+    // Typ pohybu (např. NTRF) má 4 znaky, za ním reference zákazníka a //reference banky.
+    // Jen reference banky pohyb jednoznačně určuje, zákazník ji může opakovat.
+    let bank_reference = rest
+        .get(amount_len + 4..)
+        .and_then(|references| references.split_once("//"))
+        .map(|(_, bank)| bank);
+
+    Some(Entry {
+        value_date,
+        booking_date,
+        amount: if debit { -amount } else { amount },
+        bank_reference: bank_reference.and_then(non_empty),
+        details: non_empty(details),
+    })
+}
+
+/// Doplní řádek z pole `:86:`.
+fn apply_information(row: &mut StatementRow, content: &str) {
+    let text = content.replace('\n', "");
+
+    if text.get(3..4) == Some("?") {
+        // Strukturované podpole: ?20-?29 zpráva, ?30 kód banky, ?31 účet, ?32-?33 název
+        let mut description = Vec::new();
+        let mut bank_code = None;
+        let mut account = None;
+        let mut name = Vec::new();
+        for part in text.split('?').skip(1) {
+            let (Some(code), Some(value)) = (part.get(..2), part.get(2..).and_then(non_empty))
+            else {
+                continue;
+            };
+            match code {
+                "20" | "21" | "22" | "23" | "24" | "25" | "26" | "27" | "28" | "29" => {
+                    description.push(value)
+                }
+                "30" => bank_code = Some(value),
+                "31" => account = Some(value),
+                "32" | "33" => name.push(value),
+                _ => {}
+            }
+        }
+        row.description = non_empty(&description.join(" "));
+        row.counterparty = non_empty(&name.join(" "));
+        row.counterparty_account = match (account, bank_code) {
+            (Some(account), Some(bank)) if !account.contains('/') => {
+                Some(format!("{}/{}", account, bank))
+            }
+            (account, _) => account,
+        };
+    } else {
+        row.description = non_empty(&content.replace('\n', " "));
+    }
+
+    let all = [row.description.as_deref(), row.note.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    row.variable_symbol = find_symbol(&all, "VS");
+    row.constant_symbol = find_symbol(&all, "KS");
+    row.specific_symbol = find_symbol(&all, "SS");
+}
+
+/// Rozparsuje MT940 jednoho účtu (i více denních výpisů za sebou).
+pub(crate) fn parse(text: &str) -> Result<ParsedStatement> {
+    let fields = fields(text);
+    if !fields
+        .iter()
+        .any(|f| f.tag == "61" || f.tag.starts_with("60"))
+    {
+        return Err(AppError::InvalidInput(
+            "Soubor není ve formátu MT940".to_string(),
+        ));
+    }
+
+    let mut accounts: Vec<&str> = fields
+        .iter()
+        .filter(|f| f.tag == "25")
+        .map(|f| f.content.trim())
+        .collect();
+    accounts.dedup();
+    if accounts.len() > 1 {
+        return Err(AppError::InvalidInput(
+            "Soubor obsahuje výpisy více účtů, importujte je jednotlivě".to_string(),
+        ));
+    }
+
+    let mut parsed = ParsedStatement::default();
+    let mut currency: Option<String> = None;
+    let mut last_entry: Option<usize> = None;
+
+    for field in &fields {
+        match field.tag.as_str() {
+            "60F" | "60M" => {
+                if let Some((balance, code)) = parse_balance(&field.content) {
+                    currency = Some(code);
+                    if parsed.opening_balance.is_none() {
+                        parsed.opening_balance = Some(balance);
+                    }
+                } else {
+                    parsed.error(field.line, "Neplatný počáteční zůstatek (:60F:)");
+                }
+                last_entry = None;
+            }
+            "62F" | "62M" => {
+                match parse_balance(&field.content) {
+                    Some((balance, _)) => parsed.closing_balance = Some(balance),
+                    None => parsed.error(field.line, "Neplatný konečný zůstatek (:62F:)"),
+                }
+                last_entry = None;
+            }
+            "61" => {
+                last_entry = None;
+                let Some(entry) = parse_entry(&field.content) else {
+                    parsed.error(field.line, "Neplatný řádek pohybu (:61:)");
+                    continue;
+                };
+                parsed.rows.push(StatementRow {
+                    line: field.line,
+                    date: entry.booking_date.format("%Y-%m-%d").to_string(),
+                    amount: entry.amount,
+                    currency: currency.clone(),
+                    value_date: Some(entry.value_date.format("%Y-%m-%d").to_string()),
+                    external_id: entry.bank_reference,
+                    note: entry.details,
+                    ..Default::default()
+                });
+                last_entry = Some(parsed.rows.len() - 1);
+            }
+            "86" => {
+                if let Some(row) = last_entry.and_then(|index| parsed.rows.get_mut(index)) {
+                    apply_information(row, &field.content);
+                }
+            }
+            _ => last_entry = None,
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_consecutive_daily_statements() {
+        let parsed = parse(include_str!("fixtures/statement.sta")).unwrap();
+
+        assert_eq!(parsed.rows.len(), 3);
+        let first = &parsed.rows[0];
+        assert_eq!((first.line, first.date.as_str()), (6, "2024-03-05"));
+        assert_eq!(first.amount.minor(), -125050);
+        assert_eq!(first.currency.as_deref(), Some("CZK"));
+        assert_eq!(first.external_id.as_deref(), Some("BANK-1"));
+        assert_eq!(first.note.as_deref(), Some("Platba"));
+        assert_eq!(
+            first.description.as_deref(),
+            Some("Elektrarna VS 1234567890 KS 0308")
+        );
+        assert_eq!(first.variable_symbol.as_deref(), Some("1234567890"));
+        assert_eq!(first.constant_symbol.as_deref(), Some("0308"));
+
+        // Zaúčtování v lednu k valutě z prosince
+        let salary = &parsed.rows[1];
+        assert_eq!(salary.date, "2024-01-02");
+        assert_eq!(salary.value_date.as_deref(), Some("2023-12-29"));
+        assert_eq!(salary.external_id, None);
+        assert_eq!(
+            salary.description.as_deref(),
+            Some("Mzda 12/2023 VS:202312")
+        );
+        assert_eq!(salary.counterparty.as_deref(), Some("Zamestnavatel a.s."));
+        assert_eq!(
+            salary.counterparty_account.as_deref(),
+            Some("0000000123/0100")
+        );
+        assert_eq!(salary.variable_symbol.as_deref(), Some("202312"));
+
+        // Storno odepsání (RD) je připsání
+        let reversal = &parsed.rows[2];
+        assert_eq!((reversal.line, reversal.amount.minor()), (20, 1000));
+        assert_eq!(reversal.external_id, None);
+
+        let errors: Vec<usize> = parsed.errors.iter().map(|e| e.line).collect();
+        assert_eq!(errors, [12]);
+        let opening = parsed.opening_balance.unwrap();
+        assert_eq!(opening.amount.minor(), 100000);
+        assert_eq!(opening.date.as_deref(), Some("2024-02-29"));
+        let closing = parsed.closing_balance.unwrap();
+        assert_eq!(closing.amount.minor(), 4475950);
+        assert_eq!(closing.date.as_deref(), Some("2024-04-02"));
+
+        let other = include_str!("fixtures/statement.sta").replacen(
+            ":25:2000145399/0800",
+            ":25:19-2000145399/0800",
+            1,
+        );
+        assert!(matches!(parse(&other), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn continuation_line_starting_with_dash_stays_in_field() {
+        let text = "{4:\n:25:2000145399/0800\n:60F:C240229CZK100,00\n\
+                    :61:2403050305D10,50NTRFNONREF\n:86:Nakup\n-sleva 5 %\nVS 42\n\
+                    :62F:C240331CZK89,50\n-}";
+        let parsed = parse(text).unwrap();
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(
+            parsed.rows[0].description.as_deref(),
+            Some("Nakup -sleva 5 % VS 42")
+        );
+        assert_eq!(parsed.rows[0].variable_symbol.as_deref(), Some("42"));
+        assert_eq!(parsed.closing_balance.unwrap().amount.minor(), 8950);
+    }
+
+    #[test]
+    fn message_trailer_closes_field() {
+        let text = ":60F:C240229CZK100,00\n:61:2403050305D10,50NTRFNONREF\n:86:Nakup\n\
+                    -}{5:{CHK:123456789ABC}}\ncizi text\n";
+        let parsed = parse(text).unwrap();
+        assert_eq!(parsed.rows[0].description.as_deref(), Some("Nakup"));
+    }
+
+    #[test]
+    fn only_bank_reference_identifies_entry() {
+        let text = ":25:2000145399/0800\n:61:2403050305D10,00NTRFINV1\n\
+                    :61:2403060306D10,00NTRFINV1\n:61:2403070307D10,00NTRFINV1//B7\n";
+        let parsed = parse(text).unwrap();
+        let ids: Vec<Option<&str>> = parsed
+            .rows
+            .iter()
+            .map(|r| r.external_id.as_deref())
+            .collect();
+        assert_eq!(ids, [None, None, Some("B7")]);
+    }
+}
@@ -0,0 +1,271 @@
+//! OFX 1.x (SGML, koncové značky listů chybí) i 2.x (XML).
+
+use super::{
+    decode_auto, guess_decimal_comma, non_empty, parse_amount, StatementBalance, ParsedStatement,
+    StatementRow,
+};
+use crate::utils::error::{AppError, Result};
+use chrono::NaiveDate;
+
+/// Kódování podle hlavičky OFX 1.x (`CHARSET:1250`) nebo XML prologu, jinak odhad.
+pub(crate) fn decode(bytes: &[u8]) -> String {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_uppercase();
+
+    let label = if let Some(pos) = head.find("CHARSET:") {
+        let charset: String = head[pos + 8..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        match charset.as_str() {
+            "1250" => Some("windows-1250".to_string()),
+            "1252" => Some("windows-1252".to_string()),
+            "ISO-8859-1" | "ISO-8859-2" | "UTF-8" => Some(charset.to_lowercase()),
+            _ => None,
+        }
+    } else if let Some(pos) = head.find("ENCODING=\"") {
+        head[pos + 10..].split('"').next().map(str::to_lowercase)
+    } else {
+        None
+    };
+
+    match label.and_then(|l| encoding_rs::Encoding::for_label(l.as_bytes())) {
+        Some(encoding) if encoding != encoding_rs::UTF_8 => encoding.decode(bytes).0.into_owned(),
+        _ => decode_auto(bytes),
+    }
+}
+
+struct Element<'a> {
+    name: String,
+    closing: bool,
+    text: &'a str,
+    offset: usize,
+}
+
+/// Rozloží tělo OFX na značky a text za nimi; funguje pro SGML i XML.
+fn elements(body: &str) -> Vec<Element<'_>> {
+    let mut result = Vec::new();
+    let mut rest = body;
+    let mut offset = 0;
+
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else { break };
+        let tag = &rest[start + 1..start + end];
+        let after = &rest[start + end + 1..];
+        let text_len = after.find('<').unwrap_or(after.len());
+
+        if !tag.starts_with('?') && !tag.starts_with('!') {
+            let closing = tag.starts_with('/');
+            result.push(Element {
+                name: tag.trim_start_matches('/').trim().to_uppercase(),
+                closing,
+                text: after[..text_len].trim(),
+                offset: offset + start,
+            });
+        }
+
+        let consumed = start + end + 1 + text_len;
+        offset += consumed;
+        rest = &rest[consumed..];
+    }
+    result
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// `20240305120000.000[+1:CET]` → `2024-03-05`
+fn parse_date(text: &str) -> Option<String> {
+    let digits = text.get(..8)?;
+    NaiveDate::parse_from_str(digits, "%Y%m%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+#[derive(Default)]
+struct PendingTransaction {
+    line: usize,
+    date: Option<String>,
+    amount: Option<String>,
+    fitid: Option<String>,
+    check_number: Option<String>,
+    name: Option<String>,
+    memo: Option<String>,
+}
+
+/// Rozparsuje výpis OFX s jedním bankovním nebo kartovým účtem.
+pub(crate) fn parse(text: &str) -> Result<ParsedStatement> {
+    // Hledá se přímo v původním textu: `to_uppercase` může změnit délku znaků
+    // před značkou a posunout bajtový offset.
+    let start = text
+        .as_bytes()
+        .windows(5)
+        .position(|window| window.eq_ignore_ascii_case(b"<OFX>"))
+        .ok_or_else(|| AppError::InvalidInput("Soubor není ve formátu OFX".to_string()))?;
+    let body = &text[start..];
+    let line_of = |offset: usize| text[..start + offset].matches('\n').count() + 1;
+
+    let mut parsed = ParsedStatement::default();
+    let mut currency: Option<String> = None;
+    let mut statements = 0;
+    let mut pending: Option<PendingTransaction> = None;
+    let mut in_ledger_balance = false;
+    let mut balance_amount: Option<String> = None;
+    let mut balance_date: Option<String> = None;
+    let mut pending_rows = Vec::new();
+
+    for element in elements(body) {
+        let value = non_empty(&unescape(element.text));
+
+        if element.closing {
+            match element.name.as_str() {
+                "STMTTRN" => {
+                    if let Some(transaction) = pending.take() {
+                        pending_rows.push(transaction);
+                    }
+                }
+                "LEDGERBAL" => in_ledger_balance = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match element.name.as_str() {
+            "STMTRS" | "CCSTMTRS" => statements += 1,
+            "CURDEF" => currency = value.map(|c| c.to_uppercase()),
+            "STMTTRN" => {
+                pending = Some(PendingTransaction {
+                    line: line_of(element.offset),
+                    ..Default::default()
+                })
+            }
+            "LEDGERBAL" => in_ledger_balance = true,
+            "BALAMT" if in_ledger_balance => balance_amount = value,
+            "DTASOF" if in_ledger_balance => balance_date = value,
+            name => {
+                if let Some(transaction) = pending.as_mut() {
+                    match name {
+                        "DTPOSTED" => transaction.date = value,
+                        "TRNAMT" => transaction.amount = value,
+                        "FITID" => transaction.fitid = value,
+                        "CHECKNUM" => transaction.check_number = value,
+                        "NAME" => transaction.name = value,
+                        "MEMO" => transaction.memo = value,
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    if statements > 1 {
+        return Err(AppError::InvalidInput(
+            "Soubor obsahuje výpisy více účtů, importujte je jednotlivě".to_string(),
+        ));
+    }
+
+    for transaction in pending_rows {
+        let Some(date) = transaction.date.as_deref().and_then(parse_date) else {
+            parsed.error(transaction.line, "Chybí nebo je neplatné datum (DTPOSTED)");
+            continue;
+        };
+        let Some(amount) = transaction
+            .amount
+            .as_deref()
+            .and_then(|a| parse_amount(a, guess_decimal_comma(a)))
+        else {
+            parsed.error(transaction.line, "Chybí nebo je neplatná částka (TRNAMT)");
+            continue;
+        };
+
+        parsed.rows.push(StatementRow {
+            line: transaction.line,
+            date,
+            amount,
+            currency: currency.clone(),
+            description: transaction.memo,
+            counterparty: transaction.name,
+            note: None,
+            external_id: transaction
+                .fitid
+                .or_else(|| transaction.check_number.map(|n| format!("CHECKNUM:{}", n))),
+            ..Default::default()
+        });
+    }
+
+    parsed.closing_balance = balance_amount
+        .and_then(|a| parse_amount(&a, guess_decimal_comma(&a)))
+        .map(|amount| StatementBalance {
+            amount,
+            date: balance_date.as_deref().and_then(parse_date),
+        });
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sgml_statement_in_windows_1250() {
+        let text = decode(include_bytes!("fixtures/statement_1250.ofx"));
+        let parsed = parse(&text).unwrap();
+
+        assert_eq!(parsed.rows.len(), 3);
+        let first = &parsed.rows[0];
+        assert_eq!((first.line, first.date.as_str()), (12, "2024-03-05"));
+        assert_eq!(first.amount.minor(), -125050);
+        assert_eq!(first.currency.as_deref(), Some("CZK"));
+        assert_eq!(first.counterparty.as_deref(), Some("Billa & spol."));
+        assert_eq!(first.description.as_deref(), Some("Nákup potravin"));
+        assert_eq!(first.external_id.as_deref(), Some("A1"));
+        assert_eq!(parsed.rows[1].amount.minor(), 4500000);
+        assert_eq!(parsed.rows[2].counterparty.as_deref(), Some("Šek"));
+        assert_eq!(parsed.rows[2].external_id.as_deref(), Some("CHECKNUM:1001"));
+
+        let errors: Vec<usize> = parsed.errors.iter().map(|e| e.line).collect();
+        assert_eq!(errors, [15, 16]);
+        let balance = parsed.closing_balance.unwrap();
+        assert_eq!(balance.amount.minor(), 4354950);
+        assert_eq!(balance.date.as_deref(), Some("2024-03-31"));
+    }
+
+    #[test]
+    fn xml_credit_card_statement() {
+        let text = decode(include_bytes!("fixtures/creditcard.ofx"));
+        let parsed = parse(&text).unwrap();
+
+        let amounts: Vec<i64> = parsed.rows.iter().map(|r| r.amount.minor()).collect();
+        assert_eq!(amounts, [-9990, 123400]);
+        assert_eq!(
+            parsed.rows[0].counterparty.as_deref(),
+            Some("Kavárna <U mostu>")
+        );
+        assert_eq!(parsed.rows[0].currency.as_deref(), Some("EUR"));
+        assert_eq!(parsed.rows[1].description.as_deref(), Some("Vrácení"));
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.closing_balance.unwrap().amount.minor(), 113410);
+
+        // Výpisy dvou účtů v jednom souboru se musí importovat zvlášť
+        let twice = text.replace("</CCSTMTRS>", "</CCSTMTRS><CCSTMTRS></CCSTMTRS>");
+        assert!(matches!(parse(&twice), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn finds_ofx_tag_after_text_that_changes_length_in_uppercase() {
+        // "ǰ" má v `to_uppercase` o bajt víc, offset by skončil uvnitř "ž"
+        let text = "MEMO:ǰǰǰǰǰǰ\n<ofx>žž<STMTRS><CURDEF>czk<STMTTRN><DTPOSTED>20240305\
+                    <TRNAMT>-10.50<FITID>A1</STMTTRN></STMTRS></ofx>";
+        let parsed = parse(text).unwrap();
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(parsed.rows[0].amount.minor(), -1050);
+        assert_eq!(parsed.rows[0].currency.as_deref(), Some("CZK"));
+        assert_eq!(parsed.rows[0].line, 2);
+    }
+}
@@ -0,0 +1,221 @@
+//! Quicken Interchange Format - sekce bankovních, hotovostních a kartových účtů.
+
+use super::{guess_decimal_comma, non_empty, parse_amount, ParsedStatement, StatementRow};
+use crate::utils::error::{AppError, Result};
+use chrono::NaiveDate;
+
+/// Typy sekcí s pohyby na účtu; investiční a seznamové sekce se přeskočí.
+const ACCOUNT_SECTIONS: &[&str] = &["bank", "cash", "ccard", "oth a", "oth l"];
+
+#[derive(Default)]
+struct Record {
+    line: usize,
+    date: Option<String>,
+    amount: Option<String>,
+    payee: Option<String>,
+    memo: Option<String>,
+    number: Option<String>,
+    category: Option<String>,
+}
+
+/// Datum QIF: "03/05/2024", "3/ 5'24", "05.03.2024" nebo "2024-03-05".
+/// Bez zadaného formátu se lomítka čtou americky (měsíc/den).
+fn parse_date(raw: &str, format: Option<&str>) -> Option<String> {
+    let text: String = raw
+        .trim()
+        .replace('\'', "/")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    let format = match format {
+        Some(format) => format,
+        None => {
+            let short_year = text
+                .rsplit(['/', '.'])
+                .next()
+                .is_some_and(|year| year.len() == 2);
+            if text.contains('-') {
+                "%Y-%m-%d"
+            } else if text.contains('.') {
+                if short_year { "%d.%m.%y" } else { "%d.%m.%Y" }
+            } else if short_year {
+                "%m/%d/%y"
+            } else {
+                "%m/%d/%Y"
+            }
+        }
+    };
+
+    NaiveDate::parse_from_str(&text, format)
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+fn finish(record: Record, date_format: Option<&str>, parsed: &mut ParsedStatement) {
+    // Úvodní zůstatek účtu z Quickenu není pohyb
+    let opening_balance = record
+        .payee
+        .as_deref()
+        .is_some_and(|p| p.eq_ignore_ascii_case("Opening Balance"))
+        && record.category.as_deref().is_some_and(|c| c.starts_with('['));
+    if opening_balance {
+        return;
+    }
+
+    let Some(date) = record.date.as_deref().and_then(|d| parse_date(d, date_format)) else {
+        parsed.error(record.line, "Chybí nebo je neplatné datum");
+        return;
+    };
+    let Some(amount) = record
+        .amount
+        .as_deref()
+        .and_then(|a| parse_amount(a, guess_decimal_comma(a)))
+    else {
+        parsed.error(record.line, "Chybí nebo je neplatná částka");
+        return;
+    };
+
+    parsed.rows.push(StatementRow {
+        line: record.line,
+        date,
+        amount,
+        currency: None,
+        description: record.memo,
+        counterparty: record.payee,
+        note: None,
+        // Jen číslo šeku identifikuje platbu, "ATM", "DEP" apod. se opakují
+        external_id: record
+            .number
+            .filter(|n| n.chars().all(|c| c.is_ascii_digit()))
+            .map(|n| format!("CHECKNUM:{}", n)),
+        ..Default::default()
+    });
+}
+
+/// Rozparsuje QIF soubor. `date_format` (chrono) přebije odhad formátu data.
+pub(crate) fn parse(text: &str, date_format: Option<&str>) -> Result<ParsedStatement> {
+    let mut parsed = ParsedStatement::default();
+    let mut in_account_section = false;
+    let mut seen_header = false;
+    let mut record: Option<Record> = None;
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('!') {
+            let header = header.trim().to_lowercase();
+            if let Some(kind) = header.strip_prefix("type:") {
+                seen_header = true;
+                in_account_section = ACCOUNT_SECTIONS.contains(&kind.trim());
+            } else if header == "account" {
+                seen_header = true;
+                in_account_section = false;
+            }
+            record = None;
+            continue;
+        }
+
+        if !in_account_section {
+            continue;
+        }
+
+        let current = record.get_or_insert_with(|| Record {
+            line: index + 1,
+            ..Default::default()
+        });
+        let (code, value) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        let value = non_empty(value);
+
+        match code {
+            "D" => current.date = value,
+            "T" | "U" => current.amount = value,
+            "P" => current.payee = value,
+            "M" => current.memo = value,
+            "N" => current.number = value,
+            "L" => current.category = value,
+            "^" => {
+                if let Some(done) = record.take().filter(|r| r.date.is_some() || r.amount.is_some()) {
+                    finish(done, date_format, &mut parsed);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !seen_header {
+        return Err(AppError::InvalidInput(
+            "Soubor není ve formátu QIF (chybí záhlaví !Type)".to_string(),
+        ));
+    }
+
+    // Poslední záznam bez ukončovacího "^"
+    if let Some(done) = record.filter(|r| r.date.is_some() || r.amount.is_some()) {
+        finish(done, date_format, &mut parsed);
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_account_sections_only() {
+        let parsed = parse(include_str!("fixtures/bank.qif"), None).unwrap();
+
+        let rows: Vec<(usize, &str, i64)> = parsed
+            .rows
+            .iter()
+            .map(|r| (r.line, r.date.as_str(), r.amount.minor()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (11, "2024-03-05", -123456),
+                (18, "2024-03-06", 10000),
+                (30, "2024-03-10", -9990),
+            ]
+        );
+        let albert = &parsed.rows[0];
+        assert_eq!(albert.counterparty.as_deref(), Some("Albert"));
+        assert_eq!(albert.description.as_deref(), Some("Nákup"));
+        assert_eq!(albert.external_id.as_deref(), Some("CHECKNUM:42"));
+
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line, 22);
+    }
+
+    #[test]
+    fn date_format_override_and_missing_header() {
+        let text = "!Type:Bank\nD05/03/2024\nT-1\n^\nD05.03.24\nT-2\n^\n";
+        let dates = |format| -> Vec<String> {
+            let parsed = parse(text, format).unwrap();
+            parsed.rows.into_iter().map(|r| r.date).collect()
+        };
+        assert_eq!(dates(None), ["2024-05-03", "2024-03-05"]);
+        assert_eq!(dates(Some("%d/%m/%Y")), ["2024-03-05"]);
+
+        assert!(matches!(
+            parse("D03/01/2024\nT1\n^\n", None),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn only_check_numbers_become_external_ids() {
+        let text = "!Type:Bank\nD03/05/2024\nT-500\nNATM\n^\nD03/06/2024\nT-500\nNATM\n^\n\
+                    D03/07/2024\nT-20\nNEFT\n^\nD03/08/2024\nT-80\nN1002\n^\n";
+        let parsed = parse(text, None).unwrap();
+        let ids: Vec<Option<&str>> = parsed
+            .rows
+            .iter()
+            .map(|r| r.external_id.as_deref())
+            .collect();
+        assert_eq!(ids, [None, None, None, Some("CHECKNUM:1002")]);
+    }
+}
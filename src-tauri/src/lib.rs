@@ -1,5 +1,6 @@
 mod commands;
 mod db;
+mod importers;
 mod models;
 mod utils;
 
@@ -44,6 +45,8 @@ pub fn run() {
             commands::delete_account,
             commands::get_account_balance,
             commands::set_account_balance,
+            commands::verify_ledger,
+            commands::rebuild_balances,
             // Categories
             commands::create_category,
             commands::get_categories,
@@ -57,6 +60,20 @@ pub fn run() {
             commands::update_transaction,
             commands::delete_transaction,
             commands::get_transactions_filtered,
+            // Transaction Splits
+            commands::get_transaction_splits,
+            commands::set_transaction_splits,
+            commands::create_transaction_split,
+            commands::update_transaction_split,
+            commands::delete_transaction_split,
+            // Shared Expenses
+            commands::get_shared_expense_splits,
+            commands::split_shared_expense,
+            commands::clear_shared_expense_splits,
+            commands::record_settlement,
+            commands::get_settlements,
+            commands::delete_settlement,
+            commands::get_settle_up,
             // Tags
             commands::create_tag,
             commands::get_tags,
@@ -83,6 +100,7 @@ pub fn run() {
             // Backup & Export
             commands::export_database,
             commands::import_database,
+            commands::get_schema_version,
             commands::export_transactions_csv,
             commands::export_full_backup,
             commands::save_backup_to_file,
@@ -90,7 +108,64 @@ pub fn run() {
             // Reports
             commands::get_monthly_summary,
             commands::get_category_breakdown,
+            commands::get_payee_breakdown,
             commands::get_cash_flow_data,
+            commands::get_fx_gain_loss,
+            // Exchange Rates
+            commands::set_exchange_rate,
+            commands::get_exchange_rates,
+            commands::delete_exchange_rate,
+            commands::convert_amount,
+            commands::import_cnb_rates,
+            // Statement Imports
+            commands::get_import_profiles,
+            commands::create_import_profile,
+            commands::update_import_profile,
+            commands::delete_import_profile,
+            commands::import_transactions_csv,
+            commands::import_transactions_ofx,
+            commands::import_transactions_qif,
+            commands::import_transactions_camt,
+            commands::import_transactions_mt940,
+            // Duplicates
+            commands::find_duplicate_transactions,
+            commands::merge_transactions,
+            // Categorization Rules
+            commands::get_rules,
+            commands::create_rule,
+            commands::update_rule,
+            commands::delete_rule,
+            commands::apply_rules_to_range,
+            commands::suggest_rule_from_transaction,
+            // Payees
+            commands::get_payees,
+            commands::create_payee,
+            commands::update_payee,
+            commands::delete_payee,
+            commands::assign_payees_to_range,
+            // Search
+            commands::search_transactions,
+            // Saved Views
+            commands::get_saved_views,
+            commands::create_saved_view,
+            commands::update_saved_view,
+            commands::delete_saved_view,
+            commands::run_saved_view,
+            // Reconciliation
+            commands::start_reconciliation,
+            commands::get_reconciliation,
+            commands::get_reconciliations,
+            commands::get_reconciliation_transactions,
+            commands::set_transactions_cleared,
+            commands::update_reconciliation,
+            commands::finish_reconciliation,
+            commands::cancel_reconciliation,
+            commands::unlock_transaction,
+            // Payment QR
+            commands::generate_payment_qr,
+            commands::parse_payment_qr,
+            // Schedules
+            commands::get_schedule_occurrences,
             // Household Members
             commands::get_household_members,
             commands::create_household_member,
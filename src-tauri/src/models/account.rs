@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
@@ -9,13 +10,13 @@ pub struct Account {
     pub owner_user_id: Option<i64>,
     pub account_number: Option<String>,
     pub currency: String,
-    pub initial_balance: f64,
-    pub current_balance: f64,
+    pub initial_balance: Money,
+    pub current_balance: Money,
     pub color: Option<String>,
     pub icon: Option<String>,
     pub is_premium: bool,
-    pub premium_min_flow: Option<f64>,
-    pub credit_limit: Option<f64>,
+    pub premium_min_flow: Option<Money>,
+    pub credit_limit: Option<Money>,
     pub active: bool,
     pub created_at: String,
     pub updated_at: String,
@@ -29,12 +30,12 @@ pub struct CreateAccountInput {
     pub owner_user_id: Option<i64>,
     pub account_number: Option<String>,
     pub currency: Option<String>,
-    pub initial_balance: Option<f64>,
+    pub initial_balance: Option<Money>,
     pub color: Option<String>,
     pub icon: Option<String>,
     pub is_premium: Option<bool>,
-    pub premium_min_flow: Option<f64>,
-    pub credit_limit: Option<f64>,
+    pub premium_min_flow: Option<Money>,
+    pub credit_limit: Option<Money>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -48,9 +49,9 @@ pub struct UpdateAccountInput {
     pub color: Option<String>,
     pub icon: Option<String>,
     pub is_premium: Option<bool>,
-    pub premium_min_flow: Option<f64>,
-    pub credit_limit: Option<f64>,
-    pub current_balance: Option<f64>,
+    pub premium_min_flow: Option<Money>,
+    pub credit_limit: Option<Money>,
+    pub current_balance: Option<Money>,
     pub active: bool,
 }
 
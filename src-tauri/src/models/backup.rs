@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
 use super::household::*;
 use super::bank::Bank;
 
+/// Verze formátu zálohy. Od 1.1.0 jsou ID členů domácnosti ID uživatelů
+/// (stejná, na která odkazuje `owner_user_id` účtů).
+pub const BACKUP_VERSION: &str = "1.1.0";
+
 /// Complete backup structure for export/import
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FullBackup {
@@ -40,19 +45,19 @@ pub struct AccountExtended {
     pub owner_user_id: Option<i64>,
     pub account_number: Option<String>,
     pub currency: String,
-    pub initial_balance: f64,
-    pub current_balance: f64,
+    pub initial_balance: Money,
+    pub current_balance: Money,
     pub color: Option<String>,
     pub icon: Option<String>,
     pub is_premium: bool,
-    pub premium_min_flow: Option<f64>,
+    pub premium_min_flow: Option<Money>,
     pub active: bool,
 }
 
 impl Default for FullBackup {
     fn default() -> Self {
         Self {
-            version: "1.0.0".to_string(),
+            version: BACKUP_VERSION.to_string(),
             created_at: chrono::Utc::now().to_rfc3339(),
             data: BackupData::default(),
         }
@@ -0,0 +1,11 @@
+use serde::Serialize;
+use crate::models::Transaction;
+
+/// Dvojice pravděpodobných duplicit; `score` 0–100, 100 = shodný otisk zdroje
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCandidate {
+    pub transaction: Transaction,
+    pub duplicate: Transaction,
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
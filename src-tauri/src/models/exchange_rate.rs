@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRate {
+    pub id: i64,
+    pub date: String,
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate: f64,
+    pub source: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetExchangeRateInput {
+    pub date: String,
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate: f64,
+    pub source: Option<String>,
+}
+
+/// Výsledek importu kurzovního lístku
+#[derive(Debug, Serialize)]
+pub struct RateImportResult {
+    pub date: String,
+    pub imported: usize,
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
 
 /// Typ finančního cíle/fondu
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,17 +22,18 @@ pub struct FinancialGoal {
     pub color: Option<String>,
     
     // Pro weekly_variable
-    pub weekly_amount: Option<f64>,      // Částka za týden (1150 Kč)
+    pub weekly_amount: Option<Money>,    // Částka za týden (1150 Kč)
     pub day_of_week: Option<i32>,        // Den v týdnu (0=Po, 1=Út, 2=St...)
+    pub schedule: Option<String>,        // Opakování (RRULE), výchozí týdně v `day_of_week`
     
     // Pro fund
-    pub monthly_contribution: Option<f64>, // Měsíční příspěvek (2000 Kč)
-    pub current_balance: Option<f64>,      // Aktuální zůstatek fondu
+    pub monthly_contribution: Option<Money>, // Měsíční příspěvek (2000 Kč)
+    pub current_balance: Option<Money>,    // Aktuální zůstatek fondu
     
     // Pro yearly_goal
-    pub yearly_amount: Option<f64>,       // Roční částka (10000 Kč)
+    pub yearly_amount: Option<Money>,     // Roční částka (10000 Kč)
     pub target_month: Option<i32>,        // Měsíc splátky (1-12, duben=4)
-    pub current_saved: Option<f64>,       // Kolik už mám naspořeno
+    pub current_saved: Option<Money>,     // Kolik už mám naspořeno
     
     // Společné
     pub account_id: Option<i64>,          // Z jakého účtu se platí
@@ -50,8 +52,8 @@ pub struct MonthlyPlan {
     pub month: i32,
     pub planned_count: i32,
     pub realized_count: i32,
-    pub planned_amount: f64,
-    pub realized_amount: f64,
+    pub planned_amount: Money,
+    pub realized_amount: Money,
     pub notes: Option<String>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
@@ -63,13 +65,13 @@ pub struct CreateMonthlyPlanInput {
     pub year: i32,
     pub month: i32,
     pub planned_count: Option<i32>,
-    pub planned_amount: Option<f64>,
+    pub planned_amount: Option<Money>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateMonthlyPlanInput {
     pub realized_count: i32,
-    pub realized_amount: f64,
+    pub realized_amount: Money,
     pub notes: Option<String>,
 }
 
@@ -79,13 +81,14 @@ pub struct CreateGoalInput {
     pub goal_type: String,
     pub icon: Option<String>,
     pub color: Option<String>,
-    pub weekly_amount: Option<f64>,
+    pub weekly_amount: Option<Money>,
     pub day_of_week: Option<i32>,
-    pub monthly_contribution: Option<f64>,
-    pub current_balance: Option<f64>,
-    pub yearly_amount: Option<f64>,
+    pub schedule: Option<String>,
+    pub monthly_contribution: Option<Money>,
+    pub current_balance: Option<Money>,
+    pub yearly_amount: Option<Money>,
     pub target_month: Option<i32>,
-    pub current_saved: Option<f64>,
+    pub current_saved: Option<Money>,
     pub account_id: Option<i64>,
     pub notes: Option<String>,
 }
@@ -96,13 +99,14 @@ pub struct UpdateGoalInput {
     pub goal_type: String,
     pub icon: Option<String>,
     pub color: Option<String>,
-    pub weekly_amount: Option<f64>,
+    pub weekly_amount: Option<Money>,
     pub day_of_week: Option<i32>,
-    pub monthly_contribution: Option<f64>,
-    pub current_balance: Option<f64>,
-    pub yearly_amount: Option<f64>,
+    pub schedule: Option<String>,
+    pub monthly_contribution: Option<Money>,
+    pub current_balance: Option<Money>,
+    pub yearly_amount: Option<Money>,
     pub target_month: Option<i32>,
-    pub current_saved: Option<f64>,
+    pub current_saved: Option<Money>,
     pub account_id: Option<i64>,
     pub notes: Option<String>,
     pub is_active: bool,
@@ -113,7 +117,7 @@ pub struct UpdateGoalInput {
 pub struct FundWithdrawal {
     pub id: i64,
     pub goal_id: i64,
-    pub amount: f64,
+    pub amount: Money,
     pub description: Option<String>,
     pub date: String,
     pub created_at: Option<String>,
@@ -122,7 +126,7 @@ pub struct FundWithdrawal {
 #[derive(Debug, Deserialize)]
 pub struct CreateWithdrawalInput {
     pub goal_id: i64,
-    pub amount: f64,
+    pub amount: Money,
     pub description: Option<String>,
     pub date: Option<String>,
 }
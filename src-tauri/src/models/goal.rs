@@ -1,15 +1,16 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavingsGoal {
     pub id: i64,
     pub name: String,
-    pub target_amount: f64,
-    pub current_amount: f64,
+    pub target_amount: Money,
+    pub current_amount: Money,
     pub currency: String,
     pub deadline: Option<String>,
     pub account_id: Option<i64>,
-    pub auto_deposit_amount: Option<f64>,
+    pub auto_deposit_amount: Option<Money>,
     pub auto_deposit_frequency: Option<String>,
     pub active: bool,
     pub created_at: String,
@@ -19,23 +20,23 @@ pub struct SavingsGoal {
 #[derive(Debug, Deserialize)]
 pub struct CreateSavingsGoalInput {
     pub name: String,
-    pub target_amount: f64,
+    pub target_amount: Money,
     pub currency: Option<String>,
     pub deadline: Option<String>,
     pub account_id: Option<i64>,
-    pub auto_deposit_amount: Option<f64>,
+    pub auto_deposit_amount: Option<Money>,
     pub auto_deposit_frequency: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateSavingsGoalInput {
     pub name: String,
-    pub target_amount: f64,
-    pub current_amount: f64,
+    pub target_amount: Money,
+    pub current_amount: Money,
     pub currency: String,
     pub deadline: Option<String>,
     pub account_id: Option<i64>,
-    pub auto_deposit_amount: Option<f64>,
+    pub auto_deposit_amount: Option<Money>,
     pub auto_deposit_frequency: Option<String>,
     pub active: bool,
 }
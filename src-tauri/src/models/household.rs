@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
 
+/// Osoba domácnosti - pohled na `users` (bez společného uživatele), `id` je ID uživatele
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HouseholdMember {
     pub id: Option<i64>,
@@ -13,15 +15,18 @@ pub struct HouseholdMember {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemberIncome {
     pub id: Option<i64>,
+    // ID osoby (`users.id`)
     pub member_id: i64,
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub frequency: String,
     pub day_of_month: Option<i32>,
     pub account_id: Option<i64>,
     pub is_active: bool,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    // Pravidlo opakování (RRULE); `frequency` a `day_of_month` zůstávají pro přehledy
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,7 +35,7 @@ pub struct ScheduledTransfer {
     pub name: String,
     pub from_account_id: i64,
     pub to_account_id: i64,
-    pub amount: f64,
+    pub amount: Money,
     pub day_of_month: i32,
     pub description: Option<String>,
     pub category: Option<String>,
@@ -38,13 +43,19 @@ pub struct ScheduledTransfer {
     pub is_active: bool,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    // Platební údaje převodu (příkaz do banky)
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FixedExpense {
     pub id: Option<i64>,
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub category: String,
     pub frequency: String,
     pub day_of_month: Option<i32>,
@@ -54,6 +65,7 @@ pub struct FixedExpense {
     pub notes: Option<String>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,7 +73,7 @@ pub struct BudgetCategory {
     pub id: Option<i64>,
     pub name: String,
     pub budget_type: String,
-    pub monthly_limit: f64,
+    pub monthly_limit: Money,
     pub color: String,
     pub icon: Option<String>,
     pub assigned_to: Option<String>,
@@ -81,10 +93,11 @@ pub struct CreateMemberInput {
 pub struct CreateIncomeInput {
     pub member_id: i64,
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub frequency: Option<String>,
     pub day_of_month: Option<i32>,
     pub account_id: Option<i64>,
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -92,29 +105,35 @@ pub struct CreateTransferInput {
     pub name: String,
     pub from_account_id: i64,
     pub to_account_id: i64,
-    pub amount: f64,
+    pub amount: Money,
     pub day_of_month: i32,
     pub description: Option<String>,
     pub category: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateFixedExpenseInput {
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub category: String,
     pub frequency: Option<String>,
     pub day_of_month: Option<i32>,
     pub account_id: Option<i64>,
     pub assigned_to: Option<String>,
     pub notes: Option<String>,
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateBudgetCategoryInput {
     pub name: String,
     pub budget_type: String,
-    pub monthly_limit: f64,
+    pub monthly_limit: Money,
     pub color: Option<String>,
     pub icon: Option<String>,
     pub assigned_to: Option<String>,
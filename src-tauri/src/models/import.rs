@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
+
+/// Mapování sloupců bankovního CSV výpisu. Sloupce se hledají podle názvu v záhlaví.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProfile {
+    pub id: i64,
+    pub name: String,
+    pub bank_code: Option<String>,
+    pub delimiter: String,
+    pub encoding: String,
+    pub date_format: String,
+    pub decimal_comma: bool,
+    pub date_column: String,
+    // Se zadaným `debit_column` obsahuje jen připsané částky
+    pub amount_column: String,
+    pub debit_column: Option<String>,
+    // Sloupec se směrem platby; hodnota `debit_value` znamená odchozí platbu
+    pub direction_column: Option<String>,
+    pub debit_value: Option<String>,
+    pub currency_column: Option<String>,
+    pub description_column: Option<String>,
+    pub counterparty_column: Option<String>,
+    pub note_column: Option<String>,
+    pub is_builtin: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveImportProfileInput {
+    pub name: String,
+    pub bank_code: Option<String>,
+    pub delimiter: Option<String>,
+    pub encoding: Option<String>,
+    pub date_format: Option<String>,
+    pub decimal_comma: Option<bool>,
+    pub date_column: String,
+    pub amount_column: String,
+    pub debit_column: Option<String>,
+    pub direction_column: Option<String>,
+    pub debit_value: Option<String>,
+    pub currency_column: Option<String>,
+    pub description_column: Option<String>,
+    pub counterparty_column: Option<String>,
+    pub note_column: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CsvImportInput {
+    pub path: String,
+    pub profile_id: i64,
+    pub account_id: i64,
+    pub category_id: Option<i64>,
+    pub dry_run: bool,
+}
+
+/// Import OFX/QIF/camt/MT940; `date_format` (např. "%d/%m/%Y") jen pro QIF s jiným než americkým datem
+#[derive(Debug, Deserialize)]
+pub struct StatementImportInput {
+    pub path: String,
+    pub account_id: i64,
+    pub category_id: Option<i64>,
+    pub dry_run: bool,
+    pub date_format: Option<String>,
+}
+
+/// Řádek výpisu po importu (nebo náhledu): kladná částka = příjem, záporná = výdaj
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreviewRow {
+    pub line: usize,
+    pub date: String,
+    pub amount: Money,
+    pub currency: String,
+    pub transaction_type: String,
+    pub description: Option<String>,
+    pub external_id: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    pub transaction_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// `opening_balance` a `statement_balance` jsou počáteční a konečný zůstatek z výpisu;
+/// `account_balance` je zůstatek účtu po importu (u náhledu předpokládaný);
+/// `balance_difference` = konečný zůstatek z výpisu − zůstatek účtu.
+#[derive(Debug, Serialize)]
+pub struct ImportResult {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub rows: Vec<ImportPreviewRow>,
+    pub errors: Vec<ImportRowError>,
+    pub opening_balance: Option<Money>,
+    pub opening_balance_date: Option<String>,
+    pub statement_balance: Option<Money>,
+    pub statement_balance_date: Option<String>,
+    pub account_balance: Money,
+    pub balance_difference: Option<Money>,
+}
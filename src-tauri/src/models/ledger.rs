@@ -0,0 +1,31 @@
+use serde::Serialize;
+use crate::utils::money::Money;
+
+/// Porovnání uloženého zůstatku účtu se zůstatkem spočteným z transakcí
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountBalanceCheck {
+    pub account_id: i64,
+    pub account_name: String,
+    pub currency: String,
+    pub initial_balance: Money,
+    pub stored_balance: Money,
+    pub computed_balance: Money,
+    pub difference: Money,
+}
+
+/// Záznam odkazující na neexistující řádek jiné tabulky
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedReference {
+    pub table_name: String,
+    pub row_id: i64,
+    pub column_name: String,
+    pub referenced_table: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerReport {
+    pub is_consistent: bool,
+    pub checked_accounts: usize,
+    pub discrepancies: Vec<AccountBalanceCheck>,
+    pub orphaned_references: Vec<OrphanedReference>,
+}
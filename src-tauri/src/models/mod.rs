@@ -4,12 +4,24 @@ pub mod account;
 pub mod category;
 pub mod tag;
 pub mod transaction;
+pub mod split;
 pub mod recurring;
 pub mod flow;
 pub mod goal;
 pub mod financial_goals;
 pub mod household;
+pub mod shared_expense;
 pub mod backup;
+pub mod ledger;
+pub mod exchange_rate;
+pub mod import;
+pub mod duplicate;
+pub mod rule;
+pub mod payee;
+pub mod search;
+pub mod saved_view;
+pub mod reconciliation;
+pub mod payment_qr;
 
 pub use user::*;
 pub use bank::*;
@@ -17,10 +29,22 @@ pub use account::*;
 pub use category::*;
 pub use tag::*;
 pub use transaction::*;
+pub use split::*;
 pub use recurring::*;
 pub use flow::*;
 pub use goal::*;
 pub use financial_goals::*;
 pub use household::*;
+pub use shared_expense::*;
 pub use backup::*;
+pub use ledger::*;
+pub use exchange_rate::*;
+pub use import::*;
+pub use duplicate::*;
+pub use rule::*;
+pub use payee::*;
+pub use search::*;
+pub use saved_view::*;
+pub use reconciliation::*;
+pub use payment_qr::*;
 
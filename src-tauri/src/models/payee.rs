@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Obchodník nebo protistrana, na kterého se mapují různé podoby popisu transakce
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payee {
+    pub id: i64,
+    pub name: String,
+    pub default_category_id: Option<i64>,
+    pub default_owner_user_id: Option<i64>,
+    pub aliases: Vec<PayeeAlias>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Text hledaný v popisu nebo protiúčtu (bez ohledu na velikost písmen), případně regex
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayeeAlias {
+    pub id: i64,
+    pub payee_id: i64,
+    pub pattern: String,
+    pub is_regex: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayeeAliasInput {
+    pub pattern: String,
+    pub is_regex: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavePayeeInput {
+    pub name: String,
+    pub default_category_id: Option<i64>,
+    pub default_owner_user_id: Option<i64>,
+    pub aliases: Option<Vec<PayeeAliasInput>>,
+}
@@ -0,0 +1,52 @@
+use crate::utils::money::Money;
+use serde::{Deserialize, Serialize};
+
+/// Zadání QR Platby. Se `source_type` ("scheduled_transfer", "fixed_expense",
+/// "recurring_payment") a `source_id` se údaje převezmou ze záznamu;
+/// vyplněná pole mají přednost.
+#[derive(Debug, Deserialize)]
+pub struct PaymentQrInput {
+    pub source_type: Option<String>,
+    pub source_id: Option<i64>,
+    pub account: Option<String>,
+    pub bic: Option<String>,
+    pub amount: Option<Money>,
+    pub currency: Option<String>,
+    pub due_date: Option<String>,
+    pub message: Option<String>,
+    pub recipient_name: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    /// "svg" (výchozí) nebo "png"
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaymentQr {
+    pub spayd: String,
+    pub mime_type: String,
+    /// SVG jako text, PNG jako data URL
+    pub image: String,
+}
+
+/// Platba načtená z QR kódu; pole odpovídají `CreateTransactionInput`,
+/// aby šla rovnou předvyplnit do formuláře transakce.
+#[derive(Debug, Serialize)]
+pub struct PaymentDraft {
+    pub date: String,
+    pub amount: Option<Money>,
+    pub currency: String,
+    pub transaction_type: String,
+    /// "planned" pro splatnost v budoucnu, jinak "completed"
+    pub status: String,
+    pub description: Option<String>,
+    pub counterparty_account: String,
+    pub iban: String,
+    pub bic: Option<String>,
+    pub recipient_name: Option<String>,
+    pub due_date: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+}
@@ -0,0 +1,25 @@
+use crate::utils::money::Money;
+use serde::{Deserialize, Serialize};
+
+/// Odsouhlasení účtu s výpisem. U otevřeného se `cleared_balance` počítá průběžně
+/// z odškrtnutých transakcí; dokončit lze jen při nulovém `difference`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Reconciliation {
+    pub id: i64,
+    pub account_id: i64,
+    pub statement_date: String,
+    pub statement_balance: Money,
+    pub cleared_balance: Money,
+    pub difference: Money,
+    pub cleared_count: i64,
+    pub status: String,
+    pub created_at: String,
+    pub finished_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartReconciliationInput {
+    pub account_id: i64,
+    pub statement_date: String,
+    pub statement_balance: Money,
+}
@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecurringPayment {
     pub id: i64,
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub currency: String,
     pub frequency: String,
     pub frequency_value: i32,
@@ -17,12 +18,19 @@ pub struct RecurringPayment {
     pub last_execution_date: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    // Platební údaje pro spárování s odchozí platbou z výpisu
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    /// Pravidlo opakování (RRULE, viz `utils::schedule`), podle něj se počítá `next_execution_date`
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateRecurringPaymentInput {
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub currency: Option<String>,
     pub frequency: String,
     pub frequency_value: Option<i32>,
@@ -30,12 +38,18 @@ pub struct CreateRecurringPaymentInput {
     pub account_id: i64,
     pub category_id: Option<i64>,
     pub description: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    /// Bez pravidla se odvodí z `frequency`, `frequency_value` a `day_of_period`
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateRecurringPaymentInput {
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub currency: String,
     pub frequency: String,
     pub frequency_value: i32,
@@ -44,5 +58,28 @@ pub struct UpdateRecurringPaymentInput {
     pub category_id: Option<i64>,
     pub description: Option<String>,
     pub active: bool,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    pub schedule: Option<String>,
 }
 
+/// Transakce založená zpracováním pravidelné platby.
+#[derive(Debug, Serialize)]
+pub struct RecurringOccurrence {
+    pub recurring_payment_id: i64,
+    pub name: String,
+    pub occurrence_date: String,
+    pub transaction_id: i64,
+    pub amount: Money,
+    pub currency: String,
+}
+
+/// Výsledek `process_recurring_payments`, včetně dohnaných zmeškaných termínů.
+#[derive(Debug, Serialize)]
+pub struct RecurringProcessReport {
+    pub created: Vec<RecurringOccurrence>,
+    /// Termíny, k nimž transakce už existovala (opakované spuštění)
+    pub skipped: usize,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
+
+/// Pravidlo kategorizace: všechny vyplněné podmínky musí platit, akce doplní
+/// prázdné údaje transakce (kategorii, vlastníka, tok, sdílení) a přidá štítky.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategorizationRule {
+    pub id: i64,
+    pub name: String,
+    pub priority: i64,
+    pub active: bool,
+    pub stop_processing: bool,
+    // Podmínky: regex nad popisem a poznámkou, text protistrany (účet nebo popis),
+    // účet, rozsah částky a typ transakce
+    pub match_pattern: Option<String>,
+    pub match_counterparty: Option<String>,
+    pub account_id: Option<i64>,
+    pub min_amount: Option<Money>,
+    pub max_amount: Option<Money>,
+    pub transaction_type: Option<String>,
+    // Akce
+    pub set_category_id: Option<i64>,
+    pub set_tag_ids: Vec<i64>,
+    pub set_owner_user_id: Option<i64>,
+    pub set_is_shared: Option<bool>,
+    pub set_flow_group_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Vstup pro uložení pravidla; vrací ho i návrh pravidla z transakce
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveRuleInput {
+    pub name: String,
+    pub priority: Option<i64>,
+    pub active: Option<bool>,
+    pub stop_processing: Option<bool>,
+    pub match_pattern: Option<String>,
+    pub match_counterparty: Option<String>,
+    pub account_id: Option<i64>,
+    pub min_amount: Option<Money>,
+    pub max_amount: Option<Money>,
+    pub transaction_type: Option<String>,
+    pub set_category_id: Option<i64>,
+    pub set_tag_ids: Option<Vec<i64>>,
+    pub set_owner_user_id: Option<i64>,
+    pub set_is_shared: Option<bool>,
+    pub set_flow_group_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleRunResult {
+    pub checked: usize,
+    pub updated: usize,
+}
@@ -0,0 +1,25 @@
+use crate::models::TransactionFilters;
+use serde::{Deserialize, Serialize};
+
+/// Pojmenovaná sada filtrů; `date_range` (např. "last_3_months") má přednost
+/// před pevnými daty ve `filters` a vyhodnocuje se při každém spuštění.
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedView {
+    pub id: i64,
+    pub name: String,
+    pub filters: TransactionFilters,
+    pub date_range: Option<String>,
+    pub sort_by: Option<String>,
+    pub sort_direction: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveViewInput {
+    pub name: String,
+    pub filters: Option<TransactionFilters>,
+    pub date_range: Option<String>,
+    pub sort_by: Option<String>,
+    pub sort_direction: Option<String>,
+}
@@ -0,0 +1,11 @@
+use crate::models::Transaction;
+use serde::Serialize;
+
+/// Výsledek fulltextového hledání; `snippet` má nalezené výrazy v `<mark>…</mark>`
+/// (text transakce není escapovaný), vyšší `score` = relevantnější.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionSearchHit {
+    pub transaction: Transaction,
+    pub snippet: String,
+    pub score: f64,
+}
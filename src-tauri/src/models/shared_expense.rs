@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
+
+/// Podíl uživatele na sdíleném výdaji
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedExpenseSplit {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub user_id: i64,
+    pub amount: Money,
+    pub percentage: Option<f64>,
+    pub created_at: String,
+}
+
+/// Podíl při rozdělení: `amount` pro metodu "amount", `percentage` pro "percentage",
+/// u "income_ratio" stačí `user_id`.
+#[derive(Debug, Deserialize)]
+pub struct ShareInput {
+    pub user_id: i64,
+    pub amount: Option<Money>,
+    pub percentage: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settlement {
+    pub id: i64,
+    pub from_user_id: i64,
+    pub to_user_id: i64,
+    pub amount: Money,
+    pub currency: String,
+    pub date: String,
+    pub transaction_id: Option<i64>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordSettlementInput {
+    pub from_user_id: i64,
+    pub to_user_id: i64,
+    pub amount: Money,
+    pub date: String,
+    pub from_account_id: Option<i64>,
+    pub to_account_id: Option<i64>,
+    pub note: Option<String>,
+}
+
+/// Saldo uživatele za období (kladné = ostatní mu dluží)
+#[derive(Debug, Clone, Serialize)]
+pub struct MemberBalance {
+    pub user_id: i64,
+    pub user_name: String,
+    pub paid_for_others: Money,
+    pub owed_to_others: Money,
+    pub settled_paid: Money,
+    pub settled_received: Money,
+    pub net: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Repayment {
+    pub from_user_id: i64,
+    pub from_user_name: String,
+    pub to_user_id: i64,
+    pub to_user_name: String,
+    pub amount: Money,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SettleUpReport {
+    pub currency: String,
+    pub balances: Vec<MemberBalance>,
+    pub repayments: Vec<Repayment>,
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
+
+/// Řádek rozpadu transakce do kategorie (např. položky jedné účtenky)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionSplit {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub amount: Money,
+    pub category_id: Option<i64>,
+    pub note: Option<String>,
+    pub tag_ids: Vec<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SplitLineInput {
+    pub amount: Money,
+    pub category_id: Option<i64>,
+    pub note: Option<String>,
+    pub tag_ids: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSplitInput {
+    pub transaction_id: i64,
+    pub amount: Money,
+    pub category_id: Option<i64>,
+    pub note: Option<String>,
+    pub tag_ids: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSplitInput {
+    pub amount: Money,
+    pub category_id: Option<i64>,
+    pub note: Option<String>,
+    pub tag_ids: Option<Vec<i64>>,
+}
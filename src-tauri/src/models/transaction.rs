@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::money::Money;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     pub id: i64,
     pub date: String,
-    pub amount: f64,
+    pub amount: Money,
     pub currency: String,
     pub transaction_type: String,
     pub from_account_id: Option<i64>,
@@ -14,7 +15,28 @@ pub struct Transaction {
     pub owner_user_id: Option<i64>,
     pub is_shared: bool,
     pub status: String,
+    // Převod: částka připsaná na cílový účet v jeho měně, kurz a poplatek v měně zdroje
+    pub destination_amount: Option<Money>,
+    pub destination_currency: Option<String>,
+    pub exchange_rate: Option<f64>,
+    pub fee_amount: Option<Money>,
+    // Identifikátor pohybu z bankovního výpisu (FITID apod.)
+    pub external_id: Option<String>,
+    // Platební údaje z výpisu: datum valuty, symboly a protiúčet (číslo/kód banky nebo IBAN)
+    pub value_date: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    // Otisk zdroje (banka, datum, částka, protistrana, reference) pro hledání duplicit
+    pub fingerprint: Option<String>,
+    pub payee_id: Option<i64>,
+    // Odsouhlasení s výpisem: uncleared | cleared | reconciled (uzamčená transakce)
+    pub reconciliation_status: String,
+    pub reconciliation_id: Option<i64>,
     pub recurring_payment_id: Option<i64>,
+    // Termín pravidelné platby, kterým transakce vznikla
+    pub occurrence_date: Option<String>,
     pub flow_group_id: Option<i64>,
     pub notes: Option<String>,
     pub created_at: String,
@@ -24,7 +46,7 @@ pub struct Transaction {
 #[derive(Debug, Deserialize)]
 pub struct CreateTransactionInput {
     pub date: String,
-    pub amount: f64,
+    pub amount: Money,
     pub currency: Option<String>,
     pub transaction_type: String,
     pub from_account_id: Option<i64>,
@@ -35,12 +57,27 @@ pub struct CreateTransactionInput {
     pub is_shared: Option<bool>,
     pub status: Option<String>,
     pub notes: Option<String>,
+    pub destination_amount: Option<Money>,
+    pub exchange_rate: Option<f64>,
+    pub fee_amount: Option<Money>,
+    pub external_id: Option<String>,
+    pub value_date: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+    pub payee_id: Option<i64>,
+    // Jen pro transakce vygenerované z pravidelné platby, z frontendu se nepřebírá
+    #[serde(skip)]
+    pub recurring_payment_id: Option<i64>,
+    #[serde(skip)]
+    pub occurrence_date: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateTransactionInput {
     pub date: String,
-    pub amount: f64,
+    pub amount: Money,
     pub currency: String,
     pub transaction_type: String,
     pub from_account_id: Option<i64>,
@@ -51,9 +88,32 @@ pub struct UpdateTransactionInput {
     pub is_shared: bool,
     pub status: String,
     pub notes: Option<String>,
+    pub destination_amount: Option<Money>,
+    pub exchange_rate: Option<f64>,
+    pub fee_amount: Option<Money>,
+    // Chybějící pole ponechají uloženou hodnotu, `null` ji smaže
+    #[serde(default, deserialize_with = "present")]
+    pub payee_id: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present")]
+    pub variable_symbol: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub constant_symbol: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub specific_symbol: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub counterparty_account: Option<Option<String>>,
+}
+
+/// Odliší uvedené pole (i s `null`) od chybějícího.
+fn present<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TransactionFilters {
     pub start_date: Option<String>,
     pub end_date: Option<String>,
@@ -61,11 +121,47 @@ pub struct TransactionFilters {
     pub account_ids: Option<Vec<i64>>,
     pub bank_ids: Option<Vec<i64>>,
     pub category_ids: Option<Vec<i64>>,
+    /// Filtr kategorií zahrne i jejich podkategorie
+    pub include_subcategories: Option<bool>,
     pub tag_ids: Option<Vec<i64>>,
+    /// Jen transakce bez štítku
+    pub untagged: Option<bool>,
+    pub is_shared: Option<bool>,
+    // Přesná shoda platebních údajů (symboly bez ohledu na úvodní nuly)
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
     pub types: Option<Vec<String>>,
     pub statuses: Option<Vec<String>>,
-    pub min_amount: Option<f64>,
-    pub max_amount: Option<f64>,
+    pub min_amount: Option<Money>,
+    pub max_amount: Option<Money>,
+    /// Fulltextový dotaz nad `transactions_fts` (slova, `"fráze"`, `předpona*`)
     pub search_query: Option<String>,
 }
 
+
+/// Stránka výpisu transakcí: řazení (`date`, `amount`, `description`, `category`,
+/// `payee`, `created_at`) a směr (`asc`/`desc`, výchozí `date desc`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageRequest {
+    pub sort_by: Option<String>,
+    pub sort_direction: Option<String>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Jedna stránka transakcí a souhrn za celý vyfiltrovaný výběr ve výchozí měně.
+#[derive(Debug, Serialize)]
+pub struct TransactionPage {
+    pub items: Vec<Transaction>,
+    pub offset: i64,
+    pub limit: i64,
+    pub total_count: i64,
+    pub currency: String,
+    pub total_income: Money,
+    pub total_expense: Money,
+    pub net: Money,
+    /// Chybějící kurzy; takové transakce jsou ve výpisu, ale ne v součtech
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,244 @@
+//! Relativní období ("last 3 months", "this_fiscal_year") převedená na konkrétní data.
+
+use crate::utils::error::{AppError, Result};
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+fn first_of_month(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).expect("měsíc 1-12")
+}
+
+/// Začátek měsíce posunutý o `months` (záporně do minulosti).
+fn shift_month(date: NaiveDate, months: i32) -> NaiveDate {
+    let index = date.year() * 12 + date.month0() as i32 + months;
+    first_of_month(index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+fn month_end(start: NaiveDate) -> NaiveDate {
+    shift_month(start, 1).pred_opt().expect("platné datum")
+}
+
+/// Začátek fiskálního roku, do kterého patří `today`.
+fn fiscal_year_start(today: NaiveDate, fiscal_month_start: u32) -> NaiveDate {
+    let year = if today.month() >= fiscal_month_start {
+        today.year()
+    } else {
+        today.year() - 1
+    };
+    first_of_month(year, fiscal_month_start)
+}
+
+/// Převede výraz na dvojici (od, do) včetně; mezery a podtržítka jsou zaměnitelné.
+///
+/// Podporované výrazy: `today`, `yesterday`, `this_/last_` + `week`, `month`,
+/// `quarter`, `year`, `fiscal_year`, `year_to_date` a `last_N_days|weeks|months|years`
+/// (N jednotek končících dneškem).
+pub fn resolve(
+    expression: &str,
+    today: NaiveDate,
+    fiscal_month_start: u32,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let normalized = expression
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    let invalid = || AppError::InvalidInput(format!("Neznámé období '{}'", expression));
+
+    let this_month = first_of_month(today.year(), today.month());
+    let quarter = shift_month(this_month, -(today.month0() as i32 % 3));
+    let week = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let fiscal = fiscal_year_start(today, fiscal_month_start.clamp(1, 12));
+
+    let range = match normalized.as_str() {
+        "today" => (today, today),
+        "yesterday" => {
+            let day = today.pred_opt().ok_or_else(invalid)?;
+            (day, day)
+        }
+        "this_week" => (week, week + Days::new(6)),
+        "last_week" => (week - Days::new(7), week - Days::new(1)),
+        "this_month" => (this_month, month_end(this_month)),
+        "last_month" => {
+            let start = shift_month(this_month, -1);
+            (start, month_end(start))
+        }
+        "this_quarter" => (
+            quarter,
+            shift_month(quarter, 3).pred_opt().ok_or_else(invalid)?,
+        ),
+        "last_quarter" => {
+            let start = shift_month(quarter, -3);
+            (start, quarter.pred_opt().ok_or_else(invalid)?)
+        }
+        "this_year" => (
+            first_of_month(today.year(), 1),
+            month_end(first_of_month(today.year(), 12)),
+        ),
+        "last_year" => (
+            first_of_month(today.year() - 1, 1),
+            month_end(first_of_month(today.year() - 1, 12)),
+        ),
+        "year_to_date" => (first_of_month(today.year(), 1), today),
+        "this_fiscal_year" => (
+            fiscal,
+            shift_month(fiscal, 12).pred_opt().ok_or_else(invalid)?,
+        ),
+        "last_fiscal_year" => (
+            shift_month(fiscal, -12),
+            fiscal.pred_opt().ok_or_else(invalid)?,
+        ),
+        other => {
+            let parts: Vec<&str> = other.split('_').collect();
+            let ["last", count, unit] = parts.as_slice() else {
+                return Err(invalid());
+            };
+            let count: u32 = count.parse().ok().filter(|n| *n > 0).ok_or_else(invalid)?;
+            let start = match unit.trim_end_matches('s') {
+                "day" => today.checked_sub_days(Days::new(count as u64 - 1)),
+                "week" => today.checked_sub_days(Days::new(count as u64 * 7 - 1)),
+                "month" => today
+                    .checked_sub_months(Months::new(count))
+                    .and_then(|d| d.succ_opt()),
+                "year" => count
+                    .checked_mul(12)
+                    .and_then(|months| today.checked_sub_months(Months::new(months)))
+                    .and_then(|d| d.succ_opt()),
+                _ => None,
+            };
+            (start.ok_or_else(invalid)?, today)
+        }
+    };
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn range(expression: &str, today: &str) -> (String, String) {
+        let (from, to) = resolve(expression, date(today), 7).unwrap();
+        (from.to_string(), to.to_string())
+    }
+
+    fn pair(from: &str, to: &str) -> (String, String) {
+        (from.to_string(), to.to_string())
+    }
+
+    #[test]
+    fn calendar_periods() {
+        assert_eq!(
+            range("this week", "2024-03-13"),
+            pair("2024-03-11", "2024-03-17")
+        );
+        assert_eq!(
+            range("last_week", "2024-03-13"),
+            pair("2024-03-04", "2024-03-10")
+        );
+        assert_eq!(
+            range("last month", "2024-03-31"),
+            pair("2024-02-01", "2024-02-29")
+        );
+        assert_eq!(
+            range("yesterday", "2024-03-01"),
+            pair("2024-02-29", "2024-02-29")
+        );
+        assert_eq!(
+            range("Year To Date", "2024-03-13"),
+            pair("2024-01-01", "2024-03-13")
+        );
+    }
+
+    #[test]
+    fn quarter_boundaries() {
+        assert_eq!(
+            range("this_quarter", "2024-05-15"),
+            pair("2024-04-01", "2024-06-30")
+        );
+        assert_eq!(
+            range("this_quarter", "2024-06-30"),
+            pair("2024-04-01", "2024-06-30")
+        );
+        assert_eq!(
+            range("last_quarter", "2024-05-15"),
+            pair("2024-01-01", "2024-03-31")
+        );
+        assert_eq!(
+            range("last_quarter", "2024-01-10"),
+            pair("2023-10-01", "2023-12-31")
+        );
+    }
+
+    #[test]
+    fn fiscal_year_boundaries() {
+        // Fiskální rok od července
+        assert_eq!(
+            range("this_fiscal_year", "2024-06-30"),
+            pair("2023-07-01", "2024-06-30")
+        );
+        assert_eq!(
+            range("this_fiscal_year", "2024-07-01"),
+            pair("2024-07-01", "2025-06-30")
+        );
+        assert_eq!(
+            range("last_fiscal_year", "2024-03-10"),
+            pair("2022-07-01", "2023-06-30")
+        );
+
+        let (from, to) = resolve("this_fiscal_year", date("2024-03-10"), 1).unwrap();
+        assert_eq!((from, to), (date("2024-01-01"), date("2024-12-31")));
+    }
+
+    #[test]
+    fn last_n_units_end_today() {
+        assert_eq!(
+            range("last_7_days", "2024-03-10"),
+            pair("2024-03-04", "2024-03-10")
+        );
+        assert_eq!(
+            range("last 1 day", "2024-03-10"),
+            pair("2024-03-10", "2024-03-10")
+        );
+        assert_eq!(
+            range("LAST_2_WEEKS", "2024-03-10"),
+            pair("2024-02-26", "2024-03-10")
+        );
+        assert_eq!(
+            range("last 3 months", "2024-03-10"),
+            pair("2023-12-11", "2024-03-10")
+        );
+        assert_eq!(
+            range("last 1 year", "2024-02-29"),
+            pair("2023-03-01", "2024-02-29")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_out_of_range_expressions() {
+        let today = date("2024-03-10");
+        for expression in [
+            "",
+            "next month",
+            "last 0 days",
+            "last x days",
+            "last 3 fortnights",
+            "last 400000000 years",
+            "last 4294967295 months",
+            "last 99999999999 days",
+        ] {
+            assert!(
+                matches!(
+                    resolve(expression, today, 1),
+                    Err(AppError::InvalidInput(_))
+                ),
+                "{}",
+                expression
+            );
+        }
+    }
+}
@@ -1,2 +1,11 @@
 pub mod error;
 
+pub mod money;
+
+pub mod date_range;
+
+pub mod payment;
+
+pub mod spayd;
+
+pub mod schedule;
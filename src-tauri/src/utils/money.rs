@@ -0,0 +1,354 @@
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Počet setin v jedné měnové jednotce (haléře, centy).
+///
+/// Platí pro všechny měny bez ohledu na jejich exponent podle ISO 4217:
+/// JPY (0 míst) se ukládá s nulovými setinami, BHD/KWD (3 místa) se
+/// zaokrouhlí na setiny. Aplikace takové měny nepodporuje s plnou přesností.
+pub const MINOR_UNITS: i64 = 100;
+
+/// Peněžní částka uložená přesně jako celé číslo v setinách měnové jednotky.
+///
+/// Do databáze se zapisuje jako `INTEGER`, přes IPC se serializuje jako číslo
+/// se dvěma desetinnými místy, takže frontend pracuje s částkami jako dřív.
+///
+/// Zaokrouhlovací pravidlo je všude stejné: na setiny, polovina od nuly
+/// (1,005 → 1,01; -1,005 → -1,01). Platí pro vstupy z frontendu, zálohy,
+/// import i přepočty kurzem.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn minor(self) -> i64 {
+        self.0
+    }
+
+    /// Převede číslo z frontendu/JSON na částku. Vychází z nejkratšího desetinného
+    /// zápisu čísla, takže 1.005 se zaokrouhlí na 1.01 (ne podle binární reprezentace).
+    /// Nekonečno, NaN a hodnoty mimo rozsah `i64` setin vrací chybu.
+    pub fn from_f64(value: f64) -> Result<Self, ParseMoneyError> {
+        if !value.is_finite() {
+            return Err(ParseMoneyError(value.to_string()));
+        }
+        value.to_string().parse()
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / MINOR_UNITS as f64
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Rozdělí částku v poměru vah metodou největšího zbytku, takže součet dílů
+    /// je vždy přesně původní částka. Nulový součet vah dá samé nuly.
+    pub fn allocate(self, weights: &[f64]) -> Vec<Money> {
+        let total_weight: f64 = weights.iter().sum();
+        if weights.is_empty() || total_weight <= 0.0 {
+            return vec![Money::ZERO; weights.len()];
+        }
+
+        let exact: Vec<f64> = weights
+            .iter()
+            .map(|w| self.0 as f64 * w / total_weight)
+            .collect();
+        let mut parts: Vec<i64> = exact.iter().map(|e| e.trunc() as i64).collect();
+
+        let mut remainder = self.0 - parts.iter().sum::<i64>();
+        let step = remainder.signum();
+        let mut order: Vec<usize> = (0..parts.len()).collect();
+        order.sort_by(|&a, &b| {
+            let fa = (exact[a] - exact[a].trunc()).abs();
+            let fb = (exact[b] - exact[b].trunc()).abs();
+            fb.partial_cmp(&fa).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        for index in order.into_iter().cycle() {
+            if remainder == 0 {
+                break;
+            }
+            parts[index] += step;
+            remainder -= step;
+        }
+
+        parts.into_iter().map(Money).collect()
+    }
+
+    /// Vynásobí částku koeficientem (kurz, podíl) a zaokrouhlí na setiny.
+    pub fn mul_f64(self, factor: f64) -> Self {
+        Money((self.0 as f64 * factor).round() as i64)
+    }
+}
+
+impl fmt::Display for Money {
+    /// Formát pro export: `-1234.50`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:02}",
+            sign,
+            abs / MINOR_UNITS as u64,
+            abs % MINOR_UNITS as u64
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoneyError(String);
+
+impl fmt::Display for ParseMoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Neplatná částka: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseMoneyError {}
+
+impl FromStr for Money {
+    type Err = ParseMoneyError;
+
+    /// Přijímá `1234`, `-1234.5`, `1234,56` i `+0,005`; desetinná tečka i čárka.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseMoneyError(s.to_string());
+        let trimmed = s.trim();
+
+        let (negative, digits) = match trimmed.as_bytes().first() {
+            Some(b'-') => (true, &trimmed[1..]),
+            Some(b'+') => (false, &trimmed[1..]),
+            _ => (false, trimmed),
+        };
+
+        let (whole, fraction) = match digits.find(['.', ',']) {
+            Some(pos) => (&digits[..pos], &digits[pos + 1..]),
+            None => (digits, ""),
+        };
+
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(err());
+        }
+
+        let whole_value: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| err())?
+        };
+
+        let frac_digits: Vec<i64> = fraction.bytes().map(|b| (b - b'0') as i64).collect();
+        let cents = frac_digits.first().copied().unwrap_or(0) * 10
+            + frac_digits.get(1).copied().unwrap_or(0);
+        let round_up = frac_digits.get(2).copied().unwrap_or(0) >= 5;
+
+        let minor = whole_value
+            .checked_mul(MINOR_UNITS)
+            .and_then(|v| v.checked_add(cents + round_up as i64))
+            .ok_or_else(err)?;
+
+        Ok(Money(if negative { -minor } else { minor }))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, |acc, m| acc + m)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, |acc, m| acc + *m)
+    }
+}
+
+impl ToSql for Money {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0))
+    }
+}
+
+impl FromSql for Money {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Integer(i) => Ok(Money(i)),
+            // Výsledky výrazů typu AVG() nebo násobení kurzem
+            ValueRef::Real(f) => Ok(Money(f.round() as i64)),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+struct MoneyVisitor;
+
+impl<'de> Visitor<'de> for MoneyVisitor {
+    type Value = Money;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("částka jako číslo nebo text")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+        v.checked_mul(MINOR_UNITS)
+            .map(Money)
+            .ok_or_else(|| E::custom("Částka je příliš velká"))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+        i64::try_from(v)
+            .map_err(|_| E::custom("Částka je příliš velká"))
+            .and_then(|v| self.visit_i64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Money, E> {
+        if !v.is_finite() {
+            return Err(E::custom("Částka musí být konečné číslo"));
+        }
+        Money::from_f64(v).map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+        v.parse().map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minor(text: &str) -> Result<i64, ParseMoneyError> {
+        text.parse::<Money>().map(Money::minor)
+    }
+
+    #[test]
+    fn parses_sign_separator_and_rounds_third_digit() {
+        assert_eq!(minor("1234"), Ok(123400));
+        assert_eq!(minor("-1234.5"), Ok(-123450));
+        assert_eq!(minor(" 1234,56 "), Ok(123456));
+        assert_eq!(minor("+0,005"), Ok(1));
+        assert_eq!(minor(".5"), Ok(50));
+        assert_eq!(minor("1."), Ok(100));
+        assert_eq!(minor("0.995"), Ok(100));
+        assert_eq!(minor("-1.005"), Ok(-101));
+        assert_eq!(minor("2.0049"), Ok(200));
+        assert_eq!(minor("-0.004"), Ok(0));
+
+        for text in ["", "-", ".", "1.2.3", "1 000", "1e3", "abc", "--1"] {
+            assert!(minor(text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_amounts_out_of_range() {
+        assert_eq!(minor("92233720368547758.07"), Ok(i64::MAX));
+        assert!(minor("92233720368547758.08").is_err());
+        assert!(minor("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn from_f64_rounds_decimal_notation() {
+        assert_eq!(Money::from_f64(1.005).map(Money::minor), Ok(101));
+        assert_eq!(Money::from_f64(-1.005).map(Money::minor), Ok(-101));
+        assert_eq!(Money::from_f64(0.1 + 0.2).map(Money::minor), Ok(30));
+        assert_eq!(Money::from_f64(1e-7).map(Money::minor), Ok(0));
+        assert!(Money::from_f64(f64::NAN).is_err());
+        assert!(Money::from_f64(f64::INFINITY).is_err());
+        assert!(Money::from_f64(1e20).is_err());
+    }
+
+    #[test]
+    fn allocate_preserves_sum() {
+        let parts = |amount: i64, weights: &[f64]| -> Vec<i64> {
+            Money(amount)
+                .allocate(weights)
+                .into_iter()
+                .map(Money::minor)
+                .collect()
+        };
+        assert_eq!(parts(100, &[1.0, 1.0, 1.0]), [34, 33, 33]);
+        assert_eq!(parts(-100, &[1.0, 1.0, 1.0]), [-34, -33, -33]);
+        assert_eq!(parts(4001, &[70.0, 30.0]), [2801, 1200]);
+        assert_eq!(parts(-4001, &[70.0, 30.0]), [-2801, -1200]);
+        assert_eq!(parts(100, &[0.0, 0.0]), [0, 0]);
+        assert!(parts(100, &[]).is_empty());
+
+        for amount in [-99_999, -7, 1, 1000, 123_457] {
+            let weights = [3.3, 0.0, 1.7, 5.0];
+            assert_eq!(parts(amount, &weights).iter().sum::<i64>(), amount);
+        }
+    }
+
+    #[test]
+    fn mul_f64_rounds_half_away_from_zero() {
+        assert_eq!(Money(10000).mul_f64(25.123).minor(), 251230);
+        assert_eq!(Money(1).mul_f64(0.5).minor(), 1);
+        assert_eq!(Money(-1).mul_f64(0.5).minor(), -1);
+        assert_eq!(Money(333).mul_f64(1.0 / 3.0).minor(), 111);
+        assert_eq!(Money(500).mul_f64(0.0).minor(), 0);
+    }
+
+    #[test]
+    fn displays_two_decimals() {
+        assert_eq!(Money(-5).to_string(), "-0.05");
+        assert_eq!(Money(123450).to_string(), "1234.50");
+        assert_eq!(Money::ZERO.to_string(), "0.00");
+    }
+}
@@ -0,0 +1,358 @@
+//! České platební údaje: symboly, číslo účtu "předčíslí-číslo/kód banky" a IBAN.
+
+use crate::utils::error::{AppError, Result};
+
+/// Kódy bank z číselníku ČNB (platební styk v ČR).
+pub const BANK_CODES: &[&str] = &[
+    "0100", "0300", "0600", "0710", "0800", "2010", "2060", "2070", "2100", "2200", "2220", "2250",
+    "2260", "2275", "2600", "2700", "3030", "3050", "3060", "3500", "4000", "4300", "5500", "5800",
+    "6000", "6100", "6200", "6210", "6300", "6363", "6700", "6800", "7910", "7950", "7960", "7970",
+    "7990", "8030", "8040", "8060", "8090", "8150", "8190", "8198", "8199", "8200", "8220", "8230",
+    "8240", "8250", "8255", "8265", "8270", "8280", "8293", "8299", "8500",
+];
+
+const PREFIX_WEIGHTS: [u32; 6] = [10, 5, 8, 4, 2, 1];
+const NUMBER_WEIGHTS: [u32; 10] = [6, 3, 7, 9, 10, 5, 8, 4, 2, 1];
+
+/// Kontrola modulo 11 (zprava zarovnané číslice, váhy ČNB).
+fn mod11_valid(digits: &str, weights: &[u32]) -> bool {
+    let padding = weights.len() - digits.len();
+    let sum: u32 = digits
+        .chars()
+        .zip(&weights[padding..])
+        .map(|(c, w)| c.to_digit(10).unwrap_or(0) * w)
+        .sum();
+    sum % 11 == 0
+}
+
+fn is_digits(text: &str, max_len: usize) -> bool {
+    !text.is_empty() && text.len() <= max_len && text.chars().all(|c| c.is_ascii_digit())
+}
+
+fn trim_zeros(text: &str) -> &str {
+    let trimmed = text.trim_start_matches('0');
+    if trimmed.is_empty() {
+        "0"
+    } else {
+        trimmed
+    }
+}
+
+/// Český účet ve tvaru bez úvodních nul ("19-123457/0710"), nebo chyba.
+fn czech_account(prefix: &str, number: &str, bank_code: &str, original: &str) -> Result<String> {
+    let invalid = |reason: &str| {
+        AppError::InvalidInput(format!("Neplatné číslo účtu '{}': {}", original, reason))
+    };
+
+    if !prefix.is_empty() && !is_digits(prefix, 6) {
+        return Err(invalid("předčíslí má nejvýše 6 číslic"));
+    }
+    if !is_digits(number, 10) || trim_zeros(number).len() < 2 {
+        return Err(invalid("číslo účtu má 2 až 10 číslic"));
+    }
+    if !is_digits(bank_code, 4) || bank_code.len() != 4 {
+        return Err(invalid("kód banky má 4 číslice"));
+    }
+    if !BANK_CODES.contains(&bank_code) {
+        return Err(invalid("neznámý kód banky"));
+    }
+    if !mod11_valid(prefix, &PREFIX_WEIGHTS) || !mod11_valid(number, &NUMBER_WEIGHTS) {
+        return Err(invalid("nesouhlasí kontrolní součet"));
+    }
+
+    let prefix = trim_zeros(prefix);
+    Ok(if prefix == "0" {
+        format!("{}/{}", trim_zeros(number), bank_code)
+    } else {
+        format!("{}-{}/{}", prefix, trim_zeros(number), bank_code)
+    })
+}
+
+/// Zbytek po dělení 97 pro znaky IBANu, písmena se počítají jako 10-35.
+fn mod97(chars: impl Iterator<Item = char>) -> u32 {
+    chars.fold(0u32, |acc, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 {
+            (acc * 100 + value) % 97
+        } else {
+            (acc * 10 + value) % 97
+        }
+    })
+}
+
+/// IBAN bez mezer velkými písmeny; český IBAN se převede na tuzemský tvar.
+fn iban(compact: &str, original: &str) -> Result<String> {
+    let invalid = || AppError::InvalidInput(format!("Neplatný IBAN '{}'", original));
+
+    if !(15..=34).contains(&compact.len())
+        || !compact.chars().all(|c| c.is_ascii_alphanumeric())
+        || !compact[..2].chars().all(|c| c.is_ascii_alphabetic())
+        || !compact[2..4].chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    // Kontrola modulo 97: první čtyři znaky na konec
+    if mod97(compact[4..].chars().chain(compact[..4].chars())) != 1 {
+        return Err(invalid());
+    }
+
+    if compact.starts_with("CZ") {
+        if compact.len() != 24 {
+            return Err(invalid());
+        }
+        return czech_account(&compact[8..14], &compact[14..24], &compact[4..8], original);
+    }
+    Ok(compact.to_string())
+}
+
+/// Ověří číslo účtu protistrany (tuzemské nebo IBAN) a vrátí jeho jednotný tvar.
+pub fn validate_account(account: &str) -> Result<String> {
+    let compact: String = account
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+
+    if compact.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return iban(&compact, account);
+    }
+
+    let Some((account_part, bank_code)) = compact.split_once('/') else {
+        return Err(AppError::InvalidInput(format!(
+            "Neplatné číslo účtu '{}': chybí kód banky",
+            account
+        )));
+    };
+    let (prefix, number) = account_part.split_once('-').unwrap_or(("", account_part));
+    czech_account(prefix, number, bank_code, account)
+}
+
+/// IBAN účtu; tuzemské číslo se převede na český IBAN (potřebuje ho QR Platba).
+pub fn to_iban(account: &str) -> Result<String> {
+    let normalized = validate_account(account)?;
+    let Some((account_part, bank_code)) = normalized.split_once('/') else {
+        return Ok(normalized);
+    };
+    let (prefix, number) = account_part.split_once('-').unwrap_or(("0", account_part));
+    let bban = format!("{}{:0>6}{:0>10}", bank_code, prefix, number);
+    let check = 98 - mod97(bban.chars().chain("CZ00".chars()));
+    Ok(format!("CZ{:02}{}", check, bban))
+}
+
+/// Jednotný tvar účtu pro porovnávání; neplatný údaj (např. z výpisu) ponechá, jak je.
+pub fn normalize_account(account: &str) -> String {
+    validate_account(account).unwrap_or_else(|_| account.trim().to_string())
+}
+
+/// Variabilní, konstantní nebo specifický symbol: nejvýše 10 číslic.
+pub fn validate_symbol(name: &str, value: &str) -> Result<String> {
+    let value = value.trim();
+    if !is_digits(value, 10) {
+        return Err(AppError::InvalidInput(format!(
+            "{} musí mít 1 až 10 číslic",
+            name
+        )));
+    }
+    Ok(value.to_string())
+}
+
+fn filled(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Úprava uloženého údaje: `None` ho ponechá, prázdná hodnota smaže a ověří se jen
+/// hodnota, která se od uložené liší (převzatý údaj z výpisu nemusí projít kontrolou).
+fn changed(
+    stored: Option<String>,
+    update: Option<Option<&str>>,
+    validate: impl Fn(&str) -> Result<String>,
+) -> Result<Option<String>> {
+    let Some(update) = update else {
+        return Ok(stored);
+    };
+    match filled(update) {
+        None => Ok(None),
+        Some(value) if stored.as_deref() == Some(value) => Ok(stored),
+        Some(value) => validate(value).map(Some),
+    }
+}
+
+/// Symboly a účet protistrany, jak se ukládají u transakcí a plateb.
+#[derive(Debug, Clone, Default)]
+pub struct PaymentDetails {
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+    pub counterparty_account: Option<String>,
+}
+
+impl PaymentDetails {
+    /// Ověří vyplněné údaje; prázdné řetězce bere jako nevyplněné.
+    pub fn validated(
+        variable_symbol: Option<&str>,
+        constant_symbol: Option<&str>,
+        specific_symbol: Option<&str>,
+        counterparty_account: Option<&str>,
+    ) -> Result<Self> {
+        Ok(PaymentDetails {
+            variable_symbol: filled(variable_symbol)
+                .map(|v| validate_symbol("Variabilní symbol", v))
+                .transpose()?,
+            constant_symbol: filled(constant_symbol)
+                .map(|v| validate_symbol("Konstantní symbol", v))
+                .transpose()?,
+            specific_symbol: filled(specific_symbol)
+                .map(|v| validate_symbol("Specifický symbol", v))
+                .transpose()?,
+            counterparty_account: filled(counterparty_account)
+                .map(validate_account)
+                .transpose()?,
+        })
+    }
+
+    /// Uložené údaje po úpravě: chybějící pole zůstávají, změněná se ověří.
+    pub fn updated(
+        stored: PaymentDetails,
+        variable_symbol: Option<Option<&str>>,
+        constant_symbol: Option<Option<&str>>,
+        specific_symbol: Option<Option<&str>>,
+        counterparty_account: Option<Option<&str>>,
+    ) -> Result<Self> {
+        Ok(PaymentDetails {
+            variable_symbol: changed(stored.variable_symbol, variable_symbol, |v| {
+                validate_symbol("Variabilní symbol", v)
+            })?,
+            constant_symbol: changed(stored.constant_symbol, constant_symbol, |v| {
+                validate_symbol("Konstantní symbol", v)
+            })?,
+            specific_symbol: changed(stored.specific_symbol, specific_symbol, |v| {
+                validate_symbol("Specifický symbol", v)
+            })?,
+            counterparty_account: changed(
+                stored.counterparty_account,
+                counterparty_account,
+                validate_account,
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn czech_account_checksums() {
+        assert_eq!(
+            validate_account("2000145399/0800").unwrap(),
+            "2000145399/0800"
+        );
+        assert_eq!(
+            validate_account(" 000019-2000145399/0800 ").unwrap(),
+            "19-2000145399/0800"
+        );
+        for bad in [
+            "2000145398/0800",
+            "124/0800",
+            "18-2000145399/0800",
+            "2000145399/0000",
+            "2000145399/080",
+            "1234567-2000145399/0800",
+            "2000145399",
+            "9/0800",
+        ] {
+            assert!(validate_account(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn iban_mod97() {
+        assert_eq!(
+            validate_account("cz65 0800 0000 1920 0014 5399").unwrap(),
+            "19-2000145399/0800"
+        );
+        assert_eq!(
+            validate_account("DE89 3704 0044 0532 0130 00").unwrap(),
+            "DE89370400440532013000"
+        );
+        for bad in [
+            "CZ6608000000192000145399",
+            "CZ650800000019200014539",
+            "DE89370400440532013001",
+            "1289370400440532013000",
+        ] {
+            assert!(validate_account(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn to_iban_round_trip() {
+        for (account, iban) in [
+            ("2000145399/0800", "CZ7908000000002000145399"),
+            ("19-2000145399/0800", "CZ6508000000192000145399"),
+        ] {
+            assert_eq!(to_iban(account).unwrap(), iban);
+            assert_eq!(validate_account(iban).unwrap(), account);
+        }
+        assert_eq!(
+            to_iban("DE89370400440532013000").unwrap(),
+            "DE89370400440532013000"
+        );
+        assert!(to_iban("2000145398/0800").is_err());
+    }
+
+    #[test]
+    fn symbols_and_validated_details() {
+        assert_eq!(validate_symbol("VS", " 0308 ").unwrap(), "0308");
+        assert!(validate_symbol("VS", "12345678901").is_err());
+        assert!(validate_symbol("VS", "12a").is_err());
+
+        let details = PaymentDetails::validated(
+            Some(" "),
+            Some("0308"),
+            None,
+            Some("000019-2000145399/0800"),
+        )
+        .unwrap();
+        assert_eq!(details.variable_symbol, None);
+        assert_eq!(details.constant_symbol.as_deref(), Some("0308"));
+        assert_eq!(
+            details.counterparty_account.as_deref(),
+            Some("19-2000145399/0800")
+        );
+        assert!(PaymentDetails::validated(None, None, Some("1x"), None).is_err());
+    }
+
+    #[test]
+    fn updates_validate_only_changed_values() {
+        // Údaj převzatý z výpisu se při nezměněné hodnotě znovu neověřuje
+        let stored = PaymentDetails {
+            variable_symbol: Some("123".to_string()),
+            constant_symbol: Some("0308".to_string()),
+            specific_symbol: Some("9".to_string()),
+            counterparty_account: Some("neplatný účet".to_string()),
+        };
+        let updated = PaymentDetails::updated(
+            stored,
+            None,
+            Some(None),
+            Some(Some(" ")),
+            Some(Some("neplatný účet")),
+        )
+        .unwrap();
+        assert_eq!(updated.variable_symbol.as_deref(), Some("123"));
+        assert_eq!(updated.constant_symbol, None);
+        assert_eq!(updated.specific_symbol, None);
+        assert_eq!(
+            updated.counterparty_account.as_deref(),
+            Some("neplatný účet")
+        );
+
+        let changed = PaymentDetails::updated(updated.clone(), Some(Some("12a")), None, None, None);
+        assert!(changed.is_err());
+        assert!(
+            PaymentDetails::updated(updated, None, None, None, Some(Some("124/0800"))).is_err()
+        );
+    }
+}
@@ -0,0 +1,722 @@
+//! Opakování ve stylu RRULE (RFC 5545), společné pro pravidelné platby, příjmy,
+//! fixní výdaje, převody a cíle: "DTSTART=20240131;FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=12".
+//!
+//! Oproti RFC se den za koncem měsíce (BYMONTHDAY=31, 31. den ze začátku) posune
+//! na poslední den měsíce místo vynechání výskytu; týden začíná pondělím a pořadí
+//! dne v týdnu ("2TU", "-1FR") se počítá vždy v rámci měsíce.
+
+use crate::utils::error::{AppError, Result};
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Nejvýše tolik období po sobě bez výskytu, pak se iterace ukončí (např. "5FR" s BYMONTH).
+const MAX_EMPTY_PERIODS: u32 = 1000;
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon),
+    ("TU", Weekday::Tue),
+    ("WE", Weekday::Wed),
+    ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri),
+    ("SA", Weekday::Sat),
+    ("SU", Weekday::Sun),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn as_str(self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+/// Den v týdnu, případně s pořadím v měsíci (2 = druhý, -1 = poslední).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayRule {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    pub start: NaiveDate,
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_day: Vec<WeekdayRule>,
+    /// 1 až 31, záporně od konce měsíce (-1 = poslední den)
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub until: Option<NaiveDate>,
+    pub count: Option<u32>,
+}
+
+fn invalid(text: &str, reason: &str) -> AppError {
+    AppError::InvalidInput(format!("Neplatné opakování '{}': {}", text, reason))
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    // UNTIL může mít i čas ("20241231T235959Z"), bere se jen datum
+    let digits: String = value.chars().filter(|c| *c != '-').take(8).collect();
+    NaiveDate::parse_from_str(&digits, "%Y%m%d").ok()
+}
+
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    value.split(',').map(|item| parse(item.trim())).collect()
+}
+
+fn parse_weekday_rule(value: &str) -> Option<WeekdayRule> {
+    let split = value.len().checked_sub(2)?;
+    let (ordinal, code) = (value.get(..split)?, value.get(split..)?);
+    let weekday = WEEKDAYS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(code))?
+        .1;
+    let ordinal = match ordinal.trim_start_matches('+') {
+        "" => None,
+        number => Some(
+            number
+                .parse::<i32>()
+                .ok()
+                .filter(|n| (1..=5).contains(&n.abs()))?,
+        ),
+    };
+    Some(WeekdayRule { ordinal, weekday })
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("první den měsíce")
+}
+
+fn last_of_month(first: NaiveDate) -> NaiveDate {
+    first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(first)
+}
+
+/// Den měsíce podle pravidla; mimo rozsah měsíce se zarovná na první/poslední den.
+fn month_day(first: NaiveDate, last: NaiveDate, day: i32) -> NaiveDate {
+    let days = last.day() as i32;
+    let day = if day > 0 {
+        day.min(days)
+    } else {
+        (days + 1 + day).max(1)
+    };
+    first.with_day(day as u32).unwrap_or(last)
+}
+
+fn weekday_dates(first: NaiveDate, last: NaiveDate, rule: &WeekdayRule) -> Vec<NaiveDate> {
+    let all: Vec<NaiveDate> = first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .filter(|date| date.weekday() == rule.weekday)
+        .collect();
+    match rule.ordinal {
+        None => all,
+        Some(n) if n > 0 => all.get(n as usize - 1).copied().into_iter().collect(),
+        Some(n) => all
+            .len()
+            .checked_sub(n.unsigned_abs() as usize)
+            .map(|index| all[index])
+            .into_iter()
+            .collect(),
+    }
+}
+
+impl Schedule {
+    /// Načte pravidlo; bez DTSTART začíná opakování dnem `default_start`.
+    /// Přijímá i předponu "RRULE:" a UNTIL/DTSTART ve tvaru YYYY-MM-DD.
+    pub fn parse(text: &str, default_start: NaiveDate) -> Result<Self> {
+        let rule = text.trim();
+        let rule = rule
+            .get(..6)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("RRULE:"))
+            .map_or(rule, |_| &rule[6..]);
+
+        let mut schedule = Schedule {
+            start: default_start,
+            frequency: Frequency::Monthly,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            until: None,
+            count: None,
+        };
+        let mut frequency = None;
+        for part in rule.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(text, &format!("chybí hodnota u '{}'", part)))?;
+            let bad_value = || invalid(text, &format!("neplatná hodnota {}", part));
+            match key.trim().to_uppercase().as_str() {
+                "DTSTART" => schedule.start = parse_date(value).ok_or_else(bad_value)?,
+                "FREQ" => {
+                    frequency = Some(match value.to_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(bad_value()),
+                    })
+                }
+                "INTERVAL" => {
+                    schedule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=1000).contains(n))
+                        .ok_or_else(bad_value)?
+                }
+                "BYDAY" => {
+                    schedule.by_day = parse_list(value, parse_weekday_rule).ok_or_else(bad_value)?
+                }
+                "BYMONTHDAY" => {
+                    schedule.by_month_day = parse_list(value, |v| {
+                        v.parse::<i32>()
+                            .ok()
+                            .filter(|d| *d != 0 && (-31..=31).contains(d))
+                    })
+                    .ok_or_else(bad_value)?
+                }
+                "BYMONTH" => {
+                    schedule.by_month = parse_list(value, |v| {
+                        v.parse::<u32>().ok().filter(|m| (1..=12).contains(m))
+                    })
+                    .ok_or_else(bad_value)?
+                }
+                "UNTIL" => schedule.until = Some(parse_date(value).ok_or_else(bad_value)?),
+                "COUNT" => {
+                    schedule.count = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|n| *n > 0)
+                            .ok_or_else(bad_value)?,
+                    )
+                }
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                other => return Err(invalid(text, &format!("nepodporovaná část '{}'", other))),
+            }
+        }
+
+        schedule.frequency = frequency.ok_or_else(|| invalid(text, "chybí FREQ"))?;
+        if schedule.until.is_some() && schedule.count.is_some() {
+            return Err(invalid(text, "UNTIL a COUNT nelze kombinovat"));
+        }
+        if schedule.until.is_some_and(|until| until < schedule.start) {
+            return Err(invalid(text, "UNTIL je před začátkem"));
+        }
+        if schedule.by_day.iter().any(|rule| rule.ordinal.is_some())
+            && !matches!(schedule.frequency, Frequency::Monthly | Frequency::Yearly)
+        {
+            return Err(invalid(
+                text,
+                "pořadí dne (např. 2TU) jen u MONTHLY a YEARLY",
+            ));
+        }
+        schedule.by_month.sort_unstable();
+        schedule.by_month.dedup();
+        Ok(schedule)
+    }
+
+    /// Převod starších polí (`frequency`, počet period, den) na pravidlo.
+    pub fn legacy(frequency: &str, interval: i32, day: Option<i32>, start: NaiveDate) -> Self {
+        let interval = interval.max(1) as u32;
+        let (frequency, interval) = match frequency {
+            "daily" => (Frequency::Daily, interval),
+            "weekly" => (Frequency::Weekly, interval),
+            "biweekly" => (Frequency::Weekly, interval * 2),
+            "quarterly" => (Frequency::Monthly, interval * 3),
+            "yearly" => (Frequency::Yearly, interval),
+            "monthly" => (Frequency::Monthly, interval),
+            // Neznámá frekvence se dřív brala jako počet dní
+            _ => (Frequency::Daily, interval),
+        };
+        let by_month_day = match (frequency, day) {
+            (Frequency::Monthly | Frequency::Yearly, Some(day)) if (1..=31).contains(&day) => {
+                vec![day]
+            }
+            _ => Vec::new(),
+        };
+        Schedule {
+            start,
+            frequency,
+            interval,
+            by_day: Vec::new(),
+            by_month_day,
+            by_month: Vec::new(),
+            until: None,
+            count: None,
+        }
+    }
+
+    /// Každý týden v daný den (0 = pondělí, jako `financial_goals.day_of_week`).
+    pub fn weekly_on(day_of_week: i32, start: NaiveDate) -> Self {
+        Schedule {
+            by_day: vec![WeekdayRule {
+                ordinal: None,
+                weekday: WEEKDAYS[day_of_week.clamp(0, 6) as usize].1,
+            }],
+            ..Schedule::legacy("weekly", 1, None, start)
+        }
+    }
+
+    /// Uložené pravidlo, nebo převod ze starších polí, když pravidlo chybí.
+    pub fn stored_or_legacy(
+        stored: Option<&str>,
+        frequency: &str,
+        interval: i32,
+        day: Option<i32>,
+        start: NaiveDate,
+    ) -> Result<Self> {
+        match stored.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(rule) => Schedule::parse(rule, start),
+            None => Ok(Schedule::legacy(frequency, interval, day, start)),
+        }
+    }
+
+    pub fn occurrences(&self) -> Occurrences<'_> {
+        Occurrences {
+            schedule: self,
+            period: 0,
+            pending: VecDeque::new(),
+            emitted: 0,
+            empty_periods: 0,
+            done: false,
+        }
+    }
+
+    /// První výskyt po `date` (bez něj).
+    pub fn next_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        self.occurrences().find(|occurrence| *occurrence > date)
+    }
+
+    /// První výskyt v den `date` nebo později.
+    pub fn next_on_or_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        self.occurrences().find(|occurrence| *occurrence >= date)
+    }
+
+    /// Výskyty v období od–do včetně.
+    pub fn between(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        self.occurrences()
+            .skip_while(|occurrence| *occurrence < from)
+            .take_while(|occurrence| *occurrence <= to)
+            .collect()
+    }
+
+    /// Průměrný počet výskytů za měsíc (za čtyři roky od začátku, bez UNTIL/COUNT).
+    pub fn monthly_factor(&self) -> f64 {
+        let unbounded = Schedule {
+            until: None,
+            count: None,
+            ..self.clone()
+        };
+        let end = self
+            .start
+            .checked_add_months(Months::new(48))
+            .unwrap_or(NaiveDate::MAX);
+        unbounded
+            .occurrences()
+            .take_while(|occurrence| *occurrence < end)
+            .count() as f64
+            / 48.0
+    }
+
+    /// Výskyty v měsíci začínajícím dnem `first` podle BYDAY a BYMONTHDAY.
+    fn month_dates(&self, first: NaiveDate) -> Vec<NaiveDate> {
+        let last = last_of_month(first);
+        let by_day: Vec<NaiveDate> = self
+            .by_day
+            .iter()
+            .flat_map(|rule| weekday_dates(first, last, rule))
+            .collect();
+        let by_month_day: Vec<NaiveDate> = self
+            .by_month_day
+            .iter()
+            .map(|day| month_day(first, last, *day))
+            .collect();
+        match (self.by_day.is_empty(), self.by_month_day.is_empty()) {
+            (true, true) => vec![month_day(first, last, self.start.day() as i32)],
+            (false, true) => by_day,
+            (true, false) => by_month_day,
+            (false, false) => by_day
+                .into_iter()
+                .filter(|date| by_month_day.contains(date))
+                .collect(),
+        }
+    }
+
+    /// Kandidáti v `index`-tém období (den/týden/měsíc/rok) od začátku.
+    fn period_dates(&self, index: u32) -> Option<Vec<NaiveDate>> {
+        let step = index.checked_mul(self.interval)?;
+        let mut dates = match self.frequency {
+            Frequency::Daily => vec![self.start.checked_add_days(Days::new(step as u64))?],
+            Frequency::Weekly => {
+                let monday = self
+                    .start
+                    .checked_sub_days(
+                        Days::new(self.start.weekday().num_days_from_monday() as u64),
+                    )?
+                    .checked_add_days(Days::new(step as u64 * 7))?;
+                if self.by_day.is_empty() {
+                    vec![monday + Days::new(self.start.weekday().num_days_from_monday() as u64)]
+                } else {
+                    self.by_day
+                        .iter()
+                        .map(|rule| monday + Days::new(rule.weekday.num_days_from_monday() as u64))
+                        .collect()
+                }
+            }
+            Frequency::Monthly => {
+                let first = first_of_month(self.start).checked_add_months(Months::new(step))?;
+                self.month_dates(first)
+            }
+            Frequency::Yearly => {
+                let year = self.start.year().checked_add(i32::try_from(step).ok()?)?;
+                let months = if self.by_month.is_empty() {
+                    vec![self.start.month()]
+                } else {
+                    self.by_month.clone()
+                };
+                let mut dates = Vec::new();
+                for month in months {
+                    dates.extend(self.month_dates(NaiveDate::from_ymd_opt(year, month, 1)?));
+                }
+                dates
+            }
+        };
+
+        // U kratších frekvencí BY* pravidla výskyty jen omezují
+        if !self.by_month.is_empty() {
+            dates.retain(|date| self.by_month.contains(&date.month()));
+        }
+        if self.frequency == Frequency::Daily && !self.by_day.is_empty() {
+            dates.retain(|date| {
+                self.by_day
+                    .iter()
+                    .any(|rule| rule.weekday == date.weekday())
+            });
+        }
+        if matches!(self.frequency, Frequency::Daily | Frequency::Weekly)
+            && !self.by_month_day.is_empty()
+        {
+            dates.retain(|date| {
+                let first = first_of_month(*date);
+                let last = last_of_month(first);
+                self.by_month_day
+                    .iter()
+                    .any(|day| month_day(first, last, *day) == *date)
+            });
+        }
+        dates.sort_unstable();
+        dates.dedup();
+        Some(dates)
+    }
+}
+
+impl fmt::Display for Schedule {
+    /// Jednotný zápis, jak se ukládá do sloupce `schedule`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "DTSTART={};FREQ={}",
+            self.start.format("%Y%m%d"),
+            self.frequency.as_str()
+        )?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|rule| {
+                    let code = WEEKDAYS
+                        .iter()
+                        .find(|(_, weekday)| *weekday == rule.weekday)
+                        .map_or("MO", |(name, _)| *name);
+                    match rule.ordinal {
+                        Some(n) => format!("{}{}", n, code),
+                        None => code.to_string(),
+                    }
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(i32::to_string).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if !self.by_month.is_empty() {
+            let months: Vec<String> = self.by_month.iter().map(u32::to_string).collect();
+            write!(f, ";BYMONTH={}", months.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Výskyty vzestupně od začátku opakování, s ohledem na UNTIL a COUNT.
+pub struct Occurrences<'a> {
+    schedule: &'a Schedule,
+    period: u32,
+    pending: VecDeque<NaiveDate>,
+    emitted: u32,
+    empty_periods: u32,
+    done: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<NaiveDate> {
+        loop {
+            if self.done
+                || self
+                    .schedule
+                    .count
+                    .is_some_and(|count| self.emitted >= count)
+            {
+                return None;
+            }
+            if let Some(date) = self.pending.pop_front() {
+                if self.schedule.until.is_some_and(|until| date > until) {
+                    self.done = true;
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(date);
+            }
+
+            let Some(dates) = self.schedule.period_dates(self.period) else {
+                self.done = true;
+                return None;
+            };
+            self.period += 1;
+            self.pending.extend(
+                dates
+                    .into_iter()
+                    .filter(|date| *date >= self.schedule.start),
+            );
+            if self.pending.is_empty() {
+                self.empty_periods += 1;
+                self.done = self.empty_periods >= MAX_EMPTY_PERIODS;
+            } else {
+                self.empty_periods = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn first(rule: &str, n: usize) -> Vec<String> {
+        Schedule::parse(rule, date("2024-01-01"))
+            .unwrap()
+            .occurrences()
+            .take(n)
+            .map(|d| d.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn month_end_days_clamp_across_february() {
+        assert_eq!(
+            first("DTSTART=20240131;FREQ=MONTHLY;BYMONTHDAY=-1", 4),
+            ["2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30"]
+        );
+        assert_eq!(
+            first("DTSTART=20230131;FREQ=MONTHLY;BYMONTHDAY=-1", 2),
+            ["2023-01-31", "2023-02-28"]
+        );
+        assert_eq!(
+            first("DTSTART=20231231;FREQ=MONTHLY;BYMONTHDAY=31", 5),
+            [
+                "2023-12-31",
+                "2024-01-31",
+                "2024-02-29",
+                "2024-03-31",
+                "2024-04-30"
+            ]
+        );
+        // Bez BYMONTHDAY se drží den začátku, nezůstane na 29.
+        assert_eq!(
+            first("DTSTART=20240131;FREQ=MONTHLY", 3),
+            ["2024-01-31", "2024-02-29", "2024-03-31"]
+        );
+        assert_eq!(
+            first("DTSTART=20240229;FREQ=YEARLY", 5),
+            [
+                "2024-02-29",
+                "2025-02-28",
+                "2026-02-28",
+                "2027-02-28",
+                "2028-02-29"
+            ]
+        );
+    }
+
+    #[test]
+    fn nth_and_last_weekday_of_month() {
+        assert_eq!(
+            first("FREQ=MONTHLY;BYDAY=2TU", 3),
+            ["2024-01-09", "2024-02-13", "2024-03-12"]
+        );
+        assert_eq!(
+            first("FREQ=MONTHLY;BYDAY=-1FR", 3),
+            ["2024-01-26", "2024-02-23", "2024-03-29"]
+        );
+        // Měsíce bez pátého pátku se přeskočí
+        assert_eq!(
+            first("FREQ=MONTHLY;BYDAY=5FR", 3),
+            ["2024-03-29", "2024-05-31", "2024-08-30"]
+        );
+        assert!(Schedule::parse("FREQ=WEEKLY;BYDAY=2TU", date("2024-01-01")).is_err());
+    }
+
+    #[test]
+    fn interval_count_and_until() {
+        assert_eq!(
+            first("DTSTART=20240115;FREQ=MONTHLY;INTERVAL=2", 3),
+            ["2024-01-15", "2024-03-15", "2024-05-15"]
+        );
+        assert_eq!(
+            first("DTSTART=20240115;FREQ=MONTHLY;COUNT=3", 10),
+            ["2024-01-15", "2024-02-15", "2024-03-15"]
+        );
+        assert_eq!(
+            first("DTSTART=20240115;FREQ=MONTHLY;UNTIL=20240315T235959Z", 10),
+            ["2024-01-15", "2024-02-15", "2024-03-15"]
+        );
+
+        let schedule =
+            Schedule::parse("FREQ=DAILY;INTERVAL=10;COUNT=4", date("2024-01-01")).unwrap();
+        assert_eq!(
+            schedule.next_after(date("2024-01-11")),
+            Some(date("2024-01-21"))
+        );
+        assert_eq!(
+            schedule.next_on_or_after(date("2024-01-11")),
+            Some(date("2024-01-11"))
+        );
+        assert_eq!(schedule.next_after(date("2024-01-31")), None);
+        assert_eq!(
+            schedule.between(date("2024-01-05"), date("2024-01-21")),
+            [date("2024-01-11"), date("2024-01-21")]
+        );
+
+        for bad in [
+            "FREQ=MONTHLY;COUNT=2;UNTIL=20250101",
+            "DTSTART=20240201;FREQ=MONTHLY;UNTIL=20240101",
+            "FREQ=MONTHLY;INTERVAL=0",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=HOURLY",
+            "BYMONTHDAY=1",
+        ] {
+            assert!(Schedule::parse(bad, date("2024-01-01")).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn weekly_by_day_skips_days_before_start() {
+        // Začátek ve středu: pondělí téhož týdne se vynechá
+        assert_eq!(
+            first("DTSTART=20240103;FREQ=WEEKLY;BYDAY=MO,FR", 3),
+            ["2024-01-05", "2024-01-08", "2024-01-12"]
+        );
+        assert_eq!(
+            first("DTSTART=20240103;FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR", 3),
+            ["2024-01-05", "2024-01-15", "2024-01-19"]
+        );
+        let friday = Schedule::weekly_on(4, date("2024-01-03"));
+        assert_eq!(
+            friday.next_on_or_after(date("2024-01-03")),
+            Some(date("2024-01-05"))
+        );
+    }
+
+    #[test]
+    fn legacy_fields() {
+        let start = date("2024-01-31");
+        let biweekly = Schedule::legacy("biweekly", 1, None, start);
+        assert_eq!(
+            (biweekly.frequency, biweekly.interval),
+            (Frequency::Weekly, 2)
+        );
+        assert_eq!(
+            Schedule::legacy("quarterly", 1, Some(31), start).between(start, date("2024-12-31")),
+            [
+                date("2024-01-31"),
+                date("2024-04-30"),
+                date("2024-07-31"),
+                date("2024-10-31")
+            ]
+        );
+        let unknown = Schedule::legacy("every", 3, Some(5), start);
+        assert_eq!((unknown.frequency, unknown.interval), (Frequency::Daily, 3));
+        assert!(unknown.by_month_day.is_empty());
+        assert!(Schedule::legacy("monthly", 1, Some(40), start)
+            .by_month_day
+            .is_empty());
+
+        let stored = Schedule::stored_or_legacy(Some(" "), "yearly", 1, None, start).unwrap();
+        assert_eq!(stored.to_string(), "DTSTART=20240131;FREQ=YEARLY");
+    }
+
+    #[test]
+    fn monthly_factor_ignores_limits() {
+        let factor = |rule: &str| {
+            Schedule::parse(rule, date("2024-01-01"))
+                .unwrap()
+                .monthly_factor()
+        };
+        assert_eq!(factor("FREQ=MONTHLY;COUNT=2"), 1.0);
+        assert_eq!(factor("FREQ=MONTHLY;INTERVAL=3"), 16.0 / 48.0);
+        assert_eq!(factor("FREQ=YEARLY"), 4.0 / 48.0);
+        assert_eq!(factor("FREQ=WEEKLY"), 209.0 / 48.0);
+        assert_eq!(factor("FREQ=WEEKLY;INTERVAL=2"), 105.0 / 48.0);
+        assert_eq!(factor("FREQ=DAILY"), 1461.0 / 48.0);
+        assert_eq!(factor("FREQ=MONTHLY;BYDAY=MO,TH"), 418.0 / 48.0);
+    }
+
+    #[test]
+    fn display_is_canonical() {
+        let schedule = Schedule::parse(
+            "rrule:freq=monthly;interval=2;byday=-1fr;until=2024-12-31",
+            date("2024-01-01"),
+        )
+        .unwrap();
+        let text = schedule.to_string();
+        assert_eq!(
+            text,
+            "DTSTART=20240101;FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR;UNTIL=20241231"
+        );
+        assert_eq!(
+            Schedule::parse(&text, date("2000-01-01")).unwrap(),
+            schedule
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! QR Platba: řetězec Short Payment Descriptor ("SPD*1.0*ACC:CZ...*AM:450.00*CC:CZK").
+
+use crate::utils::error::{AppError, Result};
+use crate::utils::money::Money;
+use crate::utils::payment::{to_iban, validate_symbol};
+use chrono::NaiveDate;
+
+const HEADER: &str = "SPD*1.0*";
+/// AM má nejvýše 10 znaků, tj. 9999999.99
+const MAX_AMOUNT_MINOR: i64 = 999_999_999;
+const MAX_MESSAGE: usize = 60;
+const MAX_RECIPIENT: usize = 35;
+
+/// Údaje jedné QR Platby; účet je vždy IBAN.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Spayd {
+    pub iban: String,
+    pub bic: Option<String>,
+    pub amount: Option<Money>,
+    pub currency: Option<String>,
+    pub due_date: Option<NaiveDate>,
+    pub message: Option<String>,
+    pub recipient_name: Option<String>,
+    pub variable_symbol: Option<String>,
+    pub constant_symbol: Option<String>,
+    pub specific_symbol: Option<String>,
+}
+
+fn invalid(reason: impl std::fmt::Display) -> AppError {
+    AppError::InvalidInput(format!("Neplatná QR Platba: {}", reason))
+}
+
+/// Hvězdička odděluje pole, v hodnotě se proto zapisuje jako %2A; procento
+/// se kóduje jako první (%25), aby se text "%2A" při čtení nezměnil na "*".
+fn escape(value: &str, max_chars: usize) -> String {
+    value
+        .trim()
+        .chars()
+        .take(max_chars)
+        .collect::<String>()
+        .replace('%', "%25")
+        .replace('*', "%2A")
+}
+
+/// Dekóduje sekvence %XX (UTF-8); ostatní znaky ponechá.
+fn unescape(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn check_amount(amount: Money) -> Result<Money> {
+    if !(1..=MAX_AMOUNT_MINOR).contains(&amount.minor()) {
+        return Err(invalid("částka musí být mezi 0.01 a 9999999.99"));
+    }
+    Ok(amount)
+}
+
+fn check_currency(currency: &str) -> Result<String> {
+    let currency = currency.trim().to_uppercase();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(invalid(format!("neplatná měna '{}'", currency)));
+    }
+    Ok(currency)
+}
+
+impl Spayd {
+    /// Řetězec pro QR kód; ověří částku, měnu a symboly, delší texty zkrátí.
+    pub fn encode(&self) -> Result<String> {
+        let mut fields = vec![match &self.bic {
+            Some(bic) => format!("ACC:{}+{}", self.iban, bic.trim().to_uppercase()),
+            None => format!("ACC:{}", self.iban),
+        }];
+        if let Some(amount) = self.amount {
+            fields.push(format!("AM:{}", check_amount(amount)?));
+        }
+        if let Some(currency) = &self.currency {
+            fields.push(format!("CC:{}", check_currency(currency)?));
+        }
+        if let Some(due_date) = self.due_date {
+            fields.push(format!("DT:{}", due_date.format("%Y%m%d")));
+        }
+        if let Some(message) = &self.message {
+            fields.push(format!("MSG:{}", escape(message, MAX_MESSAGE)));
+        }
+        if let Some(recipient_name) = &self.recipient_name {
+            fields.push(format!("RN:{}", escape(recipient_name, MAX_RECIPIENT)));
+        }
+        for (key, name, value) in [
+            ("X-VS", "Variabilní symbol", &self.variable_symbol),
+            ("X-SS", "Specifický symbol", &self.specific_symbol),
+            ("X-KS", "Konstantní symbol", &self.constant_symbol),
+        ] {
+            if let Some(value) = value {
+                fields.push(format!("{}:{}", key, validate_symbol(name, value)?));
+            }
+        }
+        Ok(format!("{}{}", HEADER, fields.join("*")))
+    }
+
+    /// Načte řetězec z naskenovaného QR kódu. Neznámá pole (RF, NT, CRC32, X-PER...) přeskočí.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let body = text
+            .get(..HEADER.len())
+            .filter(|header| header.eq_ignore_ascii_case(HEADER))
+            .map(|_| &text[HEADER.len()..])
+            .ok_or_else(|| invalid("řetězec nezačíná 'SPD*1.0*'"))?;
+
+        let mut spayd = Spayd::default();
+        let mut account = None;
+        for field in body.split('*').filter(|field| !field.trim().is_empty()) {
+            let (key, value) = field
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("pole '{}' nemá hodnotu", field)))?;
+            let value = unescape(value.trim());
+            match key.trim().to_uppercase().as_str() {
+                "ACC" => account = Some(value),
+                "AM" => {
+                    let amount = value
+                        .parse::<Money>()
+                        .map_err(|_| invalid(format!("neplatná částka '{}'", value)))?;
+                    spayd.amount = Some(check_amount(amount)?);
+                }
+                "CC" => spayd.currency = Some(check_currency(&value)?),
+                "DT" => {
+                    let due_date = NaiveDate::parse_from_str(&value, "%Y%m%d")
+                        .map_err(|_| invalid(format!("neplatné datum splatnosti '{}'", value)))?;
+                    spayd.due_date = Some(due_date);
+                }
+                "MSG" => spayd.message = Some(value),
+                "RN" => spayd.recipient_name = Some(value),
+                "X-VS" => {
+                    spayd.variable_symbol = Some(validate_symbol("Variabilní symbol", &value)?)
+                }
+                "X-SS" => {
+                    spayd.specific_symbol = Some(validate_symbol("Specifický symbol", &value)?)
+                }
+                "X-KS" => {
+                    spayd.constant_symbol = Some(validate_symbol("Konstantní symbol", &value)?)
+                }
+                _ => {}
+            }
+        }
+
+        let account = account.ok_or_else(|| invalid("chybí účet příjemce (ACC)"))?;
+        let (iban, bic) = match account.split_once('+') {
+            Some((iban, bic)) => (iban, Some(bic.trim().to_uppercase())),
+            None => (account.as_str(), None),
+        };
+        spayd.iban = to_iban(iban)?;
+        spayd.bic = bic.filter(|bic| !bic.is_empty());
+        Ok(spayd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_with_percent_survives_round_trip() {
+        let payment = Spayd {
+            iban: "CZ6508000000192000145399".to_string(),
+            message: Some("Sleva 10% kód %2A*".to_string()),
+            ..Default::default()
+        };
+        let text = payment.encode().unwrap();
+        assert!(text.ends_with("*MSG:Sleva 10%25 kód %252A%2A"), "{}", text);
+        assert_eq!(Spayd::parse(&text).unwrap().message, payment.message);
+    }
+}